@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use solpot::cpi::accounts::SubmitGuess;
+use solpot::program::Solpot;
+
+declare_id!("CvDMTm3JsyPhDGJb7dmvkC8AkS5ppamkgEc9xvNnAmAz");
+
+/// Test-only harness standing in for a program that wraps SolPot rounds: it
+/// submits a guess via CPI and reads the `GuessOutcome` that `submit_guess`
+/// returns. Never deployed outside tests.
+#[program]
+pub mod guess_relay {
+    use super::*;
+
+    /// Forwards `guess` to `solpot::submit_guess` and re-emits the outcome
+    /// it returned as a `GuessRelayed` event.
+    pub fn relay_guess(ctx: Context<RelayGuess>, guess: String) -> Result<()> {
+        let accounts = &ctx.accounts;
+        let outcome = solpot::cpi::submit_guess(
+            CpiContext::new(
+                accounts.solpot_program.to_account_info(),
+                SubmitGuess {
+                    game_config: accounts.game_config.to_account_info(),
+                    round: accounts.round.to_account_info(),
+                    player_entry: accounts.player_entry.to_account_info(),
+                    guess_record: accounts.guess_record.to_account_info(),
+                    player_profile: accounts.player_profile.to_account_info(),
+                    blacklist: accounts.blacklist.to_account_info(),
+                    winners: None,
+                    guess_log: accounts.guess_log.to_account_info(),
+                    round_leaderboard: accounts.round_leaderboard.to_account_info(),
+                    jackpot: accounts.jackpot.to_account_info(),
+                    leaderboard: None,
+                    player: accounts.player.to_account_info(),
+                    rent_sponsor: None,
+                    system_program: accounts.system_program.to_account_info(),
+                },
+            ),
+            guess,
+            Vec::new(),
+            Vec::new(),
+            Vec::new(),
+        )?
+        .get();
+
+        emit!(GuessRelayed {
+            player: accounts.player.key(),
+            is_correct: outcome.is_correct,
+            attempt: outcome.attempt,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct GuessRelayed {
+    pub player: Pubkey,
+    pub is_correct: bool,
+    pub attempt: u32,
+}
+
+/// Mirrors `solpot::SubmitGuess` for single-winner rounds without a
+/// leaderboard; SolPot validates every account itself.
+#[derive(Accounts)]
+pub struct RelayGuess<'info> {
+    /// CHECK: Validated by SolPot
+    pub game_config: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by SolPot
+    #[account(mut)]
+    pub round: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by SolPot
+    pub player_entry: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by SolPot
+    #[account(mut)]
+    pub guess_record: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by SolPot
+    #[account(mut)]
+    pub player_profile: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by SolPot
+    pub blacklist: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by SolPot
+    #[account(mut)]
+    pub guess_log: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by SolPot
+    #[account(mut)]
+    pub round_leaderboard: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by SolPot
+    #[account(mut)]
+    pub jackpot: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    pub solpot_program: Program<'info, Solpot>,
+
+    pub system_program: Program<'info, System>,
+}
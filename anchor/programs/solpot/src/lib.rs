@@ -1,8 +1,16 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::hash::hash;
-use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::hash::{hash, hashv};
+use anchor_lang::solana_program::instruction::{
+    get_stack_height, AccountMeta, Instruction, TRANSACTION_LEVEL_STACK_HEIGHT,
+};
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
 use anchor_lang::solana_program::program::invoke;
-use anchor_lang::system_program::{transfer, Transfer};
+use anchor_lang::solana_program::sysvar::slot_hashes;
+use anchor_lang::system_program::{
+    allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer,
+};
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use unicode_normalization::UnicodeNormalization;
 
 /// Metaplex Core program ID (CoREENxT6tW1HoK8ypY1SxRMZTcVPm7R94rH4PZNhX7d)
 /// Decoded from base58 at compile time using the byte literal.
@@ -23,6 +31,8 @@ pub enum SolPotError {
     RoundNotActive,
     #[msg("Round has expired")]
     RoundExpired,
+    #[msg("Round has not started yet")]
+    RoundNotStarted,
     #[msg("Round has not expired yet")]
     RoundNotExpired,
     #[msg("Round already has a winner")]
@@ -55,25 +65,410 @@ pub enum SolPotError {
     NftAlreadyMinted,
     #[msg("Player has already submitted a guess for this round")]
     AlreadyGuessed,
+    #[msg("Entry has already been refunded")]
+    AlreadyRefunded,
+    #[msg("Entries must be refunded before the round can be closed")]
+    RefundsPending,
+    #[msg("Winners account must be provided exactly when multiple winners are allowed")]
+    WinnersAccountMismatch,
+    #[msg("Maximum number of winners reached")]
+    WinnersFull,
+    #[msg("Winner accounts do not match the recorded winners")]
+    WinnerAccountsMismatch,
+    #[msg("Player has reached the guess limit for this round")]
+    GuessLimitReached,
+    #[msg("Max guesses must be at least 1")]
+    InvalidMaxGuesses,
+    #[msg("Player has already guessed the word for this round")]
+    AlreadyWinner,
+    #[msg("Extension must be a positive number of seconds")]
+    InvalidExtension,
+    #[msg("Reveal window must not be negative")]
+    InvalidRevealWindow,
+    #[msg("This round requires commit_guess / reveal_guess")]
+    CommitRevealRequired,
+    #[msg("This round does not use commit-reveal guessing")]
+    CommitRevealDisabled,
+    #[msg("Previous commitment has not been revealed yet")]
+    PendingCommitment,
+    #[msg("No unrevealed commitment for this player")]
+    NoCommitment,
+    #[msg("Reveal window for this commitment has closed")]
+    RevealWindowClosed,
+    #[msg("Revealed guess does not match the commitment")]
+    CommitmentMismatch,
+    #[msg("Merkle proof is invalid")]
+    MerkleProofInvalid,
+    #[msg("Player is not on the round's allowlist")]
+    NotAllowlisted,
+    #[msg("Guess is not in the round's dictionary")]
+    WordNotInDictionary,
+    #[msg("Only the round's winner can take the bonus")]
+    BonusOnlyForWinner,
+    #[msg("Round has no bonus word committed")]
+    BonusNotOffered,
+    #[msg("Round holds less than its recorded pot")]
+    InsufficientDistributable,
+    #[msg("No leaves were added to the answer set")]
+    EmptyAnswerSet,
+    #[msg("Answer set is already being built for another root")]
+    AnswerSetTargetMismatch,
+    #[msg("Round can't be modified once a player has entered")]
+    CannotModifyAfterEntries,
+    #[msg("Answer is fixed by the round's answer commitment or answer set")]
+    AnswerLocked,
+    #[msg("Instruction must be invoked directly, not through CPI")]
+    NestedInvocation,
+    #[msg("Token account mint does not match the game mint")]
+    TokenMintMismatch,
+    #[msg("Token accounts are required for token games")]
+    TokenAccountMissing,
+    #[msg("Not supported for token-denominated rounds")]
+    UnsupportedForTokenRound,
+    #[msg("Entry fee is below the game's minimum")]
+    EntryFeeBelowFloor,
+    #[msg("Round holds nothing beyond its recorded pot")]
+    NoSurplus,
+    #[msg("Tip must be greater than zero")]
+    InvalidTipAmount,
+    #[msg("Bounty must be greater than zero")]
+    InvalidBountyAmount,
+    #[msg("NFT name or URI doesn't match the game's metadata template")]
+    NftMetadataMismatch,
+    #[msg("NFT metadata template prefix is too long")]
+    NftTemplateTooLong,
+    #[msg("Leaderboard account is required while a win cooldown is set")]
+    LeaderboardRequired,
+    #[msg("Account is not a SolPot account of the expected type")]
+    NotMigratable,
+    #[msg("Memo is too long")]
+    MemoTooLong,
+    #[msg("Round pot has reached its cap")]
+    PotCapReached,
+    #[msg("This wallet is blacklisted")]
+    PlayerBlacklisted,
+    #[msg("Blacklist is full")]
+    BlacklistFull,
+    #[msg("Round still holds lamports above rent after distribution")]
+    DistributionIncomplete,
+    #[msg("Answer salt does not incorporate the round's commit slot hash")]
+    FairnessProofInvalid,
+    #[msg("Refundable tips have not all been returned")]
+    TipsPending,
+    #[msg("No authority transfer is pending")]
+    NoPendingAuthority,
+    #[msg("Game is paused")]
+    GamePaused,
+    #[msg("Target round must be a different, open SOL round")]
+    InvalidRolloverTarget,
+    #[msg("Round did not reach its minimum player count")]
+    MinPlayersNotMet,
+    #[msg("Min players must not exceed max players")]
+    InvalidMinPlayers,
+    #[msg("Round has not been settled yet")]
+    RoundNotSettled,
+    #[msg("These round options can't be combined")]
+    IncompatibleRoundOptions,
+    #[msg("Participants account is required for random-fallback rounds")]
+    ParticipantsAccountMismatch,
+    #[msg("Round pays a randomly drawn entrant instead of refunding")]
+    RandomDrawPending,
+    #[msg("Round has no entrants to draw from")]
+    NoParticipants,
+    /// No longer returned (a full log stops logging); kept so the codes of
+    /// later errors don't shift.
+    #[msg("Guess log is full")]
+    GuessLogFull,
+    #[msg("Runner-up can't also be a winner")]
+    DuplicateRecipient,
+    #[msg("Runner-up account is required for this payout")]
+    RunnerUpAccountMissing,
+    #[msg("Player doesn't hold the token required to enter")]
+    EntryGateNotSatisfied,
+    #[msg("Hint URI is too long")]
+    HintUriTooLong,
+    #[msg("Round title is too long")]
+    TitleTooLong,
+    #[msg("Invalid hint positions or characters")]
+    InvalidHint,
+    #[msg("Leaderboard can't shrink below its current entry count")]
+    CannotShrinkBelowCount,
+    #[msg("A round's player limit can only be raised")]
+    CannotReduceBelowCount,
+    #[msg("Player won this round")]
+    PlayerWonRound,
+    #[msg("Claim window must not be negative")]
+    InvalidClaimWindow,
+    #[msg("Winner can still claim the prize")]
+    UnclaimedWindowOpen,
+    #[msg("Collection doesn't match the game's NFT collection")]
+    CollectionMismatch,
+    #[msg("Invalid number of rounds in batch")]
+    InvalidBatchSize,
+    #[msg("Batch accounts don't match the rounds being created")]
+    BatchAccountsMismatch,
+    #[msg("Round has no answer commitment")]
+    NoAnswerCommitment,
+    #[msg("Answer has already been revealed")]
+    AnswerAlreadyRevealed,
+    #[msg("Answer can't be revealed yet or anymore")]
+    AnswerRevealWindowClosed,
+    #[msg("Answer hasn't been revealed yet")]
+    AnswerNotRevealed,
+    #[msg("Round is disputed")]
+    RoundDisputed,
+    #[msg("Game must be paused")]
+    GameNotPaused,
+    #[msg("Max players out of range")]
+    InvalidMaxPlayers,
+    #[msg("Round duration out of range")]
+    InvalidDuration,
+    #[msg("Players can't refer themselves")]
+    SelfReferral,
+    #[msg("Referrer accounts are required when a referrer is given")]
+    ReferrerAccountMissing,
+    #[msg("Account is not the expected PDA")]
+    PdaMismatch,
+    #[msg("Round was cancelled")]
+    RoundCancelled,
+    #[msg("Invalid entry fee curve")]
+    InvalidFeeCurve,
+    #[msg("Amount exceeds accrued fees")]
+    InsufficientAccruedFees,
+    #[msg("You must enter the round first")]
+    NotEntered,
+    #[msg("Guess cooldown must not be negative")]
+    InvalidGuessCooldown,
+    #[msg("Grace period must not be negative")]
+    InvalidGracePeriod,
+    #[msg("Anti-snipe window and cap must not be negative")]
+    InvalidAntiSnipe,
+    #[msg("Charity wallet does not match the round's")]
+    CharityWalletMismatch,
+    #[msg("The last round was just created with this nonce")]
+    DuplicateRoundNonce,
+    #[msg("Round's hint stays sealed until someone enters")]
+    RoundSealed,
+    #[msg("Charity share needs a charity wallet and must leave room for the runner-up")]
+    InvalidCharityShare,
+    #[msg("Wait for the guess cooldown to pass")]
+    GuessCooldown,
+    #[msg("Close every round before closing the game")]
+    OpenRoundsRemain,
+    #[msg("Unknown normalization flags")]
+    InvalidNormalizationFlags,
+    #[msg("Unknown guess kind")]
+    InvalidGuessKind,
+    #[msg("Guess is not valid for the round's guess kind")]
+    InvalidGuessEncoding,
+    #[msg("Every player entry must be reclaimed first")]
+    EntriesNotReclaimed,
+    #[msg("Every tip record must be reclaimed first")]
+    TipsNotReclaimed,
+    #[msg("Win is still within its dispute period")]
+    DisputePeriodActive,
+    #[msg("Answer is too long")]
+    AnswerTooLong,
+    #[msg("Jackpot is empty")]
+    JackpotEmpty,
+    #[msg("Player has no wins on the leaderboard")]
+    LoyaltyNotEarned,
+    #[msg("Vesting cliff must lie within a non-negative duration")]
+    InvalidVestingSchedule,
+    #[msg("Prize vault must be provided exactly for vesting rounds")]
+    PrizeVaultMismatch,
+    #[msg("Nothing has vested since the last claim")]
+    NothingToClaim,
+    #[msg("The vested prize has been fully claimed")]
+    VaultEmpty,
+    #[msg("A season is already running")]
+    SeasonActive,
+    #[msg("No season is running")]
+    SeasonNotActive,
+    #[msg("Season must span at least one round and pay 1 to 10 players")]
+    InvalidSeason,
 }
 
 // ── State ───────────────────────────────────────────────────────────────────
 
+/// Global game settings. When `mint` is set the game is token-denominated:
+/// entry fees and pots are amounts of that mint (still stored in the
+/// `*_lamports` fields) held in per-round vaults.
 #[account]
+#[derive(Default)]
 pub struct GameConfig {
     pub authority: Pubkey,
     pub round_count: u64,
     pub entry_fee_lamports: u64,
     pub fee_basis_points: u16,
     pub bump: u8,
+    /// `Pubkey::default()` for SOL games.
+    pub mint: Pubkey,
+    /// Set by `propose_authority_transfer`; becomes `authority` once it signs
+    /// `accept_authority_transfer`.
+    pub pending_authority: Pubkey,
+    /// Kill switch: blocks new rounds, entries and guesses while set. Payouts,
+    /// refunds and closing keep working so funds are always recoverable.
+    pub paused: bool,
+    /// Share of each pot paid to the round's earliest entrant when they
+    /// didn't win. `fee_basis_points + runner_up_basis_points <= 10000`.
+    pub runner_up_basis_points: u16,
+    /// Metaplex Core collection reward NFTs are minted into.
+    /// `Pubkey::default()` mints standalone assets.
+    pub collection: Pubkey,
+    /// Bounds on `duration_seconds` for new rounds.
+    pub min_duration_seconds: i64,
+    pub max_duration_seconds: i64,
+    /// Share of each referred entry fee paid to the referrer.
+    pub referral_basis_points: u16,
+    /// Rounds created and not yet closed; `close_game` needs this at 0.
+    pub open_rounds: u32,
+    /// Lifetime totals, in lamports or the game mint's base units: entry
+    /// fees paid, prizes paid to winners and runners-up, and house fees.
+    /// Configs created before these counters existed are 24 bytes short and
+    /// don't deserialize until `migrate_game_config` reallocates them.
+    pub total_wagered: u64,
+    pub total_paid_out: u64,
+    pub total_fees: u64,
+    /// Discount on the entry fee for players with at least one win on the
+    /// leaderboard. The pot gets the discounted fee; the house eats the rest.
+    pub loyalty_discount_bps: u16,
+    /// Delay between a win and its payout, giving the authority time to
+    /// `reveal_answer` and observers time to check it.
+    pub dispute_seconds: i64,
+    /// Where house fees go: `sweep_fees` pays out to it and token rounds pay
+    /// their fee to a token account it owns. Starts as the authority.
+    pub fee_destination: Pubkey,
+    /// How far a round's balance may fall short of its recorded pot before
+    /// payouts refuse to go ahead.
+    pub dust_tolerance_lamports: u64,
+    /// Lowest entry fee a round may charge anyone, discounts included.
+    /// Checked whenever a round takes its fee snapshot.
+    pub min_entry_fee: u64,
+    /// Reward NFT metadata template: when set, a round's reward must be
+    /// named `nft_name_prefix` followed by the round id, and its URI must be
+    /// `nft_uri_prefix` followed by the round id. Both empty leaves name and
+    /// URI up to the minter.
+    pub nft_name_prefix: String,
+    pub nft_uri_prefix: String,
+    /// How long after a win a player's correct guesses don't win, going by
+    /// `PlayerProfile::last_win_at` (set when the win is made) and
+    /// `LeaderboardEntry::last_win_at` (set at payout). 0 disables the
+    /// cooldown.
+    pub win_cooldown_seconds: i64,
+    /// Layout this config was last written or migrated with; see
+    /// `migrate_game_config`. 0 for configs older than the field.
+    pub schema_version: u8,
+    /// Leaderboard ranking: `wins * leaderboard_win_weight + whole SOL won
+    /// * leaderboard_winnings_weight` (whole units of 10^9 base units in
+    /// token games). Starts at 1 and 0, i.e. ranking by wins.
+    pub leaderboard_win_weight: u64,
+    pub leaderboard_winnings_weight: u64,
+    /// `creation_nonce` and creation time of the latest `create_round` or
+    /// `batch_create_rounds`, for their retry guard.
+    pub last_round_nonce: [u8; 16],
+    pub last_round_created_at: i64,
 }
 
 impl GameConfig {
     pub const SEED: &'static [u8] = b"game_config";
-    pub const SIZE: usize = 8 + 32 + 8 + 8 + 2 + 1;
+    pub const SIZE: usize = 8 + 32 + 8 + 8 + 2 + 1 + 32 + 32 + 1 + 2 + 32 + 8 + 8 + 2 + 4 + 8 + 8 + 8 + 2 + 8 + 32 + 8 + 8
+        + 4 + Self::MAX_NFT_NAME_PREFIX_LEN + 4 + Self::MAX_NFT_URI_PREFIX_LEN + 8 + 1
+        + 8 + 8 + 16 + 8;
+    pub const MAX_REFERRAL_BASIS_POINTS: u16 = 5000;
+    pub const MAX_LOYALTY_DISCOUNT_BPS: u16 = 5000;
+    pub const MAX_NFT_NAME_PREFIX_LEN: usize = 32;
+    pub const MAX_NFT_URI_PREFIX_LEN: usize = 160;
+    pub const SCHEMA_VERSION: u8 = 3;
+    pub const DEFAULT_MIN_DURATION_SECONDS: i64 = 60;
+    pub const DEFAULT_MAX_DURATION_SECONDS: i64 = 30 * 24 * 60 * 60;
+
+    /// Gives the fields a config written at `old_schema_version` lacks the
+    /// values `initialize_game` would, and stamps the current version; see
+    /// `migrate_game_config`.
+    pub fn apply_migration_defaults(&mut self, old_schema_version: u8) {
+        if self.fee_destination == Pubkey::default() {
+            self.fee_destination = self.authority;
+        }
+        if old_schema_version < 2 {
+            self.leaderboard_win_weight = 1;
+            self.leaderboard_winnings_weight = 0;
+            // Zero bounds would refuse every new round.
+            if self.max_duration_seconds == 0 {
+                self.min_duration_seconds = Self::DEFAULT_MIN_DURATION_SECONDS;
+                self.max_duration_seconds = Self::DEFAULT_MAX_DURATION_SECONDS;
+            }
+        }
+        self.schema_version = Self::SCHEMA_VERSION;
+    }
+
+    pub fn validate_round_bounds(&self, max_players: u32, duration_seconds: i64) -> Result<()> {
+        require!(
+            (1..=Round::MAX_ALLOWED_PLAYERS).contains(&max_players),
+            SolPotError::InvalidMaxPlayers
+        );
+        require!(
+            (self.min_duration_seconds..=self.max_duration_seconds).contains(&duration_seconds),
+            SolPotError::InvalidDuration
+        );
+        Ok(())
+    }
+
+    /// Retry guard for round creation: fails if the latest round was created
+    /// with the same non-zero `nonce` less than
+    /// `Round::NONCE_WINDOW_SECONDS` before `now`, i.e. the call is a
+    /// resubmission of one that already landed.
+    pub fn require_fresh_round_nonce(&self, nonce: &[u8; 16], now: i64) -> Result<()> {
+        let window_ends_at = self
+            .last_round_created_at
+            .saturating_add(Round::NONCE_WINDOW_SECONDS);
+        require!(
+            *nonce == [0u8; 16] || *nonce != self.last_round_nonce || now >= window_ends_at,
+            SolPotError::DuplicateRoundNonce
+        );
+        Ok(())
+    }
+
+    /// Leaderboard score for `entry` under the current weights.
+    pub fn leaderboard_score(&self, entry: &LeaderboardEntry) -> u64 {
+        let whole_sol = entry.total_winnings / LAMPORTS_PER_SOL;
+        (entry.wins as u64)
+            .saturating_mul(self.leaderboard_win_weight)
+            .saturating_add(whole_sol.saturating_mul(self.leaderboard_winnings_weight))
+    }
+
+    /// Fails with `NftMetadataMismatch` unless `name` and `uri` are what the
+    /// metadata template gives for `round_id`.
+    pub fn require_reward_metadata(&self, round_id: u64, name: &str, uri: &str) -> Result<()> {
+        if self.nft_name_prefix.is_empty() && self.nft_uri_prefix.is_empty() {
+            return Ok(());
+        }
+        require!(
+            name == format!("{}{}", self.nft_name_prefix, round_id)
+                && uri == format!("{}{}", self.nft_uri_prefix, round_id),
+            SolPotError::NftMetadataMismatch
+        );
+        Ok(())
+    }
+
+    /// Fails with `EntryFeeBelowFloor` if a round charging `entry_fee` would
+    /// let a player in for less than `min_entry_fee` once the loyalty
+    /// discount comes off.
+    pub fn require_entry_fee_floor(&self, entry_fee: u64) -> Result<()> {
+        let max_discount = entry_fee
+            .checked_mul(self.loyalty_discount_bps as u64)
+            .and_then(|v| v.checked_div(10000))
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+        require!(
+            entry_fee - max_discount >= self.min_entry_fee,
+            SolPotError::EntryFeeBelowFloor
+        );
+        Ok(())
+    }
 }
 
 #[account]
+#[derive(Default)]
 pub struct Round {
     pub id: u64,
     pub game_config: Pubkey,
@@ -88,13 +483,495 @@ pub struct Round {
     pub max_players: u32,
     pub created_at: i64,
     pub expires_at: i64,
+    /// Snapshot of `game_config.entry_fee_lamports` at creation; later config
+    /// updates don't affect existing rounds. The base of the fee curve.
     pub entry_fee_lamports: u64,
     pub bump: u8,
+    /// When set, every correct guess before expiry is recorded in the round's
+    /// `Winners` account and the pot is split evenly between them.
+    pub allow_multiple_winners: bool,
+    pub max_guesses: u32,
+    /// Charged on every guess and added to the pot. Kept by the house if the
+    /// round ends unsolved.
+    pub guess_fee_lamports: u64,
+    pub refunded_count: u32,
+    /// Non-zero enables commit-reveal guessing: each commitment must be
+    /// revealed within this many seconds of being made.
+    pub reveal_window_seconds: i64,
+    /// Merkle root over the hashes of every accepted answer. When zeroed only
+    /// `word_hash` is accepted.
+    pub answers_merkle_root: [u8; 32],
+    /// Snapshot of `game_config.mint`; `Pubkey::default()` for SOL rounds.
+    pub mint: Pubkey,
+    /// Round-owned token account holding the pot of token rounds.
+    pub vault: Pubkey,
+    /// Rounds that end with fewer players are refunded even if won.
+    pub min_players: u32,
+    /// Unsolved rounds pay a randomly drawn entrant instead of refunding.
+    /// Entrants are tracked in the round's `Participants` account.
+    pub random_fallback: bool,
+    /// Earliest entrant, eligible for the runner-up share. Entered at
+    /// `first_entered_at`.
+    pub first_entrant: Pubkey,
+    /// Entrants must hold a nonzero balance of this mint.
+    /// `Pubkey::default()` for ungated rounds.
+    pub gate_mint: Pubkey,
+    /// Length of the answer, shown to players as a hint. 0 if undisclosed.
+    pub word_length: u8,
+    /// Off-chain clue art for the round.
+    pub hint_uri: String,
+    /// How long after guessing ends the winner has to be paid before the
+    /// authority may reclaim the prize. 0 means the prize never lapses.
+    pub claim_window_seconds: i64,
+    /// `hash(answer || salt)`, opened by `reveal_answer` to prove the round
+    /// was solvable. All zeroes when the round has no commitment.
+    pub answer_commitment: [u8; 32],
+    pub answer_revealed: bool,
+    /// Set by `mark_disputed` when an unsolved round's answer was never
+    /// revealed; sends the round down the refund path.
+    pub disputed: bool,
+    /// Set by `cancel_round`; every entry is refunded right away.
+    pub cancelled: bool,
+    /// Dynamic pricing: each entry raises the fee by this much, up to
+    /// `max_entry_fee_lamports`. 0 keeps a flat fee.
+    pub fee_increment_lamports: u64,
+    pub max_entry_fee_lamports: u64,
+    /// Minimum delay between two guesses by the same player. 0 disables it.
+    pub guess_cooldown_seconds: i64,
+    /// `Round::NORMALIZE_*` bits applied to guesses before hashing; see
+    /// `normalize_guess`.
+    pub normalization_flags: u8,
+    /// Bumped by `restart_round`. Part of the `PlayerEntry` and
+    /// `GuessRecord` seeds, so entries from earlier runs never collide.
+    pub generation: u32,
+    /// Emit `PotMilestone` each time the pot passes a multiple of this.
+    /// 0 disables milestones.
+    pub milestone_lamports: u64,
+    /// Non-zero pays the winner through the round's `PrizeVault`, unlocking
+    /// linearly over this many seconds after `vesting_cliff_seconds`.
+    pub vesting_duration_seconds: i64,
+    pub vesting_cliff_seconds: i64,
+    /// Delegate allowed to extend, cancel and close this round alongside
+    /// the game authority; `Pubkey::default()` when there is none. Set with
+    /// `set_round_authority`. The game authority itself is never stored
+    /// here, so a transferred authority loses its rounds along with the game.
+    pub round_authority: Pubkey,
+    /// When the winning guess was made (committed, for commit-reveal
+    /// rounds). A drawn winner settles the round as it expired, so the draw
+    /// records `expires_at`.
+    pub won_at: i64,
+    /// Plaintext answer published by `reveal_answer`; empty until then.
+    pub revealed_answer: String,
+    /// Merkle root over `hash(player)` of every wallet invited to the round.
+    /// When set, only those wallets may enter and guess; zeroed means open.
+    pub allowlist_root: [u8; 32],
+    /// Part of the pot put up by the authority rather than players. Returned
+    /// to the authority, not refunded, if the round goes unsolved.
+    pub seeded_lamports: u64,
+    /// Merkle root over the hashes of every word accepted as a guess. When
+    /// set, other guesses are turned away without costing an attempt.
+    pub dictionary_root: [u8; 32],
+    /// Display name for the round; see `update_round_metadata`.
+    pub title: String,
+    /// Answer to the double-or-nothing bonus the winner may take with
+    /// `spawn_bonus_round`. Zeroed when no bonus is offered.
+    pub bonus_word_hash: [u8; 32],
+    /// Single-player bonus round spawned from a win. Its pot is the stake
+    /// plus the house's matching amount, paid out without a fee.
+    pub is_bonus: bool,
+    /// `PlayerEntry` accounts not yet reclaimed. `close_round` and
+    /// `restart_round` need this back at zero.
+    pub live_entries: u32,
+    /// When `first_entrant` entered; 0 until someone does.
+    pub first_entered_at: i64,
+    /// Guesses settled in this round, right or wrong.
+    pub total_guesses: u32,
+    /// Of `total_guesses`, how many were correct. More than one only in
+    /// multi-winner rounds.
+    pub correct_guesses: u32,
+    /// Lamports spectators added to the pot through `tip_round`.
+    pub total_tips: u64,
+    /// Whether tips go back to their tippers, pro rata, when the round ends
+    /// without a winner. Otherwise they're forfeited with the rest of the
+    /// leftover pot.
+    pub refund_tips: bool,
+    /// Of `total_tips`, the amount whose tippers have been through
+    /// `reclaim_tip` on the refund path.
+    pub refunded_tips: u64,
+    /// House cut for this round in place of `game_config.fee_basis_points`,
+    /// e.g. a smaller one for harder words.
+    pub fee_basis_points_override: Option<u16>,
+    /// Layout this round was created or migrated with; see `migrate_round`.
+    /// 0 for rounds older than the field.
+    pub schema_version: u8,
+    /// Most the pot may ever hold: entries, guess fees, tips, bounties,
+    /// rollovers, the jackpot and reconciled lamports are all refused past
+    /// it. 0 means no cap.
+    pub max_pot_lamports: u64,
+    /// Future slot whose hash is folded into the `answer_commitment` salt,
+    /// proving the answer was only fixed once that slot was produced. 0
+    /// when the round has no fairness proof.
+    pub commit_slot: u64,
+    /// Hash of `commit_slot`, read from SlotHashes by `commit_answer`.
+    pub commit_slot_hash: [u8; 32],
+    /// `Round::GUESS_KIND_*`: how guesses are canonicalized before hashing,
+    /// and so what input the frontend collects. `reveal_answer` still takes
+    /// the answer as a string, so it only suits answers that are valid UTF-8.
+    pub guess_kind: u8,
+    /// How long after `expires_at` guesses (and wins) are still accepted.
+    /// Entry always closes at `expires_at`.
+    pub grace_seconds: i64,
+    /// An entry this close to `expires_at` pushes it back by as much; see
+    /// `apply_anti_snipe`. 0 disables anti-sniping.
+    pub anti_snipe_seconds: i64,
+    /// Cap on the total anti-snipe extension, so a round can't run forever.
+    pub max_anti_snipe_seconds: i64,
+    /// How far anti-sniping has pushed back `expires_at` so far.
+    pub anti_snipe_extended_seconds: i64,
+    /// Fundraiser round: no house fee, and `charity_basis_points` of the
+    /// pot goes to `charity_wallet` when it is paid out. SOL rounds only.
+    pub charity_mode: bool,
+    pub charity_wallet: Pubkey,
+    pub charity_basis_points: u16,
+    /// Client-chosen idempotency key passed to `create_round` (or shared by a
+    /// `batch_create_rounds` batch); zero if none.
+    pub creation_nonce: [u8; 16],
+    /// Surprise drop: the hint can't be published until the first entry
+    /// unlocks it for everyone; see `is_sealed`.
+    pub sealed: bool,
+    /// When the round opens for entries and guesses; `created_at` unless
+    /// `create_round` scheduled it for later. `expires_at` counts from here.
+    pub starts_at: i64,
+    /// Part of the pot the authority added with `add_bounty` after
+    /// creation. Handled like `seeded_lamports`: returned to the authority,
+    /// not refunded, if the round goes unsolved.
+    pub bounty_lamports: u64,
+    /// `TipRecord` accounts not yet reclaimed. Like `live_entries`, this
+    /// must be back at zero before the round is closed or restarted.
+    pub live_tips: u32,
+}
+
+/// What `submit_guess` (and its variants) return as return data, so a
+/// program calling in via CPI can branch on the outcome in the same
+/// transaction. Borsh-encoded, 6 bytes: `is_correct` (1 byte, 0 or 1),
+/// `attempt` (u32 LE, 1-based) and `win_cooldown_active` (1 byte, set when
+/// a correct guess doesn't count as a win).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct GuessOutcome {
+    pub is_correct: bool,
+    pub attempt: u32,
+    pub win_cooldown_active: bool,
+}
+
+/// Lifecycle stage of a round as seen by clients; see `Round::phase`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RoundPhase {
+    /// Not open yet (`starts_at` is in the future).
+    Pending,
+    /// Taking entries and guesses.
+    Active,
+    /// Past expiry without a settled winner: awaiting reveals, a random
+    /// draw, refunds or closing.
+    Expired,
+    /// Has its winner(s); the pot hasn't been paid out yet.
+    Won,
+    /// Pot paid out, reclaimed or rolled over.
+    Distributed,
+    /// Pulled by the authority; entries are being refunded.
+    Cancelled,
+    /// Settled with every entry reclaimed; only `close_round` or
+    /// `restart_round` is left.
+    Closed,
 }
 
 impl Round {
     pub const SEED: &'static [u8] = b"round";
-    pub const SIZE: usize = 8 + 8 + 32 + 32 + 1 + 32 + 1 + 8 + 1 + 1 + 4 + 4 + 8 + 8 + 8 + 1;
+    pub const VAULT_SEED: &'static [u8] = b"vault";
+    pub const SIZE: usize = 8 + 8 + 32 + 32 + 1 + 32 + 1 + 8 + 1 + 1 + 4 + 4 + 8 + 8 + 8 + 1 + 1 + 4
+        + 8 + 4 + 8 + 32 + 32 + 32 + 4 + 1 + 32 + 32 + 1 + 4 + Self::MAX_HINT_URI_LEN + 8
+        + 32 + 1 + 1 + 1 + 8 + 8 + 8 + 1 + 4 + 8 + 8 + 8 + 32 + 8
+        + 4 + Self::MAX_ANSWER_LEN + 32 + 8 + 32
+        + 4 + 32 + 1 + 4 + 8 + 4 + 4 + 8 + 1 + 8 + 1 + 2 + 1 + 8 + 8 + 32 + 1 + 8
+        + 8 + 8 + 8 + 1 + 32 + 2 + 16 + 1 + 8 + 8 + 4;
+    pub const MAX_MERKLE_PROOF_LEN: usize = 16;
+    pub const MAX_HINT_URI_LEN: usize = 200;
+    pub const MAX_ANSWER_LEN: usize = 64;
+    pub const MAX_TITLE_LEN: usize = 64;
+    pub const SCHEMA_VERSION: u8 = 11;
+    /// How long the winner has to take their one bonus guess.
+    pub const BONUS_DURATION_SECONDS: i64 = 600;
+    pub const MAX_ALLOWED_PLAYERS: u32 = 10_000;
+    /// How long a `create_round` nonce blocks an identical one.
+    pub const NONCE_WINDOW_SECONDS: i64 = 600;
+    /// Most rounds `close_expired_rounds` takes in one transaction.
+    pub const MAX_CLOSE_BATCH_LEN: usize = 8;
+    pub const NORMALIZE_TRIM: u8 = 1 << 0;
+    pub const NORMALIZE_COLLAPSE_WHITESPACE: u8 = 1 << 1;
+    pub const NORMALIZE_NFKC: u8 = 1 << 2;
+    pub const NORMALIZE_ALL: u8 =
+        Self::NORMALIZE_TRIM | Self::NORMALIZE_COLLAPSE_WHITESPACE | Self::NORMALIZE_NFKC;
+    /// UTF-8 text, hashed after `normalize_guess`.
+    pub const GUESS_KIND_STRING: u8 = 0;
+    /// Raw bytes, hashed as is.
+    pub const GUESS_KIND_BYTES: u8 = 1;
+    /// A little-endian unsigned integer of up to 8 significant bytes, hashed
+    /// as a u64.
+    pub const GUESS_KIND_NUMBER: u8 = 2;
+    /// A 32-byte hash of the answer computed by the client, hashed once more,
+    /// so `word_hash` is `hash(hash(answer))`; see `submit_guess_hashed`.
+    pub const GUESS_KIND_HASHED: u8 = 3;
+    /// How long after guessing ends the authority has to reveal a committed
+    /// answer before the round can be disputed.
+    pub const ANSWER_REVEAL_GRACE_SECONDS: i64 = 24 * 60 * 60;
+
+    /// Account size for a round whose `title` is `title_len` bytes long.
+    /// `SIZE` leaves the title empty; `update_round_metadata` reallocates.
+    pub const fn space_with_title(title_len: usize) -> usize {
+        Self::SIZE + title_len
+    }
+
+    pub fn is_token_round(&self) -> bool {
+        self.mint != Pubkey::default()
+    }
+
+    /// Whether `key` may operate this round: the game authority or the
+    /// round's delegate.
+    pub fn is_operator(&self, game_config: &GameConfig, key: &Pubkey) -> bool {
+        *key == game_config.authority
+            || (self.round_authority != Pubkey::default() && *key == self.round_authority)
+    }
+
+    /// Guessing closes at `guesses_close_at`, so a recorded win can never
+    /// be later than that; `distribute_pot` refuses to pay one that is.
+    pub fn won_before_expiry(&self) -> bool {
+        self.won_at <= self.guesses_close_at()
+    }
+
+    /// When `submit_guess` and `commit_guess` stop taking guesses:
+    /// `expires_at` plus the round's grace period.
+    pub fn guesses_close_at(&self) -> i64 {
+        self.expires_at.saturating_add(self.grace_seconds)
+    }
+
+    /// Anti-sniping, for an entry made at `now` (before `expires_at`): one
+    /// within the last `anti_snipe_seconds` pushes expiry back by that much,
+    /// until the round has been extended by `max_anti_snipe_seconds` in
+    /// total; the last push may be shorter. Returns the old expiry if it
+    /// moved.
+    pub fn apply_anti_snipe(&mut self, now: i64) -> Option<i64> {
+        let extension = std::cmp::min(
+            self.anti_snipe_seconds,
+            self.max_anti_snipe_seconds
+                .saturating_sub(self.anti_snipe_extended_seconds),
+        );
+        if extension <= 0 || now < self.expires_at.saturating_sub(self.anti_snipe_seconds) {
+            return None;
+        }
+        let old_expires_at = self.expires_at;
+        self.expires_at = self.expires_at.saturating_add(extension);
+        self.anti_snipe_extended_seconds += extension;
+        Some(old_expires_at)
+    }
+
+    pub fn is_vesting(&self) -> bool {
+        self.vesting_duration_seconds > 0
+    }
+
+    pub fn is_gated(&self) -> bool {
+        self.gate_mint != Pubkey::default()
+    }
+
+    pub fn is_underfilled(&self) -> bool {
+        self.player_count < self.min_players
+    }
+
+    /// Whether entries are being handed back through `refund_losers`.
+    pub fn is_refund_path(&self, now: i64) -> bool {
+        let unsolved = !self.has_winner
+            && (!self.random_fallback || self.player_count == 0 || self.disputed);
+        self.cancelled
+            || (now >= self.guessing_ends_at() && (unsolved || self.is_underfilled()))
+    }
+
+    /// Whether refundable tips still have to be handed back before the
+    /// leftover pot can be settled.
+    pub fn tips_pending(&self) -> bool {
+        self.refund_tips && self.refunded_tips < self.total_tips
+    }
+
+    /// Whether `close_round` would take the round at `now`: paid out, rolled
+    /// over or refunded in full, with every entry and tip record reclaimed.
+    pub fn is_closable(&self, now: i64) -> bool {
+        if self.live_entries != 0 || self.live_tips != 0 {
+            return false;
+        }
+        if self.is_refund_path(now) {
+            self.pot_distributed
+                || (self.refunded_count == self.player_count && !self.tips_pending())
+        } else {
+            self.has_winner && self.pot_distributed
+        }
+    }
+
+    /// Single-winner commit-reveal rounds go to the earliest commitment, not
+    /// the earliest reveal: whether a correct reveal of one made at
+    /// `committed_at` takes the win from the current winner, whose
+    /// `won_at` is their commitment time. Never once the pot is paid out.
+    pub fn commitment_beats_winner(&self, committed_at: i64) -> bool {
+        self.has_winner && !self.pot_distributed && committed_at < self.won_at
+    }
+
+    /// Whether the round's hint is still withheld. Account data is public,
+    /// so a sealed round holds no hint at all until its first entry; only
+    /// then can `update_round_metadata` and `reveal_hint` publish one.
+    pub fn is_sealed(&self) -> bool {
+        self.sealed && self.player_count == 0
+    }
+
+    /// House cut taken when this round pays out, given the game's default.
+    /// Charity rounds take none.
+    pub fn fee_basis_points(&self, game_fee_basis_points: u16) -> u16 {
+        if self.charity_mode {
+            0
+        } else {
+            self.fee_basis_points_override
+                .unwrap_or(game_fee_basis_points)
+        }
+    }
+
+    /// Gives the fields a round written at `old_schema_version` lacks values
+    /// that keep it playable as it was, and stamps the current version; see
+    /// `migrate_round`. Other new fields are neutral when zero.
+    pub fn apply_migration_defaults(&mut self, old_schema_version: u8) {
+        if old_schema_version == 0 && self.max_guesses == 0 {
+            // Rounds from before the guess limit had none.
+            self.max_guesses = u32::MAX;
+        }
+        self.schema_version = Self::SCHEMA_VERSION;
+    }
+
+    pub fn has_answer_commitment(&self) -> bool {
+        self.answer_commitment != [0u8; 32]
+    }
+
+    /// Whether `salt` is `hash(commit_slot_hash || salt_nonce)` cut to 16
+    /// bytes. Always true for rounds without a `commit_slot`.
+    pub fn salt_binds_commit_slot(&self, salt: &[u8; 16], salt_nonce: &[u8; 16]) -> bool {
+        if self.commit_slot == 0 {
+            return true;
+        }
+        let expected = hashv(&[self.commit_slot_hash.as_ref(), salt_nonce.as_ref()]).to_bytes();
+        expected[..16] == salt[..]
+    }
+
+    /// Committed answers must be revealed before unsolved pots can be paid
+    /// out to anyone other than the entrants.
+    pub fn answer_settled(&self) -> bool {
+        !self.has_answer_commitment() || self.answer_revealed
+    }
+
+    /// Whether adding `amount` keeps the pot within `max_pot_lamports`.
+    pub fn fits_pot_cap(&self, amount: u64) -> bool {
+        self.max_pot_lamports == 0
+            || self
+                .pot_lamports
+                .checked_add(amount)
+                .is_some_and(|pot| pot <= self.max_pot_lamports)
+    }
+
+    /// Adds `amount` to the pot, emitting `PotMilestone` for the highest
+    /// multiple of `milestone_lamports` crossed on the way.
+    pub fn add_to_pot(&mut self, amount: u64) -> Result<()> {
+        let old_pot = self.pot_lamports;
+        self.pot_lamports = old_pot
+            .checked_add(amount)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+
+        if self.milestone_lamports > 0 {
+            let reached = self.pot_lamports / self.milestone_lamports;
+            if reached > old_pot / self.milestone_lamports {
+                emit!(PotMilestone {
+                    round_id: self.id,
+                    milestone_lamports: reached * self.milestone_lamports,
+                    pot_lamports: self.pot_lamports,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Fee the next entrant pays: `entry_fee + fee_increment * player_count`,
+    /// capped at `max_entry_fee_lamports`.
+    pub fn current_entry_fee(&self) -> u64 {
+        if self.fee_increment_lamports == 0 {
+            return self.entry_fee_lamports;
+        }
+        self.fee_increment_lamports
+            .saturating_mul(self.player_count as u64)
+            .saturating_add(self.entry_fee_lamports)
+            .min(self.max_entry_fee_lamports)
+    }
+
+    /// Multi-winner rounds only count as won once guessing has ended.
+    pub fn phase(&self, now: i64) -> RoundPhase {
+        if self.is_closable(now) {
+            RoundPhase::Closed
+        } else if self.cancelled {
+            RoundPhase::Cancelled
+        } else if self.pot_distributed {
+            RoundPhase::Distributed
+        } else if self.has_winner
+            && (!self.allow_multiple_winners || now >= self.guessing_ends_at())
+        {
+            RoundPhase::Won
+        } else if !self.has_started(now) {
+            RoundPhase::Pending
+        } else if self.is_active && now < self.guesses_close_at() {
+            RoundPhase::Active
+        } else {
+            RoundPhase::Expired
+        }
+    }
+
+    /// Lamports in the pot that came from the authority (the creation seed
+    /// plus any bounties) rather than from players or tippers.
+    pub fn authority_funded_lamports(&self) -> u64 {
+        self.seeded_lamports.saturating_add(self.bounty_lamports)
+    }
+
+    /// Whether the round has reached its `starts_at`. Rounds from before
+    /// the field open at `created_at`.
+    pub fn has_started(&self, now: i64) -> bool {
+        now >= self.starts_at.max(self.created_at)
+    }
+
+    pub fn answer_reveal_deadline(&self) -> i64 {
+        self.guessing_ends_at()
+            .saturating_add(Self::ANSWER_REVEAL_GRACE_SECONDS)
+    }
+
+    /// Last moment a correct guess can still land. Commitments made before
+    /// guessing closes may be revealed for up to `reveal_window_seconds`
+    /// afterwards.
+    pub fn guessing_ends_at(&self) -> i64 {
+        self.guesses_close_at()
+            .saturating_add(self.reveal_window_seconds)
+    }
+
+    /// Whether the winner is settled. Until every commitment older than a
+    /// single winner's could have been revealed, the win may still move to
+    /// an earlier committer.
+    pub fn win_is_final(&self, now: i64) -> bool {
+        self.allow_multiple_winners
+            || self.reveal_window_seconds == 0
+            || now >= self.won_at.saturating_add(self.reveal_window_seconds)
+    }
+
+    /// After this, an undistributed prize can be reclaimed by the authority.
+    pub fn claim_deadline(&self) -> Option<i64> {
+        (self.claim_window_seconds > 0)
+            .then(|| self.guessing_ends_at().saturating_add(self.claim_window_seconds))
+    }
 }
 
 #[account]
@@ -102,509 +979,8189 @@ pub struct PlayerEntry {
     pub player: Pubkey,
     pub round: Pubkey,
     pub entered_at: i64,
+    pub refunded: bool,
     pub bump: u8,
+    /// What this entry added to the pot, and what a refund returns. Less
+    /// than the entry fee when a referral cut was paid out.
+    pub amount_paid: u64,
 }
 
 impl PlayerEntry {
     pub const SEED: &'static [u8] = b"player_entry";
+    pub const SIZE: usize = 8 + 32 + 32 + 8 + 1 + 1 + 8;
+}
+
+/// What one spectator has tipped into a round run. Tips don't make the
+/// tipper a player.
+/// Seeds: ["tip", round, round.generation, tipper]
+#[account]
+pub struct TipRecord {
+    pub round: Pubkey,
+    pub tipper: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+impl TipRecord {
+    pub const SEED: &'static [u8] = b"tip";
     pub const SIZE: usize = 8 + 32 + 32 + 8 + 1;
 }
 
-/// Tiny PDA whose existence proves a player already submitted a guess.
+/// Tracks how many guesses a player has submitted in a round, plus their
+/// latest commitment in commit-reveal rounds.
 /// Seeds: ["guess_record", round, player]
 #[account]
 pub struct GuessRecord {
+    pub count: u32,
+    /// hash(guess || salt || player)
+    pub commitment: [u8; 32],
+    pub revealed: bool,
+    pub committed_at: i64,
     pub bump: u8,
+    /// When the player last spent an attempt, for `guess_cooldown_seconds`.
+    pub last_guess_at: i64,
 }
 
 impl GuessRecord {
     pub const SEED: &'static [u8] = b"guess_record";
-    pub const SIZE: usize = 8 + 1;
+    pub const SIZE: usize = 8 + 4 + 32 + 1 + 8 + 1 + 8;
 }
 
+/// Correct guessers of a multi-winner round, in the order they solved it.
+/// `players[0]` always matches `round.winner`.
+/// Seeds: ["winners", round]
 #[account]
-pub struct Leaderboard {
-    pub game_config: Pubkey,
-    pub entries: Vec<LeaderboardEntry>,
+pub struct Winners {
+    pub round: Pubkey,
+    pub players: Vec<Pubkey>,
     pub bump: u8,
 }
 
-impl Leaderboard {
-    pub const SEED: &'static [u8] = b"leaderboard";
-    pub const MAX_ENTRIES: usize = 50;
-    pub const SIZE: usize = 8 + 32 + 4 + (Self::MAX_ENTRIES * LeaderboardEntry::SIZE) + 1;
+impl Winners {
+    pub const SEED: &'static [u8] = b"winners";
+    pub const MAX_WINNERS: usize = 32;
+    pub const SIZE: usize = 8 + 32 + 4 + (Self::MAX_WINNERS * 32) + 1;
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
-pub struct LeaderboardEntry {
-    pub player: Pubkey,
-    pub wins: u32,
-    pub total_winnings: u64,
+/// Everyone who entered a random-fallback round, in entry order.
+/// Seeds: ["participants", round]
+#[account]
+pub struct Participants {
+    pub round: Pubkey,
+    pub players: Vec<Pubkey>,
+    pub bump: u8,
 }
 
-impl LeaderboardEntry {
-    pub const SIZE: usize = 32 + 4 + 8;
+impl Participants {
+    pub const SEED: &'static [u8] = b"participants";
+    pub const MAX_PLAYERS: usize = 100;
+    pub const SIZE: usize = 8 + 32 + 4 + (Self::MAX_PLAYERS * 32) + 1;
 }
 
-// ── Events ──────────────────────────────────────────────────────────────────
+/// Hashes of wrong guesses made in a round, so disputes can be checked
+/// against on-chain state without revealing the plaintext guesses.
+/// Seeds: ["guess_log", round]
+#[account]
+#[derive(Default)]
+pub struct GuessLog {
+    pub round: Pubkey,
+    /// When full, overwrite the oldest entry instead of keeping the first
+    /// `MAX_ENTRIES` and logging nothing further. Guessing goes on either way.
+    pub overwrite_oldest: bool,
+    /// Index of the oldest entry once the log has wrapped.
+    pub head: u32,
+    pub entries: Vec<GuessLogEntry>,
+    pub bump: u8,
+}
 
-#[event]
-pub struct RoundCreated {
-    pub round_id: u64,
-    pub entry_fee_lamports: u64,
-    pub expires_at: i64,
-    pub max_players: u32,
+impl GuessLog {
+    pub const SEED: &'static [u8] = b"guess_log";
+    pub const MAX_ENTRIES: usize = 64;
+    pub const SIZE: usize = 8 + 32 + 1 + 4 + 4 + (Self::MAX_ENTRIES * GuessLogEntry::SIZE) + 1;
+
+    /// Records `entry`. A full log that doesn't overwrite drops it rather
+    /// than failing the guess, so a busy round never locks up.
+    pub fn append(&mut self, entry: GuessLogEntry) {
+        if self.entries.len() < Self::MAX_ENTRIES {
+            self.entries.push(entry);
+            return;
+        }
+        if !self.overwrite_oldest {
+            return;
+        }
+
+        let head = self.head as usize;
+        self.entries[head] = entry;
+        self.head = ((head + 1) % Self::MAX_ENTRIES) as u32;
+    }
 }
 
-#[event]
-pub struct PlayerEntered {
-    pub round_id: u64,
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct GuessLogEntry {
     pub player: Pubkey,
-    pub pot_lamports: u64,
-    pub player_count: u32,
+    pub guess_hash: [u8; 32],
+    pub timestamp: i64,
 }
 
-#[event]
-pub struct GuessResult {
-    pub round_id: u64,
-    pub player: Pubkey,
-    pub is_correct: bool,
+impl GuessLogEntry {
+    pub const SIZE: usize = 32 + 32 + 8;
 }
 
-#[event]
-pub struct PotDistributed {
-    pub round_id: u64,
-    pub winner: Pubkey,
-    pub winner_amount: u64,
-    pub fee_amount: u64,
+/// Letters of the answer the authority has unmasked so far.
+/// Seeds: ["hint", round]
+#[account]
+pub struct Hint {
+    pub round: Pubkey,
+    pub revealed_chars: Vec<RevealedChar>,
+    pub bump: u8,
 }
 
-#[event]
-pub struct NftMinted {
-    pub round_id: u64,
-    pub winner: Pubkey,
-    pub mint: Pubkey,
+impl Hint {
+    pub const SEED: &'static [u8] = b"hint";
+    pub const MAX_REVEALED: usize = 32;
+    pub const SIZE: usize = 8 + 32 + 4 + (Self::MAX_REVEALED * RevealedChar::SIZE) + 1;
 }
 
-#[event]
-pub struct RoundClosed {
-    pub round_id: u64,
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct RevealedChar {
+    pub position: u8,
+    pub char: u8,
 }
 
-// ── Program ─────────────────────────────────────────────────────────────────
+impl RevealedChar {
+    pub const SIZE: usize = 1 + 1;
+}
 
-#[program]
-pub mod solpot {
-    use super::*;
+/// Round-scoped scoreboard: everyone who entered, how many guesses they've
+/// made and how many it took the winners. Holds at most
+/// `min(max_players, MAX_ENTRIES)` entrants; later entrants aren't tracked.
+/// Seeds: ["round_leaderboard", round]
+#[account]
+#[derive(Default)]
+pub struct RoundLeaderboard {
+    pub round: Pubkey,
+    pub capacity: u32,
+    pub entries: Vec<RoundLeaderboardEntry>,
+    pub bump: u8,
+}
 
-    pub fn initialize_game(
-        ctx: Context<InitializeGame>,
-        entry_fee_lamports: u64,
-        fee_basis_points: u16,
-    ) -> Result<()> {
-        require!(
-            fee_basis_points <= 1000,
-            SolPotError::InvalidFeeBasisPoints
-        );
+impl RoundLeaderboard {
+    pub const SEED: &'static [u8] = b"round_leaderboard";
+    pub const MAX_ENTRIES: u32 = 200;
 
-        let game_config = &mut ctx.accounts.game_config;
-        game_config.authority = ctx.accounts.authority.key();
-        game_config.round_count = 0;
-        game_config.entry_fee_lamports = entry_fee_lamports;
-        game_config.fee_basis_points = fee_basis_points;
-        game_config.bump = ctx.bumps.game_config;
+    pub fn capacity_for(max_players: u32) -> u32 {
+        std::cmp::min(max_players, Self::MAX_ENTRIES)
+    }
 
-        let leaderboard = &mut ctx.accounts.leaderboard;
-        leaderboard.game_config = game_config.key();
-        leaderboard.entries = Vec::new();
-        leaderboard.bump = ctx.bumps.leaderboard;
+    pub fn space(max_players: u32) -> usize {
+        let capacity = Self::capacity_for(max_players) as usize;
+        8 + 32 + 4 + 4 + (capacity * RoundLeaderboardEntry::SIZE) + 1
+    }
 
-        Ok(())
+    pub fn add_entrant(&mut self, player: Pubkey) {
+        if self.entries.len() < self.capacity as usize {
+            self.entries.push(RoundLeaderboardEntry {
+                player,
+                guesses: 0,
+                guesses_to_win: 0,
+            });
+        }
     }
 
-    pub fn create_round(
-        ctx: Context<CreateRound>,
-        word_hash: [u8; 32],
-        max_players: u32,
-        duration_seconds: i64,
-    ) -> Result<()> {
-        let clock = Clock::get()?;
-        let game_config = &mut ctx.accounts.game_config;
-        let round = &mut ctx.accounts.round;
+    pub fn record_guess(&mut self, player: Pubkey, guesses: u32) {
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.player == player) {
+            entry.guesses = guesses;
+        }
+    }
 
-        round.id = game_config.round_count;
-        round.game_config = game_config.key();
-        round.word_hash = word_hash;
-        round.is_active = true;
-        round.winner = Pubkey::default();
-        round.has_winner = false;
-        round.pot_lamports = 0;
-        round.pot_distributed = false;
-        round.nft_minted = false;
-        round.player_count = 0;
-        round.max_players = max_players;
-        round.created_at = clock.unix_timestamp;
-        round.expires_at = clock
-            .unix_timestamp
-            .checked_add(duration_seconds)
-            .ok_or(SolPotError::ArithmeticOverflow)?;
-        round.entry_fee_lamports = game_config.entry_fee_lamports;
-        round.bump = ctx.bumps.round;
+    pub fn record_win(&mut self, player: Pubkey, guesses: u32) {
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.player == player) {
+            entry.guesses_to_win = guesses;
+        }
+    }
 
-        game_config.round_count = game_config
-            .round_count
-            .checked_add(1)
-            .ok_or(SolPotError::ArithmeticOverflow)?;
+    /// Takes back `player`'s win, e.g. when an earlier commitment's reveal
+    /// replaces them as the winner.
+    pub fn remove_win(&mut self, player: Pubkey) {
+        self.record_win(player, 0);
+    }
+}
 
-        emit!(RoundCreated {
-            round_id: round.id,
-            entry_fee_lamports: round.entry_fee_lamports,
-            expires_at: round.expires_at,
-            max_players: round.max_players,
-        });
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RoundLeaderboardEntry {
+    pub player: Pubkey,
+    pub guesses: u32,
+    /// 0 unless the player holds a win in the round.
+    pub guesses_to_win: u32,
+}
 
-        Ok(())
-    }
+impl RoundLeaderboardEntry {
+    pub const SIZE: usize = 32 + 4 + 4;
+}
 
-    pub fn enter_round(ctx: Context<EnterRound>) -> Result<()> {
-        let round = &mut ctx.accounts.round;
+/// A trait attached to a reward NFT through the Core `Attributes` plugin.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct NftAttribute {
+    pub key: String,
+    pub value: String,
+}
 
-        require!(round.is_active, SolPotError::RoundNotActive);
-        require!(!round.has_winner, SolPotError::RoundAlreadyWon);
-        require!(
-            round.player_count < round.max_players,
-            SolPotError::MaxPlayersReached
-        );
+/// Settings for a `create_round` call; see the matching `Round` fields.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CreateRoundParams {
+    pub word_hash: [u8; 32],
+    pub max_players: u32,
+    pub duration_seconds: i64,
+    pub allow_multiple_winners: bool,
+    pub max_guesses: u32,
+    pub guess_fee_lamports: u64,
+    pub reveal_window_seconds: i64,
+    pub answers_merkle_root: [u8; 32],
+    pub min_players: u32,
+    pub random_fallback: bool,
+    pub guess_log_overwrite: bool,
+    pub gate_mint: Option<Pubkey>,
+    pub word_length: u8,
+    pub hint_uri: String,
+    pub claim_window_seconds: i64,
+    pub answer_commitment: [u8; 32],
+    pub fee_increment_lamports: u64,
+    pub max_entry_fee_lamports: u64,
+    pub guess_cooldown_seconds: i64,
+    pub normalization_flags: u8,
+    pub milestone_lamports: u64,
+    pub vesting_duration_seconds: i64,
+    pub vesting_cliff_seconds: i64,
+    pub allowlist_root: [u8; 32],
+    pub seed_pot: u64,
+    pub dictionary_root: [u8; 32],
+    pub refund_tips: bool,
+    pub fee_basis_points_override: Option<u16>,
+    pub max_pot_lamports: u64,
+    pub commit_slot: u64,
+    pub guess_kind: u8,
+    pub grace_seconds: i64,
+    pub anti_snipe_seconds: i64,
+    pub max_anti_snipe_seconds: i64,
+    pub charity_wallet: Option<Pubkey>,
+    pub charity_basis_points: u16,
+    pub nonce: [u8; 16],
+    pub sealed: bool,
+    pub starts_at: i64,
+}
 
-        let clock = Clock::get()?;
-        require!(
-            clock.unix_timestamp < round.expires_at,
-            SolPotError::RoundExpired
-        );
+/// One round of a `batch_create_rounds` call.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RoundParams {
+    pub word_hash: [u8; 32],
+    pub max_players: u32,
+    pub duration_seconds: i64,
+}
 
-        transfer(
-            CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.player.to_account_info(),
-                    to: round.to_account_info(),
-                },
-            ),
-            round.entry_fee_lamports,
-        )?;
+impl RoundParams {
+    /// Keeps a batch within transaction size and compute limits.
+    pub const MAX_BATCH_LEN: usize = 7;
+}
 
-        round.pot_lamports = round
-            .pot_lamports
-            .checked_add(round.entry_fee_lamports)
-            .ok_or(SolPotError::ArithmeticOverflow)?;
-        round.player_count = round
-            .player_count
+/// Holds the house cut of SOL pots until the authority sweeps it.
+/// Seeds: ["fee_vault", game_config]
+#[account]
+pub struct FeeVault {
+    pub game_config: Pubkey,
+    /// Fees credited by `distribute_pot` and not yet swept.
+    pub accrued_fees: u64,
+    pub bump: u8,
+}
+
+impl FeeVault {
+    pub const SEED: &'static [u8] = b"fee_vault";
+    pub const SIZE: usize = 8 + 32 + 8 + 1;
+}
+
+/// Rollover jackpot fed by wrong guesses. Each incorrect guess moves
+/// `loss_fee_to_jackpot_bps` of its guess fee out of the round's pot into
+/// here; `claim_jackpot` folds the balance into a running round.
+/// Seeds: ["jackpot", game_config]
+#[account]
+pub struct Jackpot {
+    pub game_config: Pubkey,
+    pub loss_fee_to_jackpot_bps: u16,
+    pub jackpot_balance: u64,
+    pub bump: u8,
+}
+
+impl Jackpot {
+    pub const SEED: &'static [u8] = b"jackpot";
+    pub const SIZE: usize = 8 + 32 + 2 + 8 + 1;
+}
+
+/// Holds a vesting round's prize, created with the round and funded by
+/// `distribute_pot`. The winner withdraws it with `claim_vested`.
+/// Seeds: ["prize_vault", round]
+#[account]
+#[derive(Default)]
+pub struct PrizeVault {
+    pub round: Pubkey,
+    pub round_id: u64,
+    pub winner: Pubkey,
+    pub total: u64,
+    pub claimed: u64,
+    /// When the pot was distributed; vesting runs from here.
+    pub start: i64,
+    /// Nothing unlocks before `start + cliff_seconds`; everything has by
+    /// `start + duration_seconds`.
+    pub cliff_seconds: i64,
+    pub duration_seconds: i64,
+    pub bump: u8,
+}
+
+impl PrizeVault {
+    pub const SEED: &'static [u8] = b"prize_vault";
+    pub const SIZE: usize = 8 + 32 + 8 + 32 + 8 + 8 + 8 + 8 + 8 + 1;
+
+    /// Amount unlocked at `now`, ignoring what was already claimed.
+    pub fn vested_at(&self, now: i64) -> u64 {
+        let elapsed = now.saturating_sub(self.start);
+        if elapsed < self.cliff_seconds {
+            0
+        } else if elapsed >= self.duration_seconds {
+            self.total
+        } else {
+            (self.total as u128 * elapsed as u128 / self.duration_seconds as u128) as u64
+        }
+    }
+}
+
+/// Which of a round's merkle roots `build_answer_set` writes.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum AnswerSetTarget {
+    /// `answers_merkle_root`: every accepted answer.
+    #[default]
+    Answers,
+    /// `dictionary_root`: every accepted guess.
+    Dictionary,
+}
+
+/// Accumulates leaf hashes across `build_answer_set` calls. Only the roots
+/// of the complete subtrees built so far (at most one per height) are kept,
+/// so leaves can be streamed in without storing them.
+/// Seeds: ["answer_set", round]
+#[account]
+#[derive(Default)]
+pub struct AnswerSetBuilder {
+    pub round: Pubkey,
+    pub target: AnswerSetTarget,
+    pub leaf_count: u32,
+    /// Subtree roots, tallest first.
+    pub frontier: Vec<MerkleNode>,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct MerkleNode {
+    pub hash: [u8; 32],
+    pub height: u8,
+}
+
+impl AnswerSetBuilder {
+    pub const SEED: &'static [u8] = b"answer_set";
+    /// One subtree per set bit of `leaf_count`.
+    pub const MAX_FRONTIER: usize = 32;
+    pub const SIZE: usize = 8 + 32 + 1 + 4 + 4 + Self::MAX_FRONTIER * (32 + 1) + 1;
+
+    /// Appends `leaf`, merging equal-height subtrees as it goes. Leaves are
+    /// paired in the order they are pushed.
+    pub fn push(&mut self, leaf: [u8; 32]) -> Result<()> {
+        self.leaf_count = self
+            .leaf_count
             .checked_add(1)
             .ok_or(SolPotError::ArithmeticOverflow)?;
+        let mut node = MerkleNode {
+            hash: leaf,
+            height: 0,
+        };
+        while let Some(top) = self.frontier.last() {
+            if top.height != node.height {
+                break;
+            }
+            node = MerkleNode {
+                hash: hash_merkle_pair(&top.hash, &node.hash),
+                height: node.height + 1,
+            };
+            self.frontier.pop();
+        }
+        self.frontier.push(node);
+        Ok(())
+    }
 
-        let player_entry = &mut ctx.accounts.player_entry;
-        player_entry.player = ctx.accounts.player.key();
-        player_entry.round = ctx.accounts.round.key();
-        player_entry.entered_at = clock.unix_timestamp;
-        player_entry.bump = ctx.bumps.player_entry;
+    /// Root of the tree over every leaf pushed so far. A node left without
+    /// a sibling is carried up a level unchanged, so the result matches
+    /// hashing the leaves level by level with `hash_merkle_pair`.
+    pub fn root(&self) -> Option<[u8; 32]> {
+        let mut nodes = self.frontier.iter().rev();
+        let first = nodes.next()?.hash;
+        Some(nodes.fold(first, |acc, node| hash_merkle_pair(&node.hash, &acc)))
+    }
+}
 
-        emit!(PlayerEntered {
-            round_id: ctx.accounts.round.id,
-            player: ctx.accounts.player.key(),
-            pot_lamports: ctx.accounts.round.pot_lamports,
-            player_count: ctx.accounts.round.player_count,
-        });
+/// Lifetime referral earnings of one referrer, for an inviter leaderboard.
+/// Seeds: ["referral_stats", referrer]
+#[account]
+#[derive(Default)]
+pub struct ReferralStats {
+    pub referrer: Pubkey,
+    pub referral_count: u32,
+    pub total_earned: u64,
+    pub bump: u8,
+}
 
-        Ok(())
+impl ReferralStats {
+    pub const SEED: &'static [u8] = b"referral_stats";
+    pub const SIZE: usize = 8 + 32 + 4 + 8 + 1;
+}
+
+/// Lifetime stats of one player, kept whether or not they make the
+/// leaderboard. Created on the player's first entry.
+/// Seeds: ["profile", game_config, player]
+#[account]
+#[derive(Default)]
+pub struct PlayerProfile {
+    pub game_config: Pubkey,
+    pub player: Pubkey,
+    pub rounds_entered: u32,
+    pub guesses_made: u32,
+    /// Wins paid out by `distribute_pot`, shares of a split pot included.
+    pub wins: u32,
+    pub total_winnings: u64,
+    /// Entry fees paid, referral cuts included.
+    pub total_wagered: u64,
+    pub bump: u8,
+    /// When the player last won a round with a guess, recorded as the win
+    /// is made so the win cooldown starts before the pot is paid out.
+    pub last_win_at: i64,
+}
+
+impl PlayerProfile {
+    pub const SEED: &'static [u8] = b"profile";
+    pub const SIZE: usize = 8 + 32 + 32 + 4 + 4 + 4 + 8 + 8 + 1 + 8;
+
+    /// Whether the player won less than `cooldown_seconds` before `now`.
+    pub fn in_win_cooldown(&self, cooldown_seconds: i64, now: i64) -> bool {
+        self.last_win_at > 0 && now < self.last_win_at.saturating_add(cooldown_seconds)
     }
+}
 
-    pub fn submit_guess(ctx: Context<SubmitGuess>, guess: String) -> Result<()> {
-        // The guess_record PDA is `init` — if it already exists Anchor will
-        // reject the tx before we even reach this point (account already in use).
-        // So reaching here means this is the player's first guess.
-        ctx.accounts.guess_record.bump = ctx.bumps.guess_record;
+/// Wallets banned from entering and guessing in a game's rounds.
+/// Seeds: ["blacklist", game_config]
+///
+/// An inline list rather than a merkle root: at `MAX_PLAYERS` keys the
+/// account stays under 10 KB and a linear scan costs a few thousand compute
+/// units, while bans take effect with one authority transaction and players
+/// never have to fetch and pass a (non-)membership proof. A merkle root would
+/// scale past the cap, but proving absence needs a sorted tree and an
+/// off-chain service serving proofs to every player.
+#[account]
+#[derive(Default)]
+pub struct Blacklist {
+    pub game_config: Pubkey,
+    pub players: Vec<Pubkey>,
+    pub bump: u8,
+}
 
-        let round = &mut ctx.accounts.round;
+impl Blacklist {
+    pub const SEED: &'static [u8] = b"blacklist";
+    pub const MAX_PLAYERS: usize = 256;
+    pub const SIZE: usize = 8 + 32 + 4 + Self::MAX_PLAYERS * 32 + 1;
+}
 
-        require!(round.is_active, SolPotError::RoundNotActive);
-        require!(!round.has_winner, SolPotError::RoundAlreadyWon);
+/// A run of consecutive rounds with its own leaderboard and a prize pool
+/// for its top scorers. One per game, reused from season to season.
+/// Seeds: ["season", game_config]
+#[account]
+#[derive(Default)]
+pub struct Season {
+    pub game_config: Pubkey,
+    /// Counts up from 1 with each `start_season`.
+    pub season_id: u32,
+    pub is_active: bool,
+    /// Rounds with ids in `start_round..end_round` count toward the season.
+    pub start_round: u64,
+    pub end_round: u64,
+    /// Lamports put up by the authority and held by this account until
+    /// `end_season` pays them out.
+    pub prize_pool: u64,
+    /// How many of the top entries share `prize_pool`.
+    pub prize_winners: u8,
+    /// Ranked like the game leaderboard, by `score`.
+    pub entries: Vec<LeaderboardEntry>,
+    pub bump: u8,
+}
 
-        let clock = Clock::get()?;
-        require!(
-            clock.unix_timestamp < round.expires_at,
-            SolPotError::RoundExpired
-        );
+impl Season {
+    pub const SEED: &'static [u8] = b"season";
+    pub const MAX_ENTRIES: usize = 20;
+    pub const MAX_PRIZE_WINNERS: u8 = 10;
+    pub const SIZE: usize =
+        8 + 32 + 4 + 1 + 8 + 8 + 8 + 1 + 4 + Self::MAX_ENTRIES * LeaderboardEntry::SIZE + 1;
 
-        let normalized = guess.to_lowercase();
-        let guess_hash = hash(normalized.as_bytes());
-        let is_correct = guess_hash.to_bytes() == round.word_hash;
+    /// Whether a win in round `round_id` counts toward the running season.
+    pub fn covers(&self, round_id: u64) -> bool {
+        self.is_active && (self.start_round..self.end_round).contains(&round_id)
+    }
+}
 
-        if is_correct {
-            round.winner = ctx.accounts.player.key();
-            round.has_winner = true;
-            round.is_active = false;
-        }
+#[account]
+#[derive(Default)]
+pub struct Leaderboard {
+    pub game_config: Pubkey,
+    /// Maximum number of entries; raised by `resize_leaderboard`.
+    pub capacity: u32,
+    pub entries: Vec<LeaderboardEntry>,
+    pub bump: u8,
+}
 
-        emit!(GuessResult {
-            round_id: round.id,
-            player: ctx.accounts.player.key(),
-            is_correct,
-        });
+impl Leaderboard {
+    pub const SEED: &'static [u8] = b"leaderboard";
+    pub const INITIAL_CAPACITY: u32 = 50;
+    pub const SIZE: usize = Self::space(Self::INITIAL_CAPACITY);
+    /// Cap on `top_players` so the event stays within the log size limit.
+    pub const MAX_TOP_PLAYERS: u8 = 50;
+    /// How many entries `player_rank` looks through. The lookup is a linear
+    /// scan, and `resize_leaderboard` doesn't bound the board, so this keeps
+    /// its compute cost fixed; players ranked lower are reported as unranked.
+    pub const MAX_RANK_SCAN: usize = 500;
 
-        Ok(())
+    pub const fn space(capacity: u32) -> usize {
+        8 + 32 + 4 + 4 + (capacity as usize * LeaderboardEntry::SIZE) + 1
     }
 
-    pub fn distribute_pot(ctx: Context<DistributePot>) -> Result<()> {
-        let pot = ctx.accounts.round.pot_lamports;
-        let fee_bps = ctx.accounts.game_config.fee_basis_points;
-        let winner_key = ctx.accounts.round.winner;
-        let round_id = ctx.accounts.round.id;
+    /// Credits a win in round `round_id`, made at `won_at`, to `player`;
+    /// see `record_ranked_win`.
+    pub fn record_win(
+        &mut self,
+        player: Pubkey,
+        amount: u64,
+        round_id: u64,
+        won_at: i64,
+        game_config: &GameConfig,
+    ) -> Result<()> {
+        record_ranked_win(
+            &mut self.entries,
+            self.capacity as usize,
+            player,
+            amount,
+            round_id,
+            won_at,
+            game_config,
+        )
+    }
 
-        let round_info = ctx.accounts.round.to_account_info();
-        let rent = Rent::get()?;
-        let min_balance = rent.minimum_balance(round_info.data_len());
-        let available = round_info
-            .lamports()
-            .checked_sub(min_balance)
-            .ok_or(SolPotError::ArithmeticOverflow)?;
-        let distributable = std::cmp::min(pot, available);
+    /// `player`'s 1-based rank and entry, if they are within the first
+    /// `MAX_RANK_SCAN` entries. O(n) in the entries scanned.
+    pub fn rank_of(&self, player: &Pubkey) -> Option<(u32, &LeaderboardEntry)> {
+        self.entries
+            .iter()
+            .take(Self::MAX_RANK_SCAN)
+            .enumerate()
+            .find(|(_, e)| e.player == *player)
+            .map(|(index, entry)| (index as u32 + 1, entry))
+    }
 
-        let fee = distributable
-            .checked_mul(fee_bps as u64)
-            .and_then(|v| v.checked_div(10000))
+    /// Whether `player` won less than `cooldown_seconds` before `now`.
+    /// Players who never made the leaderboard have no recorded win.
+    pub fn in_win_cooldown(&self, player: &Pubkey, cooldown_seconds: i64, now: i64) -> bool {
+        self.entries.iter().any(|e| {
+            e.player == *player
+                && e.last_win_at > 0
+                && now < e.last_win_at.saturating_add(cooldown_seconds)
+        })
+    }
+}
+
+/// Credits a win in round `round_id`, made at `won_at`, to `player`, and
+/// keeps the entries sorted by `score` under `game_config`'s weights. A new
+/// player is added while `entries` is under `capacity`; once it's full they
+/// replace the last entry if they outscore it. Ties keep their existing order,
+/// so a player who reaches a score ranks behind those already at it. After a
+/// weight change the whole board is rescored and re-sorted on the next win.
+fn record_ranked_win(
+    entries: &mut Vec<LeaderboardEntry>,
+    capacity: usize,
+    player: Pubkey,
+    amount: u64,
+    round_id: u64,
+    won_at: i64,
+    game_config: &GameConfig,
+) -> Result<()> {
+    if entries
+        .iter()
+        .any(|e| e.score != game_config.leaderboard_score(e))
+    {
+        for entry in entries.iter_mut() {
+            entry.score = game_config.leaderboard_score(entry);
+        }
+        entries.sort_by(|a, b| b.score.cmp(&a.score));
+    }
+
+    if let Some(index) = entries.iter().position(|e| e.player == player) {
+        let entry = &mut entries[index];
+        entry.wins = entry
+            .wins
+            .checked_add(1)
             .ok_or(SolPotError::ArithmeticOverflow)?;
-        let winner_amount = distributable
-            .checked_sub(fee)
+        entry.total_winnings = entry
+            .total_winnings
+            .checked_add(amount)
             .ok_or(SolPotError::ArithmeticOverflow)?;
-
-        **round_info.try_borrow_mut_lamports()? = round_info
-            .lamports()
-            .checked_sub(distributable)
+        entry.current_streak = entry
+            .current_streak
+            .checked_add(1)
             .ok_or(SolPotError::ArithmeticOverflow)?;
+        entry.best_streak = std::cmp::max(entry.best_streak, entry.current_streak);
+        entry.last_win_round_id = std::cmp::max(entry.last_win_round_id, round_id);
+        entry.last_win_at = std::cmp::max(entry.last_win_at, won_at);
+        entry.score = game_config.leaderboard_score(entry);
 
-        **ctx.accounts.winner.try_borrow_mut_lamports()? = ctx
-            .accounts
-            .winner
-            .lamports()
-            .checked_add(winner_amount)
-            .ok_or(SolPotError::ArithmeticOverflow)?;
+        // A win never lowers a score, so everything ahead of the entry
+        // still has at least its old score and it only moves past those
+        // now strictly behind it.
+        let score = entry.score;
+        let position = entries[..index].partition_point(|e| e.score >= score);
+        entries[position..=index].rotate_right(1);
+    } else {
+        let mut entry = LeaderboardEntry {
+            player,
+            wins: 1,
+            total_winnings: amount,
+            current_streak: 1,
+            best_streak: 1,
+            last_win_round_id: round_id,
+            last_win_at: won_at,
+            score: 0,
+        };
+        entry.score = game_config.leaderboard_score(&entry);
+        if entries.len() >= capacity {
+            match entries.last() {
+                Some(last) if last.score < entry.score => {
+                    entries.pop();
+                }
+                _ => return Ok(()),
+            }
+        }
+        let position = entries.partition_point(|e| e.score >= entry.score);
+        entries.insert(position, entry);
+    }
+    Ok(())
+}
 
-        **ctx.accounts.fee_receiver.try_borrow_mut_lamports()? = ctx
-            .accounts
-            .fee_receiver
-            .lamports()
-            .checked_add(fee)
-            .ok_or(SolPotError::ArithmeticOverflow)?;
+/// Streaks count consecutive wins. Wins extend the streak when the pot is
+/// distributed; losses are recorded lazily through `reset_streak`, and only
+/// break the streak if the lost round is newer than the player's last win.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, PartialEq, Eq, Debug)]
+pub struct LeaderboardEntry {
+    pub player: Pubkey,
+    pub wins: u32,
+    pub total_winnings: u64,
+    pub current_streak: u32,
+    pub best_streak: u32,
+    pub last_win_round_id: u64,
+    /// When the player's latest paid-out win was made.
+    pub last_win_at: i64,
+    /// Ranking key; see `GameConfig::leaderboard_score`.
+    pub score: u64,
+}
 
-        let round = &mut ctx.accounts.round;
-        round.pot_distributed = true;
-        round.pot_lamports = 0;
+impl LeaderboardEntry {
+    pub const SIZE: usize = 32 + 4 + 8 + 4 + 4 + 8 + 8 + 8;
+}
 
-        let leaderboard = &mut ctx.accounts.leaderboard;
-        if let Some(entry) = leaderboard
-            .entries
-            .iter_mut()
-            .find(|e| e.player == winner_key)
-        {
-            entry.wins = entry
-                .wins
-                .checked_add(1)
-                .ok_or(SolPotError::ArithmeticOverflow)?;
-            entry.total_winnings = entry
-                .total_winnings
-                .checked_add(winner_amount)
-                .ok_or(SolPotError::ArithmeticOverflow)?;
-        } else if leaderboard.entries.len() < Leaderboard::MAX_ENTRIES {
-            leaderboard.entries.push(LeaderboardEntry {
-                player: winner_key,
-                wins: 1,
-                total_winnings: winner_amount,
-            });
+/// `LeaderboardEntry` as the original program wrote it, before streaks,
+/// `last_win_round_id`, `last_win_at` and `score`; only read by
+/// `upgrade_leaderboard`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, PartialEq, Eq, Debug)]
+pub struct LegacyLeaderboardEntry {
+    pub player: Pubkey,
+    pub wins: u32,
+    pub total_winnings: u64,
+}
+
+impl From<LegacyLeaderboardEntry> for LeaderboardEntry {
+    fn from(entry: LegacyLeaderboardEntry) -> Self {
+        LeaderboardEntry {
+            player: entry.player,
+            wins: entry.wins,
+            total_winnings: entry.total_winnings,
+            ..Default::default()
         }
-        leaderboard.entries.sort_by(|a, b| b.wins.cmp(&a.wins));
+    }
+}
 
-        emit!(PotDistributed {
-            round_id,
-            winner: winner_key,
-            winner_amount,
-            fee_amount: fee,
-        });
+// ── Events ──────────────────────────────────────────────────────────────────
 
-        Ok(())
-    }
+/// Emitted by the `update_*` config instructions; fields that weren't
+/// updated carry the same old and new value.
+#[event]
+pub struct ConfigUpdated {
+    pub old_entry_fee_lamports: u64,
+    pub new_entry_fee_lamports: u64,
+    pub old_fee_basis_points: u16,
+    pub new_fee_basis_points: u16,
+    pub old_runner_up_basis_points: u16,
+    pub new_runner_up_basis_points: u16,
+}
 
-    pub fn mint_reward_nft(
-        ctx: Context<MintRewardNft>,
-        name: String,
-        uri: String,
-    ) -> Result<()> {
-        // Build Metaplex Core CreateV1 instruction data manually.
-        // CreateV1Args: data_state (u8) + name (String) + uri (String) + plugins (Option<Vec>)
-        let mut data: Vec<u8> = Vec::new();
-        // Metaplex Core CreateV1 discriminator (enum variant 0)
-        data.push(0u8);
-        // CreateV1Args:
-        //   data_state: DataState enum (0 = AccountState)
-        data.push(0u8);
-        //   name: borsh String (u32 LE length + bytes)
-        data.extend_from_slice(&(name.len() as u32).to_le_bytes());
-        data.extend_from_slice(name.as_bytes());
-        //   uri: borsh String
-        data.extend_from_slice(&(uri.len() as u32).to_le_bytes());
-        data.extend_from_slice(uri.as_bytes());
-        //   plugins: Option<Vec<PluginAuthorityPair>> = Some(empty vec)
-        //   Must use Some([]) not None to match the SDK's serialization format
-        data.push(1u8); // Option tag: Some
-        data.extend_from_slice(&0u32.to_le_bytes()); // Vec length: 0
-
-        // Metaplex Core uses its own program ID as a sentinel for absent optional accounts.
-        let absent = MPL_CORE_PROGRAM_ID;
-
-        let accounts = vec![
-            AccountMeta::new(ctx.accounts.asset.key(), true),           // 0: asset (writable, signer)
-            AccountMeta::new_readonly(absent, false),                   // 1: collection (absent)
-            AccountMeta::new_readonly(absent, false),                   // 2: authority (absent → defaults to payer)
-            AccountMeta::new(ctx.accounts.payer.key(), true),           // 3: payer (writable, signer)
-            AccountMeta::new_readonly(ctx.accounts.winner.key(), false),// 4: owner (the winner)
-            AccountMeta::new_readonly(absent, false),                   // 5: update_authority (absent → defaults to payer)
-            AccountMeta::new_readonly(ctx.accounts.system_program.key(), false), // 6: system_program
-            AccountMeta::new_readonly(absent, false),                   // 7: log_wrapper (absent)
-        ];
+#[event]
+pub struct AuthorityTransferProposed {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
 
-        let ix = Instruction {
-            program_id: MPL_CORE_PROGRAM_ID,
-            accounts,
-            data,
-        };
+#[event]
+pub struct AuthorityTransferred {
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
 
-        invoke(
-            &ix,
-            &[
-                ctx.accounts.asset.to_account_info(),
-                ctx.accounts.payer.to_account_info(),
-                ctx.accounts.winner.to_account_info(),
-                ctx.accounts.system_program.to_account_info(),
-                ctx.accounts.mpl_core_program.to_account_info(),
-            ],
-        )?;
+/// Emitted by `top_players`; clients read it from the transaction logs.
+#[event]
+pub struct TopPlayers {
+    pub entries: Vec<LeaderboardEntry>,
+}
 
-        ctx.accounts.round.nft_minted = true;
+/// Emitted by `player_rank`. `rank` is 1-based; 0 means the player isn't on
+/// the leaderboard (or is past `Leaderboard::MAX_RANK_SCAN`), with no wins
+/// or winnings recorded.
+#[event]
+pub struct PlayerRank {
+    pub player: Pubkey,
+    pub rank: u32,
+    pub wins: u32,
+    pub total_winnings: u64,
+}
 
-        emit!(NftMinted {
-            round_id: ctx.accounts.round.id,
-            winner: ctx.accounts.winner.key(),
-            mint: ctx.accounts.asset.key(),
-        });
+#[event]
+pub struct LeaderboardResized {
+    pub old_capacity: u32,
+    pub new_capacity: u32,
+}
 
-        Ok(())
-    }
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub old_schema_version: u8,
+    pub new_schema_version: u8,
+}
 
-    pub fn close_round(ctx: Context<CloseRound>) -> Result<()> {
-        let clock = Clock::get()?;
+#[event]
+pub struct LeaderboardUpgraded {
+    pub entries: u32,
+}
+
+#[event]
+pub struct CollectionUpdated {
+    pub old_collection: Pubkey,
+    pub new_collection: Pubkey,
+}
+
+#[event]
+pub struct NftMetadataTemplateUpdated {
+    pub nft_name_prefix: String,
+    pub nft_uri_prefix: String,
+}
+
+#[event]
+pub struct FeeDestinationUpdated {
+    pub old_fee_destination: Pubkey,
+    pub new_fee_destination: Pubkey,
+}
+
+#[event]
+pub struct DustToleranceUpdated {
+    pub old_dust_tolerance_lamports: u64,
+    pub new_dust_tolerance_lamports: u64,
+}
+
+#[event]
+pub struct WinCooldownUpdated {
+    pub old_win_cooldown_seconds: i64,
+    pub new_win_cooldown_seconds: i64,
+}
+
+#[event]
+pub struct SeasonStarted {
+    pub season_id: u32,
+    pub start_round: u64,
+    pub end_round: u64,
+    pub prize_pool: u64,
+    pub prize_winners: u8,
+}
+
+#[event]
+pub struct SeasonEnded {
+    pub season_id: u32,
+    /// Top scorers paid, best first; empty if nobody won in the season.
+    pub winners: Vec<Pubkey>,
+    pub prize_pool: u64,
+}
 
-        let expired_no_winner =
-            clock.unix_timestamp >= ctx.accounts.round.expires_at && !ctx.accounts.round.has_winner;
-        let won_and_distributed =
-            ctx.accounts.round.has_winner && ctx.accounts.round.pot_distributed;
+#[event]
+pub struct BlacklistUpdated {
+    pub player: Pubkey,
+    pub blacklisted: bool,
+    pub blacklist_len: u32,
+}
+
+#[event]
+pub struct LeaderboardWeightsUpdated {
+    pub win_weight: u64,
+    pub winnings_weight: u64,
+}
+
+#[event]
+pub struct DisputePeriodUpdated {
+    pub old_dispute_seconds: i64,
+    pub new_dispute_seconds: i64,
+}
+
+#[event]
+pub struct DurationBoundsUpdated {
+    pub min_duration_seconds: i64,
+    pub max_duration_seconds: i64,
+}
+
+#[event]
+pub struct LoyaltyDiscountUpdated {
+    pub old_loyalty_discount_bps: u16,
+    pub new_loyalty_discount_bps: u16,
+}
+
+#[event]
+pub struct LoyaltyDiscountApplied {
+    pub round_id: u64,
+    pub player: Pubkey,
+    pub list_fee: u64,
+    pub discount: u64,
+}
+
+#[event]
+pub struct ReferralRateUpdated {
+    pub old_referral_basis_points: u16,
+    pub new_referral_basis_points: u16,
+}
+
+#[event]
+pub struct GamePaused {
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct GameResumed {
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct RoundCreated {
+    pub round_id: u64,
+    pub entry_fee_lamports: u64,
+    pub expires_at: i64,
+    pub max_players: u32,
+    pub seeded_lamports: u64,
+    /// 0 when the pot is uncapped.
+    pub max_pot_lamports: u64,
+    /// When guessing closes: `expires_at` plus the grace period.
+    pub guesses_close_at: i64,
+}
+
+#[event]
+pub struct PlayerEntered {
+    pub round_id: u64,
+    pub player: Pubkey,
+    pub pot_lamports: u64,
+    pub player_count: u32,
+    pub entry_fee_charged: u64,
+    pub next_entry_fee: u64,
+}
+
+#[event]
+pub struct ReferralPaid {
+    pub round_id: u64,
+    pub player: Pubkey,
+    pub referrer: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct GuessResult {
+    pub round_id: u64,
+    pub player: Pubkey,
+    pub is_correct: bool,
+    /// 1-based attempt number of this guess for the player.
+    pub attempt: u32,
+    /// Slot the guess landed in, to order same-block guesses off-chain.
+    pub slot: u64,
+    /// The round's running `total_guesses`, including this one.
+    pub total_guesses: u32,
+    /// The round's running `correct_guesses`, including this one.
+    pub correct_guesses: u32,
+    /// The guess was correct but the player won too recently for it to
+    /// count; the round carries on.
+    pub win_cooldown_active: bool,
+    /// Note from `submit_guess_with_memo`; empty otherwise.
+    pub memo: String,
+}
+
+impl GuessResult {
+    pub const MAX_MEMO_LEN: usize = 64;
+}
+
+#[event]
+pub struct GuessCommitted {
+    pub round_id: u64,
+    pub player: Pubkey,
+    pub attempt: u32,
+}
+
+#[event]
+pub struct PotMilestone {
+    pub round_id: u64,
+    /// Highest multiple of `round.milestone_lamports` the pot has passed.
+    pub milestone_lamports: u64,
+    pub pot_lamports: u64,
+}
+
+#[event]
+pub struct GuessFeePaid {
+    pub round_id: u64,
+    pub player: Pubkey,
+    pub fee_lamports: u64,
+    pub pot_lamports: u64,
+}
+
+#[event]
+pub struct PotDistributed {
+    pub round_id: u64,
+    pub winner: Pubkey,
+    /// Total paid to winners (split evenly in multi-winner rounds).
+    pub winner_amount: u64,
+    pub fee_amount: u64,
+    pub winner_count: u32,
+    /// `Pubkey::default()` when no runner-up share was paid.
+    pub runner_up: Pubkey,
+    pub runner_up_amount: u64,
+}
+
+/// What `distribute_pot` would pay right now; see `preview_distribution`.
+#[event]
+pub struct DistributionPreview {
+    pub round_id: u64,
+    /// Total to winners (split evenly in multi-winner rounds).
+    pub winner_amount: u64,
+    pub fee_amount: u64,
+    pub runner_up_amount: u64,
+    pub charity_amount: u64,
+}
+
+#[event]
+pub struct CharityDonation {
+    pub round_id: u64,
+    pub charity_wallet: Pubkey,
+    pub amount: u64,
+}
+
+/// Emitted for each recipient of a multi-winner payout.
+#[event]
+pub struct WinnerPaid {
+    pub round_id: u64,
+    pub winner: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RandomWinnerDrawn {
+    pub round_id: u64,
+    pub winner: Pubkey,
+    pub index: u32,
+    pub slot_hash: [u8; 32],
+}
+
+#[event]
+pub struct NftMinted {
+    pub round_id: u64,
+    pub winner: Pubkey,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct EntryRefunded {
+    pub round_id: u64,
+    pub player: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct EntryReclaimed {
+    pub round_id: u64,
+    pub player: Pubkey,
+}
+
+#[event]
+pub struct RoundTipped {
+    pub round_id: u64,
+    pub tipper: Pubkey,
+    pub amount: u64,
+    pub total_tips: u64,
+    pub pot_lamports: u64,
+}
+
+#[event]
+pub struct BountyAdded {
+    pub round_id: u64,
+    pub amount: u64,
+    pub bounty_lamports: u64,
+    pub pot_lamports: u64,
+}
+
+#[event]
+pub struct TipReclaimed {
+    pub round_id: u64,
+    pub tipper: Pubkey,
+    /// Lamports handed back; 0 when the tip was paid out or forfeited.
+    pub refund: u64,
+}
+
+#[event]
+pub struct RoundAuthorityUpdated {
+    pub round_id: u64,
+    pub old_round_authority: Pubkey,
+    pub new_round_authority: Pubkey,
+}
+
+#[event]
+pub struct RoundExtended {
+    pub round_id: u64,
+    pub old_expires_at: i64,
+    pub new_expires_at: i64,
+}
+
+#[event]
+pub struct RoundForceExpired {
+    pub round_id: u64,
+    pub old_expires_at: i64,
+    pub new_expires_at: i64,
+}
+
+/// A single-winner round's win moved to a player who committed earlier.
+#[event]
+pub struct WinnerReplaced {
+    pub round_id: u64,
+    pub previous_winner: Pubkey,
+    pub winner: Pubkey,
+    pub committed_at: i64,
+}
+
+/// A correct reveal that lost to the winner's earlier (or equal) commitment.
+#[event]
+pub struct LaterCommitment {
+    pub round_id: u64,
+    pub player: Pubkey,
+    pub committed_at: i64,
+    pub winner_committed_at: i64,
+}
+
+#[event]
+pub struct MaxPlayersUpdated {
+    pub round_id: u64,
+    pub old_max_players: u32,
+    pub new_max_players: u32,
+    pub player_count: u32,
+}
+
+#[event]
+pub struct RoundMetadataUpdated {
+    pub round_id: u64,
+    pub title: String,
+    pub hint_uri: String,
+}
+
+#[event]
+pub struct AnswerSetBuilt {
+    pub round_id: u64,
+    pub target: AnswerSetTarget,
+    pub root: [u8; 32],
+    pub leaf_count: u32,
+}
+
+#[event]
+pub struct WordHashUpdated {
+    pub round_id: u64,
+    pub old_word_hash: [u8; 32],
+    pub new_word_hash: [u8; 32],
+}
+
+#[event]
+pub struct PotReconciled {
+    pub round_id: u64,
+    pub surplus: u64,
+    /// Whether the surplus went to the fee vault rather than the pot.
+    pub to_fees: bool,
+    pub pot_lamports: u64,
+}
+
+#[event]
+pub struct HintRevealed {
+    pub round_id: u64,
+    pub revealed: Vec<RevealedChar>,
+}
+
+#[event]
+pub struct StreakReset {
+    pub player: Pubkey,
+    pub round_id: u64,
+}
+
+#[event]
+pub struct PrizeReclaimed {
+    pub round_id: u64,
+    pub winner: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct AnswerCommitted {
+    pub round_id: u64,
+    pub commit_slot: u64,
+    pub commit_slot_hash: [u8; 32],
+}
+
+#[event]
+pub struct AnswerRevealed {
+    pub round_id: u64,
+    pub answer: String,
+}
+
+#[event]
+pub struct RoundDisputed {
+    pub round_id: u64,
+}
+
+#[event]
+pub struct EmergencyWithdraw {
+    pub round_id: u64,
+    /// Lamports, or tokens for token rounds.
+    pub amount: u64,
+}
+
+#[event]
+pub struct PotRolledOver {
+    pub from_round_id: u64,
+    pub to_round_id: u64,
+    pub amount: u64,
+}
+
+#[event]
+pub struct BonusWordCommitted {
+    pub round_id: u64,
+}
+
+#[event]
+pub struct BonusRoundSpawned {
+    pub parent_round_id: u64,
+    pub bonus_round_id: u64,
+    pub winner: Pubkey,
+    pub stake: u64,
+    pub pot_lamports: u64,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct RoundRestarted {
+    pub round_id: u64,
+    pub generation: u32,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct RoundCancelled {
+    pub round_id: u64,
+    pub player_count: u32,
+}
+
+#[event]
+pub struct JackpotContribution {
+    pub round_id: u64,
+    pub player: Pubkey,
+    pub amount: u64,
+    pub jackpot_balance: u64,
+}
+
+#[event]
+pub struct JackpotClaimed {
+    pub round_id: u64,
+    pub amount: u64,
+    pub pot_lamports: u64,
+}
+
+#[event]
+pub struct JackpotRateUpdated {
+    pub old_loss_fee_to_jackpot_bps: u16,
+    pub new_loss_fee_to_jackpot_bps: u16,
+}
+
+#[event]
+pub struct VestedClaim {
+    pub round_id: u64,
+    pub winner: Pubkey,
+    pub amount: u64,
+    pub claimed: u64,
+    pub total: u64,
+}
+
+#[event]
+pub struct FeesSwept {
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub remaining_fees: u64,
+}
+
+#[event]
+pub struct GameClosed {
+    pub authority: Pubkey,
+    pub round_count: u64,
+    pub unswept_fees: u64,
+}
+
+#[event]
+pub struct RoundClosed {
+    pub round_id: u64,
+}
+
+#[event]
+pub struct BatchClosed {
+    pub count: u32,
+}
+
+// ── Program ─────────────────────────────────────────────────────────────────
+
+#[program]
+pub mod solpot {
+    use super::*;
+
+    pub fn initialize_game(
+        ctx: Context<InitializeGame>,
+        entry_fee_lamports: u64,
+        fee_basis_points: u16,
+        mint: Option<Pubkey>,
+        min_entry_fee: u64,
+    ) -> Result<()> {
+        require!(
+            fee_basis_points <= 1000,
+            SolPotError::InvalidFeeBasisPoints
+        );
+
+        let game_config = &mut ctx.accounts.game_config;
+        game_config.authority = ctx.accounts.authority.key();
+        game_config.round_count = 0;
+        game_config.entry_fee_lamports = entry_fee_lamports;
+        game_config.fee_basis_points = fee_basis_points;
+        game_config.bump = ctx.bumps.game_config;
+        game_config.mint = mint.unwrap_or_default();
+        game_config.pending_authority = Pubkey::default();
+        game_config.paused = false;
+        game_config.runner_up_basis_points = 0;
+        game_config.collection = Pubkey::default();
+        game_config.min_duration_seconds = GameConfig::DEFAULT_MIN_DURATION_SECONDS;
+        game_config.max_duration_seconds = GameConfig::DEFAULT_MAX_DURATION_SECONDS;
+        game_config.referral_basis_points = 0;
+        game_config.open_rounds = 0;
+        game_config.total_wagered = 0;
+        game_config.total_paid_out = 0;
+        game_config.total_fees = 0;
+        game_config.loyalty_discount_bps = 0;
+        game_config.dispute_seconds = 0;
+        game_config.fee_destination = ctx.accounts.authority.key();
+        game_config.dust_tolerance_lamports = 0;
+        game_config.min_entry_fee = min_entry_fee;
+        game_config.nft_name_prefix = String::new();
+        game_config.nft_uri_prefix = String::new();
+        game_config.win_cooldown_seconds = 0;
+        game_config.schema_version = GameConfig::SCHEMA_VERSION;
+        game_config.leaderboard_win_weight = 1;
+        game_config.leaderboard_winnings_weight = 0;
+
+        let leaderboard = &mut ctx.accounts.leaderboard;
+        leaderboard.game_config = game_config.key();
+        leaderboard.capacity = Leaderboard::INITIAL_CAPACITY;
+        leaderboard.entries = Vec::new();
+        leaderboard.bump = ctx.bumps.leaderboard;
+
+        let fee_vault = &mut ctx.accounts.fee_vault;
+        fee_vault.game_config = game_config.key();
+        fee_vault.accrued_fees = 0;
+        fee_vault.bump = ctx.bumps.fee_vault;
+
+        let jackpot = &mut ctx.accounts.jackpot;
+        jackpot.game_config = game_config.key();
+        jackpot.loss_fee_to_jackpot_bps = 0;
+        jackpot.jackpot_balance = 0;
+        jackpot.bump = ctx.bumps.jackpot;
+
+        Ok(())
+    }
+
+    /// Only affects rounds created afterwards: `create_round` snapshots the
+    /// entry fee into the round.
+    pub fn update_entry_fee(ctx: Context<UpdateConfig>, new_fee: u64) -> Result<()> {
+        let game_config = &mut ctx.accounts.game_config;
+        let old_fee = game_config.entry_fee_lamports;
+        game_config.entry_fee_lamports = new_fee;
+
+        emit!(ConfigUpdated {
+            old_entry_fee_lamports: old_fee,
+            new_entry_fee_lamports: new_fee,
+            old_fee_basis_points: game_config.fee_basis_points,
+            new_fee_basis_points: game_config.fee_basis_points,
+            old_runner_up_basis_points: game_config.runner_up_basis_points,
+            new_runner_up_basis_points: game_config.runner_up_basis_points,
+        });
+
+        Ok(())
+    }
+
+    pub fn update_fee_basis_points(ctx: Context<UpdateConfig>, new_bps: u16) -> Result<()> {
+        require!(new_bps <= 1000, SolPotError::InvalidFeeBasisPoints);
+
+        let game_config = &mut ctx.accounts.game_config;
+        require!(
+            new_bps as u32 + game_config.runner_up_basis_points as u32 <= 10000,
+            SolPotError::InvalidFeeBasisPoints
+        );
+        let old_bps = game_config.fee_basis_points;
+        game_config.fee_basis_points = new_bps;
+
+        emit!(ConfigUpdated {
+            old_entry_fee_lamports: game_config.entry_fee_lamports,
+            new_entry_fee_lamports: game_config.entry_fee_lamports,
+            old_fee_basis_points: old_bps,
+            new_fee_basis_points: new_bps,
+            old_runner_up_basis_points: game_config.runner_up_basis_points,
+            new_runner_up_basis_points: game_config.runner_up_basis_points,
+        });
+
+        Ok(())
+    }
+
+    /// Sets the share of each pot reserved for the round's earliest entrant.
+    /// Applies to pots distributed afterwards, including running rounds.
+    pub fn update_runner_up_basis_points(
+        ctx: Context<UpdateConfig>,
+        new_bps: u16,
+    ) -> Result<()> {
+        let game_config = &mut ctx.accounts.game_config;
+        require!(
+            game_config.fee_basis_points as u32 + new_bps as u32 <= 10000,
+            SolPotError::InvalidFeeBasisPoints
+        );
+        let old_bps = game_config.runner_up_basis_points;
+        game_config.runner_up_basis_points = new_bps;
+
+        emit!(ConfigUpdated {
+            old_entry_fee_lamports: game_config.entry_fee_lamports,
+            new_entry_fee_lamports: game_config.entry_fee_lamports,
+            old_fee_basis_points: game_config.fee_basis_points,
+            new_fee_basis_points: game_config.fee_basis_points,
+            old_runner_up_basis_points: old_bps,
+            new_runner_up_basis_points: new_bps,
+        });
+
+        Ok(())
+    }
+
+    /// First step of an authority handover. Proposing `Pubkey::default()`
+    /// cancels a pending transfer.
+    pub fn propose_authority_transfer(
+        ctx: Context<UpdateConfig>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        let game_config = &mut ctx.accounts.game_config;
+        game_config.pending_authority = new_authority;
+
+        emit!(AuthorityTransferProposed {
+            authority: game_config.authority,
+            pending_authority: new_authority,
+        });
+
+        Ok(())
+    }
+
+    /// Second step of an authority handover, signed by the proposed key so
+    /// authority can never move to an address nobody controls.
+    pub fn accept_authority_transfer(ctx: Context<AcceptAuthorityTransfer>) -> Result<()> {
+        let game_config = &mut ctx.accounts.game_config;
+        require!(
+            game_config.pending_authority != Pubkey::default(),
+            SolPotError::NoPendingAuthority
+        );
+        require_keys_eq!(
+            ctx.accounts.new_authority.key(),
+            game_config.pending_authority,
+            SolPotError::Unauthorized
+        );
+
+        let old_authority = game_config.authority;
+        game_config.authority = game_config.pending_authority;
+        game_config.pending_authority = Pubkey::default();
+
+        emit!(AuthorityTransferred {
+            old_authority,
+            new_authority: game_config.authority,
+        });
+
+        Ok(())
+    }
+
+    pub fn pause_game(ctx: Context<UpdateConfig>) -> Result<()> {
+        ctx.accounts.game_config.paused = true;
+
+        emit!(GamePaused {
+            authority: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn resume_game(ctx: Context<UpdateConfig>) -> Result<()> {
+        ctx.accounts.game_config.paused = false;
+
+        emit!(GameResumed {
+            authority: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Sets the Metaplex Core collection reward NFTs are minted into. Pass
+    /// `Pubkey::default()` to go back to standalone assets.
+    pub fn set_collection(ctx: Context<UpdateConfig>, collection: Pubkey) -> Result<()> {
+        let game_config = &mut ctx.accounts.game_config;
+        let old_collection = game_config.collection;
+        game_config.collection = collection;
+
+        emit!(CollectionUpdated {
+            old_collection,
+            new_collection: collection,
+        });
+
+        Ok(())
+    }
+
+    /// Ties reward NFTs to their round: from now on they must be named
+    /// `nft_name_prefix` + round id, with URI `nft_uri_prefix` + round id,
+    /// e.g. "SolPot Winner #7" and "https://arweave.net/solpot-winner-7".
+    /// Two empty prefixes lift the requirement.
+    pub fn set_nft_metadata_template(
+        ctx: Context<UpdateConfig>,
+        nft_name_prefix: String,
+        nft_uri_prefix: String,
+    ) -> Result<()> {
+        require!(
+            nft_name_prefix.len() <= GameConfig::MAX_NFT_NAME_PREFIX_LEN
+                && nft_uri_prefix.len() <= GameConfig::MAX_NFT_URI_PREFIX_LEN,
+            SolPotError::NftTemplateTooLong
+        );
+
+        let game_config = &mut ctx.accounts.game_config;
+        game_config.nft_name_prefix = nft_name_prefix;
+        game_config.nft_uri_prefix = nft_uri_prefix;
+
+        emit!(NftMetadataTemplateUpdated {
+            nft_name_prefix: game_config.nft_name_prefix.clone(),
+            nft_uri_prefix: game_config.nft_uri_prefix.clone(),
+        });
+
+        Ok(())
+    }
+
+    pub fn update_referral_basis_points(
+        ctx: Context<UpdateConfig>,
+        new_bps: u16,
+    ) -> Result<()> {
+        require!(
+            new_bps <= GameConfig::MAX_REFERRAL_BASIS_POINTS,
+            SolPotError::InvalidFeeBasisPoints
+        );
+
+        let game_config = &mut ctx.accounts.game_config;
+        let old_bps = game_config.referral_basis_points;
+        game_config.referral_basis_points = new_bps;
+
+        emit!(ReferralRateUpdated {
+            old_referral_basis_points: old_bps,
+            new_referral_basis_points: new_bps,
+        });
+
+        Ok(())
+    }
+
+    /// Sets the entry discount for returning winners; see `enter_round`.
+    pub fn update_loyalty_discount_bps(
+        ctx: Context<UpdateConfig>,
+        new_bps: u16,
+    ) -> Result<()> {
+        require!(
+            new_bps <= GameConfig::MAX_LOYALTY_DISCOUNT_BPS,
+            SolPotError::InvalidFeeBasisPoints
+        );
+
+        let game_config = &mut ctx.accounts.game_config;
+        let old_bps = game_config.loyalty_discount_bps;
+        game_config.loyalty_discount_bps = new_bps;
+
+        emit!(LoyaltyDiscountUpdated {
+            old_loyalty_discount_bps: old_bps,
+            new_loyalty_discount_bps: new_bps,
+        });
+
+        Ok(())
+    }
+
+    /// Sets the allowed `duration_seconds` range for new rounds.
+    pub fn update_duration_bounds(
+        ctx: Context<UpdateConfig>,
+        min_duration_seconds: i64,
+        max_duration_seconds: i64,
+    ) -> Result<()> {
+        require!(
+            min_duration_seconds > 0 && min_duration_seconds <= max_duration_seconds,
+            SolPotError::InvalidDuration
+        );
+
+        let game_config = &mut ctx.accounts.game_config;
+        game_config.min_duration_seconds = min_duration_seconds;
+        game_config.max_duration_seconds = max_duration_seconds;
+
+        emit!(DurationBoundsUpdated {
+            min_duration_seconds,
+            max_duration_seconds,
+        });
+
+        Ok(())
+    }
+
+    /// Points house fees at `new_fee_destination`, e.g. a multisig treasury
+    /// kept apart from the operating key.
+    pub fn set_fee_destination(
+        ctx: Context<UpdateConfig>,
+        new_fee_destination: Pubkey,
+    ) -> Result<()> {
+        let game_config = &mut ctx.accounts.game_config;
+        let old_fee_destination = game_config.fee_destination;
+        game_config.fee_destination = new_fee_destination;
+
+        emit!(FeeDestinationUpdated {
+            old_fee_destination,
+            new_fee_destination,
+        });
+
+        Ok(())
+    }
+
+    /// Sets how the leaderboard ranks players: `wins * win_weight + whole
+    /// SOL won * winnings_weight`. The board is re-sorted under the new
+    /// weights at the next payout.
+    pub fn set_leaderboard_weights(
+        ctx: Context<UpdateConfig>,
+        win_weight: u64,
+        winnings_weight: u64,
+    ) -> Result<()> {
+        let game_config = &mut ctx.accounts.game_config;
+        game_config.leaderboard_win_weight = win_weight;
+        game_config.leaderboard_winnings_weight = winnings_weight;
+
+        emit!(LeaderboardWeightsUpdated {
+            win_weight,
+            winnings_weight,
+        });
+
+        Ok(())
+    }
+
+    /// Bans `player` from entering and guessing, creating the game's
+    /// blacklist on first use. Banning a player twice is a no-op.
+    pub fn add_to_blacklist(ctx: Context<ManageBlacklist>, player: Pubkey) -> Result<()> {
+        let blacklist = &mut ctx.accounts.blacklist;
+        blacklist.game_config = ctx.accounts.game_config.key();
+        blacklist.bump = ctx.bumps.blacklist;
+        if !blacklist.players.contains(&player) {
+            require!(
+                blacklist.players.len() < Blacklist::MAX_PLAYERS,
+                SolPotError::BlacklistFull
+            );
+            blacklist.players.push(player);
+        }
+
+        emit!(BlacklistUpdated {
+            player,
+            blacklisted: true,
+            blacklist_len: blacklist.players.len() as u32,
+        });
+
+        Ok(())
+    }
+
+    /// Lifts a ban. Removing a player who isn't listed is a no-op.
+    pub fn remove_from_blacklist(ctx: Context<ManageBlacklist>, player: Pubkey) -> Result<()> {
+        let blacklist = &mut ctx.accounts.blacklist;
+        blacklist.game_config = ctx.accounts.game_config.key();
+        blacklist.bump = ctx.bumps.blacklist;
+        if let Some(index) = blacklist.players.iter().position(|p| *p == player) {
+            blacklist.players.swap_remove(index);
+        }
+
+        emit!(BlacklistUpdated {
+            player,
+            blacklisted: false,
+            blacklist_len: blacklist.players.len() as u32,
+        });
+
+        Ok(())
+    }
+
+    /// Opens a season covering the next `round_count` rounds to be created,
+    /// funding its prize pool with `prize_pool` lamports from the authority.
+    /// Wins paid out in those rounds are ranked on the season's own
+    /// leaderboard, which starts empty.
+    pub fn start_season(
+        ctx: Context<StartSeason>,
+        round_count: u64,
+        prize_pool: u64,
+        prize_winners: u8,
+    ) -> Result<()> {
+        require!(
+            round_count > 0 && (1..=Season::MAX_PRIZE_WINNERS).contains(&prize_winners),
+            SolPotError::InvalidSeason
+        );
+        let start_round = ctx.accounts.game_config.round_count;
+        let season = &mut ctx.accounts.season;
+        require!(!season.is_active, SolPotError::SeasonActive);
+
+        season.game_config = ctx.accounts.game_config.key();
+        season.season_id = season
+            .season_id
+            .checked_add(1)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+        season.is_active = true;
+        season.start_round = start_round;
+        season.end_round = start_round
+            .checked_add(round_count)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+        season.prize_pool = prize_pool;
+        season.prize_winners = prize_winners;
+        season.entries = Vec::new();
+        season.bump = ctx.bumps.season;
+        if prize_pool > 0 {
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: season.to_account_info(),
+                    },
+                ),
+                prize_pool,
+            )?;
+        }
+
+        emit!(SeasonStarted {
+            season_id: season.season_id,
+            start_round: season.start_round,
+            end_round: season.end_round,
+            prize_pool,
+            prize_winners,
+        });
+
+        Ok(())
+    }
+
+    /// Closes the running season and splits its prize pool evenly among the
+    /// top `prize_winners` entries of its leaderboard (the first takes any
+    /// remainder). Pass those players' wallets, best first, as writable
+    /// remaining accounts. With no entries the pool goes back to the
+    /// authority. Can be called before `end_round` to cut a season short.
+    pub fn end_season<'info>(ctx: Context<'_, '_, '_, 'info, EndSeason<'info>>) -> Result<()> {
+        let season = &mut ctx.accounts.season;
+        require!(season.is_active, SolPotError::SeasonNotActive);
+
+        let winner_count = std::cmp::min(season.prize_winners as usize, season.entries.len());
+        require!(
+            ctx.remaining_accounts.len() == winner_count,
+            SolPotError::WinnerAccountsMismatch
+        );
+        let winners: Vec<Pubkey> = season.entries[..winner_count]
+            .iter()
+            .map(|e| e.player)
+            .collect();
+        let prize_pool = season.prize_pool;
+        let season_info = season.to_account_info();
+        if winner_count == 0 {
+            move_lamports(
+                &season_info,
+                &ctx.accounts.authority.to_account_info(),
+                prize_pool,
+            )?;
+        } else {
+            let share = prize_pool / winner_count as u64;
+            let first_share = prize_pool - share * (winner_count as u64 - 1);
+            for (index, (info, player)) in ctx.remaining_accounts.iter().zip(&winners).enumerate() {
+                require!(info.is_writable, SolPotError::WinnerAccountsMismatch);
+                require_keys_eq!(info.key(), *player, SolPotError::WinnerAccountsMismatch);
+                let amount = if index == 0 { first_share } else { share };
+                move_lamports(&season_info, info, amount)?;
+            }
+        }
+
+        season.is_active = false;
+        season.prize_pool = 0;
+        season.entries = Vec::new();
+
+        emit!(SeasonEnded {
+            season_id: season.season_id,
+            winners,
+            prize_pool,
+        });
+
+        Ok(())
+    }
+
+    /// Sets how long after a win a player can't win again. Their correct
+    /// guesses in the meantime are recorded but leave the round open, so
+    /// the answer is out in the open for everyone else; commit-reveal
+    /// rounds are the better fit.
+    pub fn update_win_cooldown(
+        ctx: Context<UpdateConfig>,
+        win_cooldown_seconds: i64,
+    ) -> Result<()> {
+        require!(win_cooldown_seconds >= 0, SolPotError::InvalidDuration);
+
+        let game_config = &mut ctx.accounts.game_config;
+        let old_win_cooldown_seconds = game_config.win_cooldown_seconds;
+        game_config.win_cooldown_seconds = win_cooldown_seconds;
+
+        emit!(WinCooldownUpdated {
+            old_win_cooldown_seconds,
+            new_win_cooldown_seconds: win_cooldown_seconds,
+        });
+
+        Ok(())
+    }
+
+    /// Sets how short of its recorded pot a round may be and still pay out.
+    pub fn update_dust_tolerance(
+        ctx: Context<UpdateConfig>,
+        dust_tolerance_lamports: u64,
+    ) -> Result<()> {
+        let game_config = &mut ctx.accounts.game_config;
+        let old_dust_tolerance_lamports = game_config.dust_tolerance_lamports;
+        game_config.dust_tolerance_lamports = dust_tolerance_lamports;
+
+        emit!(DustToleranceUpdated {
+            old_dust_tolerance_lamports,
+            new_dust_tolerance_lamports: dust_tolerance_lamports,
+        });
+
+        Ok(())
+    }
+
+    /// Sets how long a win must stand before `distribute_pot` pays it.
+    pub fn update_dispute_seconds(ctx: Context<UpdateConfig>, dispute_seconds: i64) -> Result<()> {
+        require!(dispute_seconds >= 0, SolPotError::InvalidDuration);
+
+        let game_config = &mut ctx.accounts.game_config;
+        let old_dispute_seconds = game_config.dispute_seconds;
+        game_config.dispute_seconds = dispute_seconds;
+
+        emit!(DisputePeriodUpdated {
+            old_dispute_seconds,
+            new_dispute_seconds: dispute_seconds,
+        });
+
+        Ok(())
+    }
+
+    /// Reallocates the global leaderboard to hold `new_max` entries. The
+    /// authority pays (or is refunded) the rent difference.
+    pub fn resize_leaderboard(ctx: Context<ResizeLeaderboard>, new_max: u32) -> Result<()> {
+        let leaderboard = &mut ctx.accounts.leaderboard;
+        require!(
+            new_max as usize >= leaderboard.entries.len(),
+            SolPotError::CannotShrinkBelowCount
+        );
+
+        let old_capacity = leaderboard.capacity;
+        leaderboard.capacity = new_max;
+
+        emit!(LeaderboardResized {
+            old_capacity,
+            new_capacity: new_max,
+        });
+
+        Ok(())
+    }
+
+    /// Brings a `GameConfig` written by an older build up to the current
+    /// layout: grows it to `GameConfig::SIZE` (the authority pays the extra
+    /// rent), fills in the new fields (see
+    /// `GameConfig::apply_migration_defaults`) and stamps `schema_version`.
+    /// Also creates the game's `FeeVault` and `Jackpot` if they don't exist
+    /// yet, since guessing and payouts need them. Configs already at the
+    /// current version are otherwise left as they are. Run it before
+    /// `migrate_round`, which needs the config to deserialize.
+    pub fn migrate_game_config(ctx: Context<MigrateGameConfig>) -> Result<()> {
+        let info = ctx.accounts.game_config.to_account_info();
+        {
+            let data = info.try_borrow_data()?;
+            require!(
+                *info.owner == crate::ID && data.starts_with(GameConfig::DISCRIMINATOR),
+                SolPotError::NotMigratable
+            );
+            // `authority` is the first field, so it sits at the same offset
+            // in every layout.
+            let authority = data
+                .get(8..40)
+                .and_then(|bytes| Pubkey::try_from(bytes).ok())
+                .ok_or(SolPotError::NotMigratable)?;
+            require_keys_eq!(
+                authority,
+                ctx.accounts.authority.key(),
+                SolPotError::Unauthorized
+            );
+        }
+
+        grow_account(
+            &info,
+            &ctx.accounts.authority,
+            &ctx.accounts.system_program,
+            GameConfig::SIZE,
+        )?;
+        let mut game_config = GameConfig::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+
+        let game_config_key = info.key();
+        let fee_vault_info = ctx.accounts.fee_vault.to_account_info();
+        if *fee_vault_info.owner != crate::ID {
+            let bump = init_pda_account(
+                &ctx.accounts.authority,
+                &fee_vault_info,
+                &ctx.accounts.system_program,
+                &[FeeVault::SEED, game_config_key.as_ref()],
+                FeeVault::SIZE,
+            )?;
+            FeeVault {
+                game_config: game_config_key,
+                accrued_fees: 0,
+                bump,
+            }
+            .try_serialize(&mut &mut fee_vault_info.try_borrow_mut_data()?[..])?;
+        }
+        let jackpot_info = ctx.accounts.jackpot.to_account_info();
+        if *jackpot_info.owner != crate::ID {
+            let bump = init_pda_account(
+                &ctx.accounts.authority,
+                &jackpot_info,
+                &ctx.accounts.system_program,
+                &[Jackpot::SEED, game_config_key.as_ref()],
+                Jackpot::SIZE,
+            )?;
+            Jackpot {
+                game_config: game_config_key,
+                loss_fee_to_jackpot_bps: 0,
+                jackpot_balance: 0,
+                bump,
+            }
+            .try_serialize(&mut &mut jackpot_info.try_borrow_mut_data()?[..])?;
+        }
+
+        let old_schema_version = game_config.schema_version;
+        if old_schema_version >= GameConfig::SCHEMA_VERSION {
+            return Ok(());
+        }
+
+        game_config.apply_migration_defaults(old_schema_version);
+        game_config.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+        emit!(AccountMigrated {
+            account: info.key(),
+            old_schema_version,
+            new_schema_version: GameConfig::SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Brings a `Round` written by an older build up to the current layout:
+    /// grows it to `Round::SIZE` if needed (the authority pays the extra
+    /// rent), fills in the new fields (see `Round::apply_migration_defaults`)
+    /// and stamps `schema_version`. Also creates the round's `GuessLog` and
+    /// `RoundLeaderboard` if it predates them, and counts rounds from before
+    /// `schema_version` existed in the game's `open_rounds`. Rounds already
+    /// at the current version are otherwise left as they are.
+    pub fn migrate_round(ctx: Context<MigrateRound>) -> Result<()> {
+        let info = ctx.accounts.round.to_account_info();
+        require!(
+            *info.owner == crate::ID && info.try_borrow_data()?.starts_with(Round::DISCRIMINATOR),
+            SolPotError::NotMigratable
+        );
+
+        grow_account(
+            &info,
+            &ctx.accounts.authority,
+            &ctx.accounts.system_program,
+            Round::SIZE,
+        )?;
+        let mut round = Round::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        let game_config_key = ctx.accounts.game_config.key();
+        require_keys_eq!(round.game_config, game_config_key, SolPotError::NotMigratable);
+        let expected = Pubkey::create_program_address(
+            &[
+                Round::SEED,
+                game_config_key.as_ref(),
+                &round.id.to_le_bytes(),
+                &[round.bump],
+            ],
+            &crate::ID,
+        )
+        .map_err(|_| SolPotError::NotMigratable)?;
+        require_keys_eq!(info.key(), expected, SolPotError::NotMigratable);
+
+        let round_key = info.key();
+        let guess_log_info = ctx.accounts.guess_log.to_account_info();
+        if *guess_log_info.owner != crate::ID {
+            let bump = init_pda_account(
+                &ctx.accounts.authority,
+                &guess_log_info,
+                &ctx.accounts.system_program,
+                &[GuessLog::SEED, round_key.as_ref()],
+                GuessLog::SIZE,
+            )?;
+            GuessLog {
+                round: round_key,
+                bump,
+                ..Default::default()
+            }
+            .try_serialize(&mut &mut guess_log_info.try_borrow_mut_data()?[..])?;
+        }
+        let round_leaderboard_info = ctx.accounts.round_leaderboard.to_account_info();
+        if *round_leaderboard_info.owner != crate::ID {
+            let bump = init_pda_account(
+                &ctx.accounts.authority,
+                &round_leaderboard_info,
+                &ctx.accounts.system_program,
+                &[RoundLeaderboard::SEED, round_key.as_ref()],
+                RoundLeaderboard::space(round.max_players),
+            )?;
+            RoundLeaderboard {
+                round: round_key,
+                capacity: RoundLeaderboard::capacity_for(round.max_players),
+                bump,
+                ..Default::default()
+            }
+            .try_serialize(&mut &mut round_leaderboard_info.try_borrow_mut_data()?[..])?;
+        }
+
+        let old_schema_version = round.schema_version;
+        if old_schema_version >= Round::SCHEMA_VERSION {
+            return Ok(());
+        }
+
+        round.apply_migration_defaults(old_schema_version);
+        round.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+        // Rounds this old were never counted as open, but `close_round`
+        // still uncounts them.
+        if old_schema_version == 0 {
+            let game_config = &mut ctx.accounts.game_config;
+            game_config.open_rounds = game_config
+                .open_rounds
+                .checked_add(1)
+                .ok_or(SolPotError::ArithmeticOverflow)?;
+        }
+
+        emit!(AccountMigrated {
+            account: info.key(),
+            old_schema_version,
+            new_schema_version: Round::SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Rewrites a leaderboard in the original program's layout (no
+    /// `capacity`, entries without streaks, `last_win_round_id`,
+    /// `last_win_at` or `score`) into the current one, growing it to fit
+    /// (the authority pays the extra rent). The board gets the initial
+    /// capacity, and entries start with no streak or recorded win and are
+    /// scored under the current weights. Leaderboards already on the current layout
+    /// are left as they are. Run it after `migrate_game_config`.
+    pub fn upgrade_leaderboard(ctx: Context<UpgradeLeaderboard>) -> Result<()> {
+        let info = ctx.accounts.leaderboard.to_account_info();
+        let mut leaderboard = {
+            let data = info.try_borrow_data()?;
+            require!(
+                *info.owner == crate::ID && data.starts_with(Leaderboard::DISCRIMINATOR),
+                SolPotError::NotMigratable
+            );
+            if decode_leaderboard(&data, ctx.bumps.leaderboard).is_some() {
+                return Ok(());
+            }
+            decode_legacy_leaderboard(&data, ctx.bumps.leaderboard)
+                .ok_or(SolPotError::NotMigratable)?
+        };
+
+        let game_config = &ctx.accounts.game_config;
+        for entry in leaderboard.entries.iter_mut() {
+            entry.score = game_config.leaderboard_score(entry);
+        }
+        leaderboard.entries.sort_by(|a, b| b.score.cmp(&a.score));
+
+        grow_account(
+            &info,
+            &ctx.accounts.authority,
+            &ctx.accounts.system_program,
+            Leaderboard::space(leaderboard.capacity),
+        )?;
+        leaderboard.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+        emit!(LeaderboardUpgraded {
+            entries: leaderboard.entries.len() as u32,
+        });
+
+        Ok(())
+    }
+
+    /// Creates the next round. The game's current entry fee is snapshotted
+    /// into the round, so later `update_entry_fee` calls don't reprice it.
+    /// A non-zero `allowlist_root` makes the round invite-only. `seed_pot`
+    /// lamports (SOL rounds only) are moved from the authority into the pot
+    /// as a guaranteed minimum prize. A non-zero `dictionary_root` limits
+    /// guesses to a word list. A non-zero `commit_slot` must be a slot that
+    /// hasn't been produced yet and leaves `answer_commitment` empty: the
+    /// commitment is made by `commit_answer` once the slot has passed, with
+    /// a salt derived from its hash (see `reveal_answer`). A `starts_at` in
+    /// the future schedules the round: it takes no entries or guesses until
+    /// then, and `duration_seconds` counts from it. Anything earlier opens
+    /// it at once.
+    pub fn create_round(ctx: Context<CreateRound>, params: CreateRoundParams) -> Result<()> {
+        let CreateRoundParams {
+            word_hash,
+            max_players,
+            duration_seconds,
+            allow_multiple_winners,
+            max_guesses,
+            guess_fee_lamports,
+            reveal_window_seconds,
+            answers_merkle_root,
+            min_players,
+            random_fallback,
+            guess_log_overwrite,
+            gate_mint,
+            word_length,
+            hint_uri,
+            claim_window_seconds,
+            answer_commitment,
+            fee_increment_lamports,
+            max_entry_fee_lamports,
+            guess_cooldown_seconds,
+            normalization_flags,
+            milestone_lamports,
+            vesting_duration_seconds,
+            vesting_cliff_seconds,
+            allowlist_root,
+            seed_pot,
+            dictionary_root,
+            refund_tips,
+            fee_basis_points_override,
+            max_pot_lamports,
+            commit_slot,
+            guess_kind,
+            grace_seconds,
+            anti_snipe_seconds,
+            max_anti_snipe_seconds,
+            charity_wallet,
+            charity_basis_points,
+            nonce,
+            sealed,
+            starts_at,
+        } = params;
+        require!(!ctx.accounts.game_config.paused, SolPotError::GamePaused);
+        if let Some(bps) = fee_basis_points_override {
+            require!(
+                bps <= 1000
+                    && bps as u32 + ctx.accounts.game_config.runner_up_basis_points as u32
+                        <= 10000,
+                SolPotError::InvalidFeeBasisPoints
+            );
+        }
+        ctx.accounts
+            .game_config
+            .validate_round_bounds(max_players, duration_seconds)?;
+        require!(
+            hint_uri.len() <= Round::MAX_HINT_URI_LEN,
+            SolPotError::HintUriTooLong
+        );
+        require!(!sealed || hint_uri.is_empty(), SolPotError::RoundSealed);
+        require!(max_guesses > 0, SolPotError::InvalidMaxGuesses);
+        require!(
+            min_players <= max_players,
+            SolPotError::InvalidMinPlayers
+        );
+        require!(
+            reveal_window_seconds >= 0,
+            SolPotError::InvalidRevealWindow
+        );
+        require!(
+            claim_window_seconds >= 0,
+            SolPotError::InvalidClaimWindow
+        );
+        require!(
+            guess_cooldown_seconds >= 0,
+            SolPotError::InvalidGuessCooldown
+        );
+        require!(grace_seconds >= 0, SolPotError::InvalidGracePeriod);
+        require!(
+            anti_snipe_seconds >= 0 && max_anti_snipe_seconds >= 0,
+            SolPotError::InvalidAntiSnipe
+        );
+        require!(
+            (charity_wallet.is_some() || charity_basis_points == 0)
+                && charity_basis_points as u32
+                    + ctx.accounts.game_config.runner_up_basis_points as u32
+                    <= 10000,
+            SolPotError::InvalidCharityShare
+        );
+        require!(
+            charity_wallet != Some(Pubkey::default()),
+            SolPotError::CharityWalletMismatch
+        );
+        require!(
+            normalization_flags & !Round::NORMALIZE_ALL == 0,
+            SolPotError::InvalidNormalizationFlags
+        );
+        require!(
+            guess_kind <= Round::GUESS_KIND_HASHED,
+            SolPotError::InvalidGuessKind
+        );
+        // `reveal_guess` takes the guess as text, which can't carry a hash.
+        require!(
+            guess_kind != Round::GUESS_KIND_HASHED || reveal_window_seconds == 0,
+            SolPotError::IncompatibleRoundOptions
+        );
+        require!(
+            fee_increment_lamports == 0
+                || max_entry_fee_lamports >= ctx.accounts.game_config.entry_fee_lamports,
+            SolPotError::InvalidFeeCurve
+        );
+        require!(
+            ctx.accounts.winners.is_some() == allow_multiple_winners,
+            SolPotError::WinnersAccountMismatch
+        );
+        require!(
+            ctx.accounts.participants.is_some() == random_fallback,
+            SolPotError::ParticipantsAccountMismatch
+        );
+        if random_fallback {
+            require!(
+                !allow_multiple_winners,
+                SolPotError::IncompatibleRoundOptions
+            );
+            require!(
+                max_players as usize <= Participants::MAX_PLAYERS,
+                SolPotError::MaxPlayersReached
+            );
+        }
+        require!(
+            vesting_duration_seconds >= 0
+                && (0..=vesting_duration_seconds).contains(&vesting_cliff_seconds),
+            SolPotError::InvalidVestingSchedule
+        );
+        let vesting = vesting_duration_seconds > 0;
+        require!(
+            ctx.accounts.prize_vault.is_some() == vesting,
+            SolPotError::PrizeVaultMismatch
+        );
+        require!(
+            !(vesting && allow_multiple_winners),
+            SolPotError::IncompatibleRoundOptions
+        );
+
+        let game_mint = ctx.accounts.game_config.mint;
+        let vault = if game_mint != Pubkey::default() {
+            let mint = ctx
+                .accounts
+                .mint
+                .as_ref()
+                .ok_or(SolPotError::TokenAccountMissing)?;
+            require_keys_eq!(mint.key(), game_mint, SolPotError::TokenMintMismatch);
+            require!(
+                guess_fee_lamports == 0 && !vesting && seed_pot == 0 && charity_wallet.is_none(),
+                SolPotError::UnsupportedForTokenRound
+            );
+            ctx.accounts
+                .vault
+                .as_ref()
+                .ok_or(SolPotError::TokenAccountMissing)?
+                .key()
+        } else {
+            Pubkey::default()
+        };
+
+        ctx.accounts
+            .game_config
+            .require_entry_fee_floor(ctx.accounts.game_config.entry_fee_lamports)?;
+
+        let clock = Clock::get()?;
+        if commit_slot > 0 {
+            require!(
+                commit_slot > clock.slot && answer_commitment == [0u8; 32],
+                SolPotError::FairnessProofInvalid
+            );
+        }
+
+        let starts_at = starts_at.max(clock.unix_timestamp);
+        let game_config = &mut ctx.accounts.game_config;
+        game_config.require_fresh_round_nonce(&nonce, clock.unix_timestamp)?;
+        game_config.last_round_nonce = nonce;
+        game_config.last_round_created_at = clock.unix_timestamp;
+        let round = &mut ctx.accounts.round;
+
+        round.id = game_config.round_count;
+        round.game_config = game_config.key();
+        round.word_hash = word_hash;
+        round.is_active = true;
+        round.winner = Pubkey::default();
+        round.has_winner = false;
+        round.won_at = 0;
+        round.pot_lamports = 0;
+        round.pot_distributed = false;
+        round.nft_minted = false;
+        round.player_count = 0;
+        round.max_players = max_players;
+        round.created_at = clock.unix_timestamp;
+        round.starts_at = starts_at;
+        round.expires_at = starts_at
+            .checked_add(duration_seconds)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+        round.entry_fee_lamports = game_config.entry_fee_lamports;
+        round.bump = ctx.bumps.round;
+        round.allow_multiple_winners = allow_multiple_winners;
+        round.max_guesses = max_guesses;
+        round.guess_fee_lamports = guess_fee_lamports;
+        round.refunded_count = 0;
+        round.reveal_window_seconds = reveal_window_seconds;
+        round.answers_merkle_root = answers_merkle_root;
+        round.mint = game_mint;
+        round.vault = vault;
+        round.min_players = min_players;
+        round.random_fallback = random_fallback;
+        round.first_entrant = Pubkey::default();
+        round.first_entered_at = 0;
+        round.gate_mint = gate_mint.unwrap_or_default();
+        round.word_length = word_length;
+        round.hint_uri = hint_uri;
+        round.claim_window_seconds = claim_window_seconds;
+        round.answer_commitment = answer_commitment;
+        round.answer_revealed = false;
+        round.revealed_answer = String::new();
+        round.title = String::new();
+        round.bonus_word_hash = [0u8; 32];
+        round.is_bonus = false;
+        round.disputed = false;
+        round.cancelled = false;
+        round.live_entries = 0;
+        round.total_guesses = 0;
+        round.correct_guesses = 0;
+        round.fee_increment_lamports = fee_increment_lamports;
+        round.max_entry_fee_lamports = max_entry_fee_lamports;
+        round.guess_cooldown_seconds = guess_cooldown_seconds;
+        round.normalization_flags = normalization_flags;
+        round.generation = 0;
+        round.milestone_lamports = milestone_lamports;
+        round.vesting_duration_seconds = vesting_duration_seconds;
+        round.vesting_cliff_seconds = vesting_cliff_seconds;
+        round.round_authority = Pubkey::default();
+        round.allowlist_root = allowlist_root;
+        round.seeded_lamports = seed_pot;
+        round.dictionary_root = dictionary_root;
+        round.total_tips = 0;
+        round.live_tips = 0;
+        round.refund_tips = refund_tips;
+        round.fee_basis_points_override = fee_basis_points_override;
+        round.refunded_tips = 0;
+        round.schema_version = Round::SCHEMA_VERSION;
+        round.max_pot_lamports = max_pot_lamports;
+        round.commit_slot = commit_slot;
+        round.commit_slot_hash = [0u8; 32];
+        round.guess_kind = guess_kind;
+        round.grace_seconds = grace_seconds;
+        round.anti_snipe_seconds = anti_snipe_seconds;
+        round.max_anti_snipe_seconds = max_anti_snipe_seconds;
+        round.charity_mode = charity_wallet.is_some();
+        round.charity_wallet = charity_wallet.unwrap_or_default();
+        round.charity_basis_points = charity_basis_points;
+        round.creation_nonce = nonce;
+        round.sealed = sealed;
+        require!(round.fits_pot_cap(seed_pot), SolPotError::PotCapReached);
+        if seed_pot > 0 {
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: round.to_account_info(),
+                    },
+                ),
+                seed_pot,
+            )?;
+            round.pot_lamports = seed_pot;
+        }
+
+        if let Some(winners) = ctx.accounts.winners.as_mut() {
+            winners.round = round.key();
+            winners.players = Vec::new();
+            winners.bump = ctx.bumps.winners.unwrap();
+        }
+        if let Some(participants) = ctx.accounts.participants.as_mut() {
+            participants.round = round.key();
+            participants.players = Vec::new();
+            participants.bump = ctx.bumps.participants.unwrap();
+        }
+        if let Some(prize_vault) = ctx.accounts.prize_vault.as_mut() {
+            prize_vault.round = round.key();
+            prize_vault.round_id = round.id;
+            prize_vault.bump = ctx.bumps.prize_vault.unwrap();
+        }
+
+        let guess_log = &mut ctx.accounts.guess_log;
+        guess_log.round = round.key();
+        guess_log.overwrite_oldest = guess_log_overwrite;
+        guess_log.head = 0;
+        guess_log.entries = Vec::new();
+        guess_log.bump = ctx.bumps.guess_log;
+
+        let round_leaderboard = &mut ctx.accounts.round_leaderboard;
+        round_leaderboard.round = round.key();
+        round_leaderboard.capacity = RoundLeaderboard::capacity_for(max_players);
+        round_leaderboard.entries = Vec::new();
+        round_leaderboard.bump = ctx.bumps.round_leaderboard;
+
+        game_config.round_count = game_config
+            .round_count
+            .checked_add(1)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+        game_config.open_rounds = game_config
+            .open_rounds
+            .checked_add(1)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+
+        emit!(RoundCreated {
+            round_id: round.id,
+            entry_fee_lamports: round.entry_fee_lamports,
+            expires_at: round.expires_at,
+            max_players: round.max_players,
+            seeded_lamports: seed_pot,
+            max_pot_lamports: round.max_pot_lamports,
+            guesses_close_at: round.guesses_close_at(),
+        });
+
+        Ok(())
+    }
+
+    /// Creates several plain rounds in one go: single winner, no guess limit
+    /// or fee, no commit-reveal, answer set, gate, hints or claim window.
+    /// `remaining_accounts` carries, per round and in order, the writable
+    /// `Round`, `GuessLog` and `RoundLeaderboard` PDAs. SOL games only.
+    /// `nonce` is the whole batch's retry guard, as in `create_round`.
+    pub fn batch_create_rounds<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchCreateRounds<'info>>,
+        rounds: Vec<RoundParams>,
+        nonce: [u8; 16],
+    ) -> Result<()> {
+        require!(!ctx.accounts.game_config.paused, SolPotError::GamePaused);
+        require!(
+            !rounds.is_empty() && rounds.len() <= RoundParams::MAX_BATCH_LEN,
+            SolPotError::InvalidBatchSize
+        );
+        require!(
+            ctx.remaining_accounts.len() == rounds.len() * 3,
+            SolPotError::BatchAccountsMismatch
+        );
+        require!(
+            ctx.accounts.game_config.mint == Pubkey::default(),
+            SolPotError::UnsupportedForTokenRound
+        );
+
+        let clock = Clock::get()?;
+        let game_config_key = ctx.accounts.game_config.key();
+        let entry_fee_lamports = ctx.accounts.game_config.entry_fee_lamports;
+        ctx.accounts
+            .game_config
+            .require_entry_fee_floor(entry_fee_lamports)?;
+
+        for params in &rounds {
+            ctx.accounts
+                .game_config
+                .validate_round_bounds(params.max_players, params.duration_seconds)?;
+        }
+        let game_config = &mut ctx.accounts.game_config;
+        game_config.require_fresh_round_nonce(&nonce, clock.unix_timestamp)?;
+        game_config.last_round_nonce = nonce;
+        game_config.last_round_created_at = clock.unix_timestamp;
+
+        for (params, accounts) in rounds.iter().zip(ctx.remaining_accounts.chunks(3)) {
+            let [round_info, guess_log_info, round_leaderboard_info] = accounts else {
+                return err!(SolPotError::BatchAccountsMismatch);
+            };
+            let id = ctx.accounts.game_config.round_count;
+            let id_bytes = id.to_le_bytes();
+
+            let round_seeds: &[&[u8]] = &[Round::SEED, game_config_key.as_ref(), &id_bytes];
+            let round_bump = init_pda_account(
+                &ctx.accounts.authority,
+                round_info,
+                &ctx.accounts.system_program,
+                round_seeds,
+                Round::SIZE,
+            )?;
+            let round = Round {
+                id,
+                game_config: game_config_key,
+                word_hash: params.word_hash,
+                is_active: true,
+                max_players: params.max_players,
+                created_at: clock.unix_timestamp,
+                starts_at: clock.unix_timestamp,
+                expires_at: clock
+                    .unix_timestamp
+                    .checked_add(params.duration_seconds)
+                    .ok_or(SolPotError::ArithmeticOverflow)?,
+                entry_fee_lamports,
+                bump: round_bump,
+                max_guesses: u32::MAX,
+                schema_version: Round::SCHEMA_VERSION,
+                creation_nonce: nonce,
+                ..Default::default()
+            };
+            round.try_serialize(&mut &mut round_info.try_borrow_mut_data()?[..])?;
+
+            let round_key = round_info.key();
+            let guess_log_bump = init_pda_account(
+                &ctx.accounts.authority,
+                guess_log_info,
+                &ctx.accounts.system_program,
+                &[GuessLog::SEED, round_key.as_ref()],
+                GuessLog::SIZE,
+            )?;
+            let guess_log = GuessLog {
+                round: round_key,
+                bump: guess_log_bump,
+                ..Default::default()
+            };
+            guess_log.try_serialize(&mut &mut guess_log_info.try_borrow_mut_data()?[..])?;
+
+            let round_leaderboard_bump = init_pda_account(
+                &ctx.accounts.authority,
+                round_leaderboard_info,
+                &ctx.accounts.system_program,
+                &[RoundLeaderboard::SEED, round_key.as_ref()],
+                RoundLeaderboard::space(params.max_players),
+            )?;
+            let round_leaderboard = RoundLeaderboard {
+                round: round_key,
+                capacity: RoundLeaderboard::capacity_for(params.max_players),
+                bump: round_leaderboard_bump,
+                ..Default::default()
+            };
+            round_leaderboard
+                .try_serialize(&mut &mut round_leaderboard_info.try_borrow_mut_data()?[..])?;
+
+            let game_config = &mut ctx.accounts.game_config;
+            game_config.round_count = game_config
+                .round_count
+                .checked_add(1)
+                .ok_or(SolPotError::ArithmeticOverflow)?;
+            game_config.open_rounds = game_config
+                .open_rounds
+                .checked_add(1)
+                .ok_or(SolPotError::ArithmeticOverflow)?;
+
+            emit!(RoundCreated {
+                round_id: round.id,
+                entry_fee_lamports: round.entry_fee_lamports,
+                expires_at: round.expires_at,
+                max_players: round.max_players,
+                seeded_lamports: 0,
+                max_pot_lamports: 0,
+                guesses_close_at: round.guesses_close_at(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// `expected_fee` must match the round's current entry fee (see
+    /// `Round::current_entry_fee`), so a client can't be charged an amount it
+    /// didn't display. With a `referrer`, the game's referral share of the
+    /// fee goes to them instead of the pot and is not refundable. Referrals
+    /// are SOL-only. Passing the game `leaderboard` claims the loyalty
+    /// discount, which requires the player to have a win on it; the
+    /// expected fee is then the discounted one. `allowlist_proof` is only
+    /// used by invite-only rounds and may be empty otherwise. A signing
+    /// `rent_sponsor` pays the `PlayerEntry` rent; the player pays it
+    /// otherwise.
+    pub fn enter_round(
+        ctx: Context<EnterRound>,
+        expected_fee: u64,
+        referrer: Option<Pubkey>,
+        allowlist_proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        require!(!ctx.accounts.game_config.paused, SolPotError::GamePaused);
+        require!(
+            *ctx.accounts.player_entry.owner != crate::ID,
+            SolPotError::AlreadyEntered
+        );
+
+        let round = &mut ctx.accounts.round;
+        require!(!round.cancelled, SolPotError::RoundCancelled);
+        require!(
+            round.commit_slot == 0 || round.has_answer_commitment(),
+            SolPotError::NoAnswerCommitment
+        );
+        // A won round is inactive too; report the more useful of the two.
+        require!(!round.has_winner, SolPotError::RoundAlreadyWon);
+        require!(round.is_active, SolPotError::RoundNotActive);
+        require_allowlisted(round, &ctx.accounts.player.key(), &allowlist_proof)?;
+        require_not_blacklisted(&ctx.accounts.blacklist, &ctx.accounts.player.key())?;
+        let list_fee = round.current_entry_fee();
+        let loyalty_discount = match ctx.accounts.leaderboard.as_ref() {
+            Some(leaderboard) => {
+                let player = ctx.accounts.player.key();
+                require!(
+                    leaderboard
+                        .entries
+                        .iter()
+                        .any(|e| e.player == player && e.wins > 0),
+                    SolPotError::LoyaltyNotEarned
+                );
+                list_fee
+                    .checked_mul(ctx.accounts.game_config.loyalty_discount_bps as u64)
+                    .and_then(|v| v.checked_div(10000))
+                    .ok_or(SolPotError::ArithmeticOverflow)?
+            }
+            None => 0,
+        };
+        let entry_fee = list_fee - loyalty_discount;
+        require!(expected_fee == entry_fee, SolPotError::EntryFeeMismatch);
+
+        require!(
+            round.player_count < round.max_players,
+            SolPotError::MaxPlayersReached
+        );
+
+        let clock = Clock::get()?;
+        require!(
+            round.has_started(clock.unix_timestamp),
+            SolPotError::RoundNotStarted
+        );
+        require!(
+            clock.unix_timestamp < round.expires_at,
+            SolPotError::RoundExpired
+        );
+
+        if round.is_gated() {
+            let gate_token_account = ctx
+                .accounts
+                .gate_token_account
+                .as_ref()
+                .ok_or(SolPotError::EntryGateNotSatisfied)?;
+            require!(
+                gate_token_account.owner == ctx.accounts.player.key()
+                    && gate_token_account.mint == round.gate_mint
+                    && gate_token_account.amount > 0,
+                SolPotError::EntryGateNotSatisfied
+            );
+        }
+
+        let mut pot_share = entry_fee;
+        if let Some(referrer) = referrer {
+            require_keys_neq!(
+                referrer,
+                ctx.accounts.player.key(),
+                SolPotError::SelfReferral
+            );
+            require!(
+                !round.is_token_round(),
+                SolPotError::UnsupportedForTokenRound
+            );
+            let referrer_account = ctx
+                .accounts
+                .referrer_account
+                .as_ref()
+                .ok_or(SolPotError::ReferrerAccountMissing)?;
+            let referral_stats = ctx
+                .accounts
+                .referral_stats
+                .as_ref()
+                .ok_or(SolPotError::ReferrerAccountMissing)?;
+
+            let cut = entry_fee
+                .checked_mul(ctx.accounts.game_config.referral_basis_points as u64)
+                .and_then(|v| v.checked_div(10000))
+                .ok_or(SolPotError::ArithmeticOverflow)?;
+            if cut > 0 {
+                transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        Transfer {
+                            from: ctx.accounts.player.to_account_info(),
+                            to: referrer_account.to_account_info(),
+                        },
+                    ),
+                    cut,
+                )?;
+            }
+            record_referral(
+                &ctx.accounts.player,
+                referral_stats,
+                &ctx.accounts.system_program,
+                referrer,
+                cut,
+            )?;
+            pot_share -= cut;
+
+            emit!(ReferralPaid {
+                round_id: round.id,
+                player: ctx.accounts.player.key(),
+                referrer,
+                amount: cut,
+            });
+        }
+        require!(round.fits_pot_cap(pot_share), SolPotError::PotCapReached);
+
+        if round.is_token_round() {
+            let player_token_account = ctx
+                .accounts
+                .player_token_account
+                .as_ref()
+                .ok_or(SolPotError::TokenAccountMissing)?;
+            let vault = ctx
+                .accounts
+                .vault
+                .as_ref()
+                .ok_or(SolPotError::TokenAccountMissing)?;
+            let token_program = ctx
+                .accounts
+                .token_program
+                .as_ref()
+                .ok_or(SolPotError::TokenAccountMissing)?;
+            token::transfer(
+                CpiContext::new(
+                    token_program.to_account_info(),
+                    token::Transfer {
+                        from: player_token_account.to_account_info(),
+                        to: vault.to_account_info(),
+                        authority: ctx.accounts.player.to_account_info(),
+                    },
+                ),
+                entry_fee,
+            )?;
+        } else {
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.player.to_account_info(),
+                        to: round.to_account_info(),
+                    },
+                ),
+                pot_share,
+            )?;
+        }
+
+        if round.player_count == 0 {
+            round.first_entrant = ctx.accounts.player.key();
+            round.first_entered_at = clock.unix_timestamp;
+        }
+        round.add_to_pot(pot_share)?;
+        round.player_count = round
+            .player_count
+            .checked_add(1)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+        round.live_entries = round
+            .live_entries
+            .checked_add(1)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+        if let Some(old_expires_at) = round.apply_anti_snipe(clock.unix_timestamp) {
+            emit!(RoundExtended {
+                round_id: round.id,
+                old_expires_at,
+                new_expires_at: round.expires_at,
+            });
+        }
+
+        let game_config = &mut ctx.accounts.game_config;
+        game_config.total_wagered = game_config
+            .total_wagered
+            .checked_add(entry_fee)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+
+        let round_key = ctx.accounts.round.key();
+        let player_key = ctx.accounts.player.key();
+        let player_entry = ctx.accounts.player_entry.to_account_info();
+        let bump = init_pda_account(
+            ctx.accounts
+                .rent_sponsor
+                .as_ref()
+                .unwrap_or(&ctx.accounts.player),
+            &player_entry,
+            &ctx.accounts.system_program,
+            &[
+                PlayerEntry::SEED,
+                round_key.as_ref(),
+                &ctx.accounts.round.generation.to_le_bytes(),
+                player_key.as_ref(),
+            ],
+            PlayerEntry::SIZE,
+        )?;
+        PlayerEntry {
+            player: player_key,
+            round: round_key,
+            entered_at: clock.unix_timestamp,
+            refunded: false,
+            bump,
+            amount_paid: pot_share,
+        }
+        .try_serialize(&mut &mut player_entry.try_borrow_mut_data()?[..])?;
+
+        let player_profile = &mut ctx.accounts.player_profile;
+        player_profile.game_config = ctx.accounts.game_config.key();
+        player_profile.player = ctx.accounts.player.key();
+        player_profile.bump = ctx.bumps.player_profile;
+        player_profile.rounds_entered = player_profile
+            .rounds_entered
+            .checked_add(1)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+        player_profile.total_wagered = player_profile
+            .total_wagered
+            .checked_add(entry_fee)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+
+        if ctx.accounts.round.random_fallback {
+            let participants = ctx
+                .accounts
+                .participants
+                .as_mut()
+                .ok_or(SolPotError::ParticipantsAccountMismatch)?;
+            participants.players.push(ctx.accounts.player.key());
+        }
+        ctx.accounts
+            .round_leaderboard
+            .add_entrant(ctx.accounts.player.key());
+
+        if loyalty_discount > 0 {
+            emit!(LoyaltyDiscountApplied {
+                round_id: ctx.accounts.round.id,
+                player: ctx.accounts.player.key(),
+                list_fee,
+                discount: loyalty_discount,
+            });
+        }
+
+        emit!(PlayerEntered {
+            round_id: ctx.accounts.round.id,
+            player: ctx.accounts.player.key(),
+            pot_lamports: ctx.accounts.round.pot_lamports,
+            player_count: ctx.accounts.round.player_count,
+            entry_fee_charged: entry_fee,
+            next_entry_fee: ctx.accounts.round.current_entry_fee(),
+        });
+
+        Ok(())
+    }
+
+    /// Lets anyone grow an open SOL round's pot without entering it. Each
+    /// tipper's total is kept in a `TipRecord` for `reclaim_tip`.
+    pub fn tip_round(ctx: Context<TipRound>, amount: u64) -> Result<()> {
+        require!(!ctx.accounts.game_config.paused, SolPotError::GamePaused);
+        require!(amount > 0, SolPotError::InvalidTipAmount);
+
+        let clock = Clock::get()?;
+        let round = &mut ctx.accounts.round;
+        require!(!round.cancelled, SolPotError::RoundCancelled);
+        require!(!round.has_winner, SolPotError::RoundAlreadyWon);
+        require!(round.is_active, SolPotError::RoundNotActive);
+        require!(clock.unix_timestamp < round.expires_at, SolPotError::RoundExpired);
+        require!(!round.is_token_round(), SolPotError::UnsupportedForTokenRound);
+        require!(round.fits_pot_cap(amount), SolPotError::PotCapReached);
+
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.tipper.to_account_info(),
+                    to: round.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        round.add_to_pot(amount)?;
+        round.total_tips = round
+            .total_tips
+            .checked_add(amount)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+
+        let tip_record = &mut ctx.accounts.tip_record;
+        if tip_record.amount == 0 {
+            round.live_tips = round
+                .live_tips
+                .checked_add(1)
+                .ok_or(SolPotError::ArithmeticOverflow)?;
+        }
+        tip_record.round = round.key();
+        tip_record.tipper = ctx.accounts.tipper.key();
+        tip_record.bump = ctx.bumps.tip_record;
+        tip_record.amount = tip_record
+            .amount
+            .checked_add(amount)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+
+        emit!(RoundTipped {
+            round_id: round.id,
+            tipper: ctx.accounts.tipper.key(),
+            amount,
+            total_tips: round.total_tips,
+            pot_lamports: round.pot_lamports,
+        });
+
+        Ok(())
+    }
+
+    /// Lets the game authority sweeten an open SOL round's pot after
+    /// creation. Unlike a tip, a bounty is kept apart from player money: if
+    /// the round goes unsolved it goes back to the authority, like the
+    /// creation seed, when the round is closed. Only the game authority,
+    /// who receives that refund, can add one, not a round delegate.
+    pub fn add_bounty(ctx: Context<AddBounty>, amount: u64) -> Result<()> {
+        require!(!ctx.accounts.game_config.paused, SolPotError::GamePaused);
+        require!(amount > 0, SolPotError::InvalidBountyAmount);
+
+        let clock = Clock::get()?;
+        let round = &mut ctx.accounts.round;
+        require!(!round.cancelled, SolPotError::RoundCancelled);
+        require!(!round.has_winner, SolPotError::RoundAlreadyWon);
+        require!(round.is_active, SolPotError::RoundNotActive);
+        require!(clock.unix_timestamp < round.expires_at, SolPotError::RoundExpired);
+        require!(!round.is_token_round(), SolPotError::UnsupportedForTokenRound);
+        require!(round.fits_pot_cap(amount), SolPotError::PotCapReached);
+
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: round.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        round.add_to_pot(amount)?;
+        round.bounty_lamports = round
+            .bounty_lamports
+            .checked_add(amount)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+
+        emit!(BountyAdded {
+            round_id: round.id,
+            amount,
+            bounty_lamports: round.bounty_lamports,
+            pot_lamports: round.pot_lamports,
+        });
+
+        Ok(())
+    }
+
+    /// `proof` is only used by rounds with an `answers_merkle_root` and may be
+    /// empty otherwise; likewise `allowlist_proof` for invite-only rounds and
+    /// `dictionary_proof` for rounds with a `dictionary_root`. A guess outside
+    /// the dictionary fails before it is charged or counted. Returns the
+    /// `GuessOutcome` as return data for CPI callers.
+    pub fn submit_guess(
+        ctx: Context<SubmitGuess>,
+        guess: String,
+        proof: Vec<[u8; 32]>,
+        allowlist_proof: Vec<[u8; 32]>,
+        dictionary_proof: Vec<[u8; 32]>,
+    ) -> Result<GuessOutcome> {
+        process_guess(
+            ctx,
+            guess.into_bytes(),
+            proof,
+            allowlist_proof,
+            dictionary_proof,
+            String::new(),
+        )
+    }
+
+    /// `submit_guess` for rounds whose answer isn't text: `guess` is
+    /// canonicalized according to the round's `guess_kind` before hashing,
+    /// so e.g. a number guess hashes the same whatever its byte width.
+    pub fn submit_guess_bytes(
+        ctx: Context<SubmitGuess>,
+        guess: Vec<u8>,
+        proof: Vec<[u8; 32]>,
+        allowlist_proof: Vec<[u8; 32]>,
+        dictionary_proof: Vec<[u8; 32]>,
+    ) -> Result<GuessOutcome> {
+        process_guess(
+            ctx,
+            guess,
+            proof,
+            allowlist_proof,
+            dictionary_proof,
+            String::new(),
+        )
+    }
+
+    /// `submit_guess` for `GUESS_KIND_HASHED` rounds: the client normalizes
+    /// and hashes its guess itself and sends only the 32-byte hash, which is
+    /// hashed once more and compared with `word_hash` (= `hash(hash(answer))`).
+    /// A long answer's plaintext never appears in the transaction, and the
+    /// program skips normalizing and hashing it. The extra hash is what
+    /// keeps this safe: `word_hash` is public, so comparing the submitted
+    /// hash with it directly would hand the pot to anyone who copied it.
+    ///
+    /// The tradeoff is that the program can't see what was guessed:
+    /// normalization is up to the client, so "Sol" and "sol" only match if
+    /// the frontend folds them the same way, and the guess log holds hashes
+    /// nobody can read back. A client that hashes wrongly only loses its own
+    /// attempt. Rounds where equivalent spellings must be judged the same
+    /// for everyone should keep the default string kind and `submit_guess`.
+    pub fn submit_guess_hashed(
+        ctx: Context<SubmitGuess>,
+        guess_hash: [u8; 32],
+        proof: Vec<[u8; 32]>,
+        allowlist_proof: Vec<[u8; 32]>,
+        dictionary_proof: Vec<[u8; 32]>,
+    ) -> Result<GuessOutcome> {
+        require!(
+            ctx.accounts.round.guess_kind == Round::GUESS_KIND_HASHED,
+            SolPotError::InvalidGuessEncoding
+        );
+        process_guess(
+            ctx,
+            guess_hash.to_vec(),
+            proof,
+            allowlist_proof,
+            dictionary_proof,
+            String::new(),
+        )
+    }
+
+    /// `submit_guess` with a short public note carried in the `GuessResult`
+    /// event for the frontend's feed. The memo is never stored. It is capped
+    /// at `GuessResult::MAX_MEMO_LEN` bytes and control characters are
+    /// stripped.
+    pub fn submit_guess_with_memo(
+        ctx: Context<SubmitGuess>,
+        guess: String,
+        proof: Vec<[u8; 32]>,
+        allowlist_proof: Vec<[u8; 32]>,
+        dictionary_proof: Vec<[u8; 32]>,
+        memo: String,
+    ) -> Result<GuessOutcome> {
+        require!(
+            memo.len() <= GuessResult::MAX_MEMO_LEN,
+            SolPotError::MemoTooLong
+        );
+        process_guess(
+            ctx,
+            guess.into_bytes(),
+            proof,
+            allowlist_proof,
+            dictionary_proof,
+            sanitize_memo(&memo),
+        )
+    }
+
+    /// First half of commit-reveal guessing: stores `hash(guess || salt ||
+    /// player)` so the plaintext guess never hits the mempool before the
+    /// attempt is locked in. Counts against the guess limit and pays the
+    /// guess fee. A signing `rent_sponsor` pays the `GuessRecord` rent on
+    /// the player's first attempt, as in `submit_guess`.
+    pub fn commit_guess(ctx: Context<CommitGuess>, commitment: [u8; 32]) -> Result<()> {
+        require!(!ctx.accounts.game_config.paused, SolPotError::GamePaused);
+
+        let clock = Clock::get()?;
+        let round = &mut ctx.accounts.round;
+
+        require!(
+            round.reveal_window_seconds > 0,
+            SolPotError::CommitRevealDisabled
+        );
+        require_guessing_open(round, clock.unix_timestamp)?;
+        require_not_blacklisted(&ctx.accounts.blacklist, &ctx.accounts.player.key())?;
+
+        let round_key = round.key();
+        let player_key = ctx.accounts.player.key();
+        let guess_record_info = ctx.accounts.guess_record.to_account_info();
+        let mut guess_record = load_guess_record(
+            &guess_record_info,
+            ctx.accounts
+                .rent_sponsor
+                .as_ref()
+                .unwrap_or(&ctx.accounts.player),
+            &ctx.accounts.system_program,
+            &[
+                GuessRecord::SEED,
+                round_key.as_ref(),
+                &round.generation.to_le_bytes(),
+                player_key.as_ref(),
+            ],
+        )?;
+        let previous_lapsed = guess_record
+            .committed_at
+            .checked_add(round.reveal_window_seconds)
+            .is_some_and(|deadline| clock.unix_timestamp > deadline);
+        require!(
+            guess_record.revealed || guess_record.commitment == [0u8; 32] || previous_lapsed,
+            SolPotError::PendingCommitment
+        );
+
+        let attempt = record_attempt(
+            round,
+            &mut guess_record,
+            &ctx.accounts.player,
+            &ctx.accounts.system_program,
+            clock.unix_timestamp,
+        )?;
+
+        guess_record.commitment = commitment;
+        guess_record.revealed = false;
+        guess_record.committed_at = clock.unix_timestamp;
+        guess_record.try_serialize(&mut &mut guess_record_info.try_borrow_mut_data()?[..])?;
+
+        ctx.accounts
+            .round_leaderboard
+            .record_guess(ctx.accounts.player.key(), attempt);
+
+        emit!(GuessCommitted {
+            round_id: round.id,
+            player: ctx.accounts.player.key(),
+            attempt,
+        });
+
+        Ok(())
+    }
+
+    /// Second half of commit-reveal guessing. May land after the round's
+    /// expiry as long as it is within the commitment's reveal window. Still
+    /// allowed while the game is paused so existing commitments can settle.
+    /// In single-winner rounds a correct reveal of a commitment older than
+    /// the winner's takes the win over; see `settle_reveal`.
+    pub fn reveal_guess(
+        ctx: Context<RevealGuess>,
+        guess: String,
+        salt: [u8; 16],
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        let player = ctx.accounts.player.key();
+        let round = &mut ctx.accounts.round;
+        let guess_record = &mut ctx.accounts.guess_record;
+
+        require!(!round.cancelled, SolPotError::RoundCancelled);
+        require_not_blacklisted(&ctx.accounts.blacklist, &player)?;
+        require!(
+            !guess_record.revealed && guess_record.commitment != [0u8; 32],
+            SolPotError::NoCommitment
+        );
+        let reveal_deadline = guess_record
+            .committed_at
+            .checked_add(round.reveal_window_seconds)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+        require!(
+            clock.unix_timestamp <= reveal_deadline,
+            SolPotError::RevealWindowClosed
+        );
+
+        let expected = hashv(&[guess.as_bytes(), salt.as_ref(), player.as_ref()]);
+        require!(
+            expected.to_bytes() == guess_record.commitment,
+            SolPotError::CommitmentMismatch
+        );
+        guess_record.revealed = true;
+
+        let player_profile = &mut ctx.accounts.player_profile;
+        player_profile.game_config = ctx.accounts.game_config.key();
+        player_profile.player = player;
+        player_profile.bump = ctx.bumps.player_profile;
+
+        let win_blocked = win_cooldown_active(
+            &ctx.accounts.game_config,
+            ctx.accounts.leaderboard.as_deref(),
+            &ctx.accounts.player_profile,
+            &player,
+            guess_record.committed_at,
+        )?;
+        let is_correct = match settle_reveal(
+            round,
+            ctx.accounts.winners.as_deref_mut(),
+            Some(&mut ctx.accounts.player_profile),
+            player,
+            guess.as_bytes(),
+            &proof,
+            guess_record.committed_at,
+            win_blocked,
+        )? {
+            RevealOutcome::Settled {
+                is_correct,
+                displaced_winner,
+            } => {
+                if let Some(previous_winner) = displaced_winner {
+                    ctx.accounts.round_leaderboard.remove_win(previous_winner);
+                    emit!(WinnerReplaced {
+                        round_id: round.id,
+                        previous_winner,
+                        winner: player,
+                        committed_at: guess_record.committed_at,
+                    });
+                }
+                is_correct
+            }
+            RevealOutcome::LaterCommitment => {
+                emit!(LaterCommitment {
+                    round_id: round.id,
+                    player,
+                    committed_at: guess_record.committed_at,
+                    winner_committed_at: round.won_at,
+                });
+                return Ok(());
+            }
+        };
+        if is_correct {
+            if !win_blocked {
+                ctx.accounts
+                    .round_leaderboard
+                    .record_win(player, guess_record.count);
+            }
+        } else {
+            ctx.accounts.guess_log.append(GuessLogEntry {
+                player,
+                guess_hash: answer_hash(round, guess.as_bytes())?,
+                timestamp: clock.unix_timestamp,
+            });
+            divert_loss_fee(round, &mut ctx.accounts.jackpot, player)?;
+        }
+
+        emit!(GuessResult {
+            round_id: round.id,
+            player,
+            is_correct,
+            attempt: guess_record.count,
+            slot: clock.slot,
+            total_guesses: round.total_guesses,
+            correct_guesses: round.correct_guesses,
+            win_cooldown_active: is_correct && win_blocked,
+            memo: String::new(),
+        });
+
+        Ok(())
+    }
+
+    /// Pays out a won round. In multi-winner rounds `winner` must be the first
+    /// recorded winner and the remaining winners are passed, in order, as
+    /// writable `remaining_accounts`, followed by their `PlayerProfile` PDAs
+    /// in the same order; any lamport remainder of the even split goes to
+    /// the first winner. The fee and runner-up share truncate, which
+    /// favors the winners; anything in the round beyond the pot (stray
+    /// transfers, dust) goes to the fee side, so a SOL round is left holding
+    /// exactly its rent-exempt minimum. When the game has a runner-up share and the
+    /// round's earliest entrant didn't win, `runner_up` (or its token account)
+    /// must be passed and is paid before the winners.
+    ///
+    /// With `auto_close` (SOL rounds only) the authority co-signs and the
+    /// round account is closed right after the payout, its rent going to the
+    /// authority. Like `close_round` it needs every `PlayerEntry` and
+    /// `TipRecord` reclaimed first. The winner can no longer mint a reward NFT afterwards.
+    pub fn distribute_pot<'info>(
+        ctx: Context<'_, '_, '_, 'info, DistributePot<'info>>,
+        auto_close: bool,
+    ) -> Result<()> {
+        pay_out_pot(
+            ctx.accounts,
+            ctx.bumps.winner_profile,
+            ctx.remaining_accounts,
+            auto_close,
+        )
+    }
+
+    /// Double or nothing: instead of `distribute_pot`, the winner of a SOL
+    /// round with a committed bonus word stakes their prize (after the house
+    /// fee) on one guess in a new single-player round. The fee vault matches
+    /// the stake, so a correct guess pays double, fee-free, through the usual
+    /// `submit_guess` and `distribute_pot`; a wrong one leaves the pot to be
+    /// swept to the fee vault by `close_round` once the bonus round expires.
+    /// The winner is entered for free and pays the new accounts' rent.
+    pub fn spawn_bonus_round(ctx: Context<SpawnBonusRound>) -> Result<()> {
+        require!(!ctx.accounts.game_config.paused, SolPotError::GamePaused);
+
+        let clock = Clock::get()?;
+        let round = &ctx.accounts.round;
+        let winner = ctx.accounts.winner.key();
+        require!(
+            round.has_winner && round.winner == winner,
+            SolPotError::BonusOnlyForWinner
+        );
+        require!(
+            round.bonus_word_hash != [0u8; 32],
+            SolPotError::BonusNotOffered
+        );
+        require!(
+            !round.pot_distributed,
+            SolPotError::PotAlreadyDistributed
+        );
+        require!(!round.is_underfilled(), SolPotError::MinPlayersNotMet);
+        require!(round.won_before_expiry(), SolPotError::RoundExpired);
+        require!(
+            clock.unix_timestamp
+                >= round
+                    .won_at
+                    .saturating_add(ctx.accounts.game_config.dispute_seconds),
+            SolPotError::DisputePeriodActive
+        );
+        require!(
+            round.win_is_final(clock.unix_timestamp),
+            SolPotError::RoundNotExpired
+        );
+        require!(
+            !round.is_token_round(),
+            SolPotError::UnsupportedForTokenRound
+        );
+        // Only plain single-winner payouts can be staked whole.
+        let pays_runner_up = ctx.accounts.game_config.runner_up_basis_points > 0
+            && round.first_entrant != Pubkey::default()
+            && round.first_entrant != winner;
+        require!(
+            !round.allow_multiple_winners
+                && !round.is_vesting()
+                && !pays_runner_up
+                && !round.charity_mode,
+            SolPotError::IncompatibleRoundOptions
+        );
+
+        let round_info = round.to_account_info();
+        let available = available_lamports(&round_info)?;
+        require_distributable(
+            round.pot_lamports,
+            available,
+            ctx.accounts.game_config.dust_tolerance_lamports,
+        )?;
+        let PotSplit {
+            fee,
+            winner_amount: stake,
+            ..
+        } = split_pot(
+            round.pot_lamports,
+            available,
+            round.fee_basis_points(ctx.accounts.game_config.fee_basis_points),
+            0,
+            1,
+        )
+        .ok_or(SolPotError::ArithmeticOverflow)?;
+
+        let fee_vault = &mut ctx.accounts.fee_vault;
+        move_lamports(&round_info, &fee_vault.to_account_info(), fee)?;
+        fee_vault.accrued_fees = fee_vault
+            .accrued_fees
+            .checked_add(fee)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+        require!(
+            fee_vault.accrued_fees >= stake,
+            SolPotError::InsufficientAccruedFees
+        );
+        fee_vault.accrued_fees = fee_vault
+            .accrued_fees
+            .checked_sub(stake)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+
+        let bonus_info = ctx.accounts.bonus_round.to_account_info();
+        move_lamports(&round_info, &bonus_info, stake)?;
+        move_lamports(&fee_vault.to_account_info(), &bonus_info, stake)?;
+        let pot_lamports = stake
+            .checked_mul(2)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+
+        let round_id = round.id;
+        let won_at = round.won_at;
+        let bonus_word_hash = round.bonus_word_hash;
+        let normalization_flags = round.normalization_flags;
+        let round_authority = round.round_authority;
+
+        let round = &mut ctx.accounts.round;
+        round.pot_distributed = true;
+        round.pot_lamports = 0;
+        ctx.accounts.leaderboard.record_win(
+            winner,
+            stake,
+            round_id,
+            won_at,
+            &ctx.accounts.game_config,
+        )?;
+
+        let game_config = &mut ctx.accounts.game_config;
+        let expires_at = clock
+            .unix_timestamp
+            .checked_add(Round::BONUS_DURATION_SECONDS)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+        let bonus_round = &mut ctx.accounts.bonus_round;
+        bonus_round.set_inner(Round {
+            id: game_config.round_count,
+            game_config: game_config.key(),
+            word_hash: bonus_word_hash,
+            is_active: true,
+            pot_lamports,
+            player_count: 1,
+            live_entries: 1,
+            max_players: 1,
+            created_at: clock.unix_timestamp,
+            starts_at: clock.unix_timestamp,
+            expires_at,
+            bump: ctx.bumps.bonus_round,
+            max_guesses: 1,
+            first_entrant: winner,
+            first_entered_at: clock.unix_timestamp,
+            normalization_flags,
+            round_authority,
+            is_bonus: true,
+            schema_version: Round::SCHEMA_VERSION,
+            ..Default::default()
+        });
+        let bonus_round_id = bonus_round.id;
+        let bonus_round_key = bonus_round.key();
+
+        game_config.round_count = game_config
+            .round_count
+            .checked_add(1)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+        game_config.open_rounds = game_config
+            .open_rounds
+            .checked_add(1)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+        game_config.total_fees = game_config
+            .total_fees
+            .checked_add(fee)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+
+        // A free entry: nothing to refund if the bonus guess misses.
+        let player_entry = &mut ctx.accounts.player_entry;
+        player_entry.player = winner;
+        player_entry.round = bonus_round_key;
+        player_entry.entered_at = clock.unix_timestamp;
+        player_entry.refunded = false;
+        player_entry.bump = ctx.bumps.player_entry;
+        player_entry.amount_paid = 0;
+
+        let guess_log = &mut ctx.accounts.guess_log;
+        guess_log.round = bonus_round_key;
+        guess_log.bump = ctx.bumps.guess_log;
+
+        let round_leaderboard = &mut ctx.accounts.round_leaderboard;
+        round_leaderboard.round = bonus_round_key;
+        round_leaderboard.capacity = RoundLeaderboard::capacity_for(1);
+        round_leaderboard.bump = ctx.bumps.round_leaderboard;
+        round_leaderboard.add_entrant(winner);
+
+        emit!(BonusRoundSpawned {
+            parent_round_id: round_id,
+            bonus_round_id,
+            winner,
+            stake,
+            pot_lamports,
+            expires_at,
+        });
+
+        Ok(())
+    }
+
+    /// Picks a winner for an unsolved random-fallback round from the slot
+    /// hash of the most recent slot, the round id and the round key. This is
+    /// not a true VRF: the current leader has some influence over slot hashes,
+    /// so it's gated behind the authority rather than being a public crank.
+    pub fn draw_random_winner(ctx: Context<DrawRandomWinner>) -> Result<()> {
+        let clock = Clock::get()?;
+        let round = &ctx.accounts.round;
+
+        require!(round.random_fallback, SolPotError::RoundNotActive);
+        require!(!round.cancelled, SolPotError::RoundCancelled);
+        require!(!round.has_winner, SolPotError::RoundAlreadyWon);
+        require!(
+            !round.pot_distributed,
+            SolPotError::PotAlreadyDistributed
+        );
+        require!(
+            clock.unix_timestamp >= round.guessing_ends_at(),
+            SolPotError::RoundNotExpired
+        );
+        require!(!round.is_underfilled(), SolPotError::MinPlayersNotMet);
+        require!(!round.disputed, SolPotError::RoundDisputed);
+        require!(round.answer_settled(), SolPotError::AnswerNotRevealed);
+
+        let players = &ctx.accounts.participants.players;
+        require!(!players.is_empty(), SolPotError::NoParticipants);
+
+        // SlotHashes is too large to deserialize on-chain; the most recent
+        // entry sits right after the u64 vec length and its u64 slot.
+        let slot_hashes_data = ctx.accounts.slot_hashes.try_borrow_data()?;
+        let mut slot_hash = [0u8; 32];
+        slot_hash.copy_from_slice(&slot_hashes_data[16..48]);
+        drop(slot_hashes_data);
+
+        let seed = hashv(&[
+            slot_hash.as_ref(),
+            round.id.to_le_bytes().as_ref(),
+            round.key().as_ref(),
+        ])
+        .to_bytes();
+        let mut seed_prefix = [0u8; 8];
+        seed_prefix.copy_from_slice(&seed[..8]);
+        let index = (u64::from_le_bytes(seed_prefix) % players.len() as u64) as usize;
+        let winner = players[index];
+
+        let round = &mut ctx.accounts.round;
+        round.winner = winner;
+        round.has_winner = true;
+        round.won_at = round.expires_at;
+        round.is_active = false;
+
+        emit!(RandomWinnerDrawn {
+            round_id: round.id,
+            winner,
+            index: index as u32,
+            slot_hash,
+        });
+
+        Ok(())
+    }
+
+    /// `name` and `uri` must follow the game's metadata template, if it has
+    /// one. `seller_fee_basis_points` adds a `Royalties` plugin paying the
+    /// game authority; `attributes` adds an `Attributes` plugin. Either is
+    /// left off when zero / empty.
+    pub fn mint_reward_nft(
+        ctx: Context<MintRewardNft>,
+        name: String,
+        uri: String,
+        seller_fee_basis_points: u16,
+        attributes: Vec<NftAttribute>,
+    ) -> Result<()> {
+        let accounts = RewardAssetAccounts {
+            asset: ctx.accounts.asset.to_account_info(),
+            payer: ctx.accounts.payer.to_account_info(),
+            winner: ctx.accounts.winner.to_account_info(),
+            collection: ctx
+                .accounts
+                .collection
+                .as_ref()
+                .map(|c| c.to_account_info()),
+            collection_authority: ctx
+                .accounts
+                .collection_authority
+                .as_ref()
+                .map(|c| c.to_account_info()),
+            mpl_core_program: ctx.accounts.mpl_core_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        };
+        mint_reward_asset(
+            &ctx.accounts.game_config,
+            &mut ctx.accounts.round,
+            accounts,
+            &name,
+            &uri,
+            seller_fee_basis_points,
+            &attributes,
+        )
+    }
+
+    /// `distribute_pot` (without `auto_close`) followed by `mint_reward_nft`
+    /// in a single instruction, so the payout and the reward either both land
+    /// or neither does. Takes the `distribute_pot` accounts and remaining
+    /// accounts, plus the mint accounts. The Core CPI sits one level below
+    /// this program, so it must be called directly from the transaction
+    /// rather than through another program; clients should also request a
+    /// higher compute unit limit, as the pair costs roughly the sum of both.
+    pub fn distribute_and_mint<'info>(
+        ctx: Context<'_, '_, '_, 'info, DistributeAndMint<'info>>,
+        name: String,
+        uri: String,
+        seller_fee_basis_points: u16,
+        attributes: Vec<NftAttribute>,
+    ) -> Result<()> {
+        require!(
+            get_stack_height() == TRANSACTION_LEVEL_STACK_HEIGHT,
+            SolPotError::NestedInvocation
+        );
+        require!(
+            !ctx.accounts.distribute.round.nft_minted,
+            SolPotError::NftAlreadyMinted
+        );
+
+        pay_out_pot(
+            &mut ctx.accounts.distribute,
+            ctx.bumps.distribute.winner_profile,
+            ctx.remaining_accounts,
+            false,
+        )?;
+
+        let accounts = RewardAssetAccounts {
+            asset: ctx.accounts.asset.to_account_info(),
+            payer: ctx.accounts.payer.to_account_info(),
+            winner: ctx.accounts.distribute.winner.to_account_info(),
+            collection: ctx
+                .accounts
+                .collection
+                .as_ref()
+                .map(|c| c.to_account_info()),
+            collection_authority: ctx
+                .accounts
+                .collection_authority
+                .as_ref()
+                .map(|c| c.to_account_info()),
+            mpl_core_program: ctx.accounts.mpl_core_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        };
+        let distribute = &mut ctx.accounts.distribute;
+        mint_reward_asset(
+            &distribute.game_config,
+            &mut distribute.round,
+            accounts,
+            &name,
+            &uri,
+            seller_fee_basis_points,
+            &attributes,
+        )
+    }
+
+    /// Returns one player's entry fee after a round expired without a winner,
+    /// or without reaching `min_players`. Permissionless so anyone can crank
+    /// refunds; the lamports always go back to the entry's own player.
+    pub fn refund_losers(ctx: Context<RefundLosers>) -> Result<()> {
+        let clock = Clock::get()?;
+        let round = &ctx.accounts.round;
+
+        require!(
+            !round.has_winner || round.is_underfilled(),
+            SolPotError::RoundAlreadyWon
+        );
+        require!(
+            !round.random_fallback
+                || round.is_underfilled()
+                || round.player_count == 0
+                || round.disputed
+                || round.cancelled,
+            SolPotError::RandomDrawPending
+        );
+        require!(
+            !round.pot_distributed,
+            SolPotError::PotAlreadyDistributed
+        );
+        require!(
+            round.cancelled || clock.unix_timestamp >= round.guessing_ends_at(),
+            SolPotError::RoundNotExpired
+        );
+        require!(
+            !ctx.accounts.player_entry.refunded,
+            SolPotError::AlreadyRefunded
+        );
+
+        let amount = ctx.accounts.player_entry.amount_paid;
+        if round.is_token_round() {
+            let vault = ctx
+                .accounts
+                .vault
+                .as_ref()
+                .ok_or(SolPotError::TokenAccountMissing)?;
+            let token_program = ctx
+                .accounts
+                .token_program
+                .as_ref()
+                .ok_or(SolPotError::TokenAccountMissing)?;
+            let player_token_account = ctx
+                .accounts
+                .player_token_account
+                .as_ref()
+                .ok_or(SolPotError::TokenAccountMissing)?;
+            require!(vault.amount >= amount, SolPotError::InsufficientFunds);
+
+            transfer_from_vault(
+                round,
+                vault,
+                &player_token_account.to_account_info(),
+                token_program,
+                amount,
+            )?;
+        } else {
+            let round_info = round.to_account_info();
+            require!(
+                available_lamports(&round_info)? >= amount,
+                SolPotError::InsufficientFunds
+            );
+
+            move_lamports(&round_info, &ctx.accounts.player, amount)?;
+        }
+
+        let round = &mut ctx.accounts.round;
+        round.pot_lamports = round
+            .pot_lamports
+            .checked_sub(amount)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+        round.refunded_count = round
+            .refunded_count
+            .checked_add(1)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+        ctx.accounts.player_entry.refunded = true;
+
+        emit!(EntryRefunded {
+            round_id: round.id,
+            player: ctx.accounts.player.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Delegates operation of a single round (extending, cancelling,
+    /// closing, editing its metadata) to `new_round_authority`. Passing
+    /// `Pubkey::default()`, or the game authority itself, revokes the
+    /// delegation.
+    pub fn set_round_authority(
+        ctx: Context<SetRoundAuthority>,
+        new_round_authority: Pubkey,
+    ) -> Result<()> {
+        let new_round_authority = if new_round_authority == ctx.accounts.authority.key() {
+            Pubkey::default()
+        } else {
+            new_round_authority
+        };
+        let round = &mut ctx.accounts.round;
+        let old_round_authority = round.round_authority;
+        round.round_authority = new_round_authority;
+
+        emit!(RoundAuthorityUpdated {
+            round_id: round.id,
+            old_round_authority,
+            new_round_authority,
+        });
+
+        Ok(())
+    }
+
+    /// Pushes back the expiry of an unsolved round. Already-expired rounds are
+    /// only revived when `allow_revive` is set.
+    pub fn extend_round(
+        ctx: Context<ExtendRound>,
+        additional_seconds: i64,
+        allow_revive: bool,
+    ) -> Result<()> {
+        require!(additional_seconds > 0, SolPotError::InvalidExtension);
+
+        let round = &mut ctx.accounts.round;
+        require!(round.is_active, SolPotError::RoundNotActive);
+        require!(!round.has_winner, SolPotError::RoundAlreadyWon);
+
+        let clock = Clock::get()?;
+        require!(
+            allow_revive || clock.unix_timestamp < round.expires_at,
+            SolPotError::RoundExpired
+        );
+
+        let old_expires_at = round.expires_at;
+        round.expires_at = round
+            .expires_at
+            .checked_add(additional_seconds)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+
+        emit!(RoundExtended {
+            round_id: round.id,
+            old_expires_at,
+            new_expires_at: round.expires_at,
+        });
+
+        Ok(())
+    }
+
+    /// Ends an unsolved round now instead of at `expires_at`, sending it
+    /// down the usual refund or close path. A round that has been won is
+    /// left alone.
+    pub fn force_expire(ctx: Context<ExtendRound>) -> Result<()> {
+        let round = &mut ctx.accounts.round;
+        require!(round.is_active, SolPotError::RoundNotActive);
+        require!(!round.has_winner, SolPotError::RoundAlreadyWon);
+
+        let clock = Clock::get()?;
+        require!(
+            clock.unix_timestamp < round.expires_at,
+            SolPotError::RoundExpired
+        );
+
+        let old_expires_at = round.expires_at;
+        round.expires_at = clock.unix_timestamp;
+        round.is_active = false;
+
+        emit!(RoundForceExpired {
+            round_id: round.id,
+            old_expires_at,
+            new_expires_at: round.expires_at,
+        });
+
+        Ok(())
+    }
+
+    /// Opens more slots in a live round. The limit can only go up, since
+    /// lowering it could strand players who already entered. The round
+    /// leaderboard keeps its size and simply doesn't track the extra
+    /// entrants once full.
+    pub fn set_max_players(ctx: Context<ExtendRound>, new_max: u32) -> Result<()> {
+        let round = &mut ctx.accounts.round;
+        require!(!round.cancelled, SolPotError::RoundCancelled);
+        require!(!round.has_winner, SolPotError::RoundAlreadyWon);
+        require!(round.is_active, SolPotError::RoundNotActive);
+
+        let clock = Clock::get()?;
+        require!(
+            clock.unix_timestamp < round.expires_at,
+            SolPotError::RoundExpired
+        );
+        require!(
+            new_max >= round.max_players && new_max >= round.player_count,
+            SolPotError::CannotReduceBelowCount
+        );
+        require!(
+            new_max <= Round::MAX_ALLOWED_PLAYERS,
+            SolPotError::InvalidMaxPlayers
+        );
+        if round.random_fallback {
+            require!(
+                new_max as usize <= Participants::MAX_PLAYERS,
+                SolPotError::MaxPlayersReached
+            );
+        }
+
+        let old_max_players = round.max_players;
+        round.max_players = new_max;
+
+        emit!(MaxPlayersUpdated {
+            round_id: round.id,
+            old_max_players,
+            new_max_players: new_max,
+            player_count: round.player_count,
+        });
+
+        Ok(())
+    }
+
+    /// Sets the round's `title` and/or `hint_uri`; `None` leaves a field as
+    /// is. The round is reallocated to fit the new title, with the caller
+    /// paying any extra rent. The answer, timing and fees can't be touched
+    /// here.
+    pub fn update_round_metadata(
+        ctx: Context<UpdateRoundMetadata>,
+        hint_uri: Option<String>,
+        title: Option<String>,
+    ) -> Result<()> {
+        let round = &mut ctx.accounts.round;
+
+        if let Some(hint_uri) = hint_uri {
+            require!(
+                hint_uri.len() <= Round::MAX_HINT_URI_LEN,
+                SolPotError::HintUriTooLong
+            );
+            require!(!round.is_sealed(), SolPotError::RoundSealed);
+            round.hint_uri = hint_uri;
+        }
+        if let Some(title) = title {
+            require!(
+                title.len() <= Round::MAX_TITLE_LEN,
+                SolPotError::TitleTooLong
+            );
+            round.title = title;
+        }
+
+        emit!(RoundMetadataUpdated {
+            round_id: round.id,
+            title: round.title.clone(),
+            hint_uri: round.hint_uri.clone(),
+        });
+
+        Ok(())
+    }
+
+    /// Offers the round's winner a double-or-nothing bonus on `bonus_word_hash`
+    /// (see `spawn_bonus_round`). Must be committed before the round is won,
+    /// so the bonus word can't be picked with the winner in mind.
+    pub fn commit_bonus_word(ctx: Context<SetWordHash>, bonus_word_hash: [u8; 32]) -> Result<()> {
+        let round = &mut ctx.accounts.round;
+        require!(!round.has_winner, SolPotError::RoundAlreadyWon);
+        require!(!round.is_bonus, SolPotError::IncompatibleRoundOptions);
+
+        round.bonus_word_hash = bonus_word_hash;
+
+        emit!(BonusWordCommitted { round_id: round.id });
+
+        Ok(())
+    }
+
+    /// Builds the round's answer set or dictionary root on-chain from leaf
+    /// hashes (`guess_hash` of each word), so it can't disagree with the
+    /// program's hashing. Large lists are sent over several calls in a fixed
+    /// order; the call with `finalize` writes the root to the round and
+    /// closes the builder. Like `set_word_hash`, only before anyone enters.
+    pub fn build_answer_set(
+        ctx: Context<BuildAnswerSet>,
+        hashes: Vec<[u8; 32]>,
+        target: AnswerSetTarget,
+        finalize: bool,
+    ) -> Result<()> {
+        let round = &ctx.accounts.round;
+        require!(
+            round.player_count == 0 && !round.has_winner,
+            SolPotError::CannotModifyAfterEntries
+        );
+
+        let builder = &mut ctx.accounts.builder;
+        if builder.leaf_count == 0 {
+            builder.round = round.key();
+            builder.target = target;
+            builder.bump = ctx.bumps.builder;
+        }
+        require!(
+            builder.target == target,
+            SolPotError::AnswerSetTargetMismatch
+        );
+        for leaf in hashes {
+            builder.push(leaf)?;
+        }
+
+        if !finalize {
+            return Ok(());
+        }
+        let root = builder.root().ok_or(SolPotError::EmptyAnswerSet)?;
+        let leaf_count = builder.leaf_count;
+
+        let round = &mut ctx.accounts.round;
+        match target {
+            AnswerSetTarget::Answers => round.answers_merkle_root = root,
+            AnswerSetTarget::Dictionary => round.dictionary_root = root,
+        }
+        ctx.accounts
+            .builder
+            .close(ctx.accounts.authority.to_account_info())?;
+
+        emit!(AnswerSetBuilt {
+            round_id: ctx.accounts.round.id,
+            target,
+            root,
+            leaf_count,
+        });
+
+        Ok(())
+    }
+
+    /// Replaces a mistyped `word_hash`. Only allowed while nobody has
+    /// entered, so no one pays in against one answer and plays for another,
+    /// and never for rounds with an `answer_commitment` or answer set, whose
+    /// answer the operator has already committed to.
+    pub fn set_word_hash(ctx: Context<SetWordHash>, new_hash: [u8; 32]) -> Result<()> {
+        let round = &mut ctx.accounts.round;
+        require!(
+            round.player_count == 0 && !round.has_winner,
+            SolPotError::CannotModifyAfterEntries
+        );
+        require!(
+            round.answer_commitment == [0u8; 32] && round.answers_merkle_root == [0u8; 32],
+            SolPotError::AnswerLocked
+        );
+
+        let old_word_hash = round.word_hash;
+        round.word_hash = new_hash;
+
+        emit!(WordHashUpdated {
+            round_id: round.id,
+            old_word_hash,
+            new_word_hash: new_hash,
+        });
+
+        Ok(())
+    }
+
+    /// Accounts for lamports sent straight to a SOL round's PDA: whatever
+    /// it holds beyond rent and `pot_lamports` is added to the pot (if it
+    /// fits under `max_pot_lamports`), or with `to_fees` moved to the fee
+    /// vault.
+    pub fn reconcile_pot(ctx: Context<ReconcilePot>, to_fees: bool) -> Result<()> {
+        let round = &mut ctx.accounts.round;
+        require!(
+            round.mint == Pubkey::default(),
+            SolPotError::UnsupportedForTokenRound
+        );
+
+        let round_info = round.to_account_info();
+        let surplus = available_lamports(&round_info)?.saturating_sub(round.pot_lamports);
+        require!(surplus > 0, SolPotError::NoSurplus);
+
+        if to_fees {
+            let fee_vault = &mut ctx.accounts.fee_vault;
+            move_lamports(&round_info, &fee_vault.to_account_info(), surplus)?;
+            fee_vault.accrued_fees = fee_vault
+                .accrued_fees
+                .checked_add(surplus)
+                .ok_or(SolPotError::ArithmeticOverflow)?;
+        } else {
+            require!(round.fits_pot_cap(surplus), SolPotError::PotCapReached);
+            round.add_to_pot(surplus)?;
+        }
+
+        emit!(PotReconciled {
+            round_id: round.id,
+            surplus,
+            to_fees,
+            pot_lamports: round.pot_lamports,
+        });
+
+        Ok(())
+    }
+
+    /// Unmasks the letters at `positions` of the answer. Re-revealing a
+    /// position overwrites it, so mistakes can be corrected.
+    pub fn reveal_hint(
+        ctx: Context<RevealHint>,
+        positions: Vec<u8>,
+        chars: Vec<u8>,
+    ) -> Result<()> {
+        let round = &ctx.accounts.round;
+        require!(round.is_active, SolPotError::RoundNotActive);
+        require!(!round.is_sealed(), SolPotError::RoundSealed);
+        require!(
+            !positions.is_empty() && positions.len() == chars.len(),
+            SolPotError::InvalidHint
+        );
+        require!(
+            positions.iter().all(|&p| p < round.word_length),
+            SolPotError::InvalidHint
+        );
+
+        let hint = &mut ctx.accounts.hint;
+        hint.round = round.key();
+        hint.bump = ctx.bumps.hint;
+
+        let revealed: Vec<RevealedChar> = positions
+            .iter()
+            .zip(&chars)
+            .map(|(&position, &char)| RevealedChar { position, char })
+            .collect();
+        for entry in &revealed {
+            match hint
+                .revealed_chars
+                .iter_mut()
+                .find(|r| r.position == entry.position)
+            {
+                Some(existing) => existing.char = entry.char,
+                None => {
+                    require!(
+                        hint.revealed_chars.len() < Hint::MAX_REVEALED,
+                        SolPotError::InvalidHint
+                    );
+                    hint.revealed_chars.push(*entry);
+                }
+            }
+        }
+
+        emit!(HintRevealed {
+            round_id: round.id,
+            revealed,
+        });
+
+        Ok(())
+    }
+
+    /// Fixes the answer of a round created with a `commit_slot`, once that
+    /// slot has been produced and while it's still in SlotHashes (roughly
+    /// the last 512 slots). `answer_commitment` is `hash(answer || salt)`
+    /// with `salt` derived from the slot's hash, which is recorded here for
+    /// `reveal_answer` to check. Entries stay closed until this lands.
+    pub fn commit_answer(ctx: Context<CommitAnswer>, answer_commitment: [u8; 32]) -> Result<()> {
+        let round = &mut ctx.accounts.round;
+        require!(round.commit_slot > 0, SolPotError::FairnessProofInvalid);
+        require!(!round.has_answer_commitment(), SolPotError::AnswerLocked);
+        require!(
+            round.player_count == 0 && !round.has_winner,
+            SolPotError::CannotModifyAfterEntries
+        );
+        require!(
+            answer_commitment != [0u8; 32],
+            SolPotError::NoAnswerCommitment
+        );
+
+        let data = ctx.accounts.slot_hashes.try_borrow_data()?;
+        let commit_slot_hash =
+            find_slot_hash(&data, round.commit_slot).ok_or(SolPotError::FairnessProofInvalid)?;
+        drop(data);
+
+        round.commit_slot_hash = commit_slot_hash;
+        round.answer_commitment = answer_commitment;
+
+        emit!(AnswerCommitted {
+            round_id: round.id,
+            commit_slot: round.commit_slot,
+            commit_slot_hash,
+        });
+
+        Ok(())
+    }
+
+    /// Publishes the plaintext answer once guessing is over (or the round is
+    /// won), so anyone can check it against `word_hash`. Rounds with an
+    /// answer commitment must open it with `salt`, proving the answer was
+    /// reachable; otherwise `salt` is ignored. `proof` is only used by
+    /// rounds with an answer set. The answer must also match the advertised
+    /// `word_length`, if any. Rounds with a `commit_slot` also need
+    /// `salt_nonce`, showing `salt` was derived from the commit slot's hash.
+    pub fn reveal_answer(
+        ctx: Context<RevealAnswer>,
+        answer: String,
+        salt: [u8; 16],
+        proof: Vec<[u8; 32]>,
+        salt_nonce: [u8; 16],
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        let round = &ctx.accounts.round;
+
+        require!(
+            answer.len() <= Round::MAX_ANSWER_LEN,
+            SolPotError::AnswerTooLong
+        );
+        require!(!round.answer_revealed, SolPotError::AnswerAlreadyRevealed);
+        require!(!round.disputed, SolPotError::RoundDisputed);
+        require!(
+            (!round.is_active || clock.unix_timestamp >= round.guessing_ends_at())
+                && clock.unix_timestamp <= round.answer_reveal_deadline(),
+            SolPotError::AnswerRevealWindowClosed
+        );
+
+        if round.has_answer_commitment() {
+            let commitment = hashv(&[answer.as_bytes(), salt.as_ref()]).to_bytes();
+            require!(
+                commitment == round.answer_commitment,
+                SolPotError::InvalidWordHash
+            );
+        }
+        require!(
+            round.salt_binds_commit_slot(&salt, &salt_nonce),
+            SolPotError::FairnessProofInvalid
+        );
+        require!(
+            round.word_length == 0 || answer.chars().count() == round.word_length as usize,
+            SolPotError::InvalidWordHash
+        );
+        require!(
+            is_answer(round, answer.as_bytes(), &proof)?,
+            SolPotError::InvalidWordHash
+        );
+
+        let round = &mut ctx.accounts.round;
+        round.answer_revealed = true;
+        round.revealed_answer = answer.clone();
+
+        emit!(AnswerRevealed {
+            round_id: round.id,
+            answer,
+        });
+
+        Ok(())
+    }
+
+    /// Flags an unsolved round whose committed answer wasn't revealed in
+    /// time, sending it to refunds. Permissionless.
+    pub fn mark_disputed(ctx: Context<MarkDisputed>) -> Result<()> {
+        let clock = Clock::get()?;
+        let round = &mut ctx.accounts.round;
+
+        require!(
+            round.has_answer_commitment(),
+            SolPotError::NoAnswerCommitment
+        );
+        require!(!round.answer_revealed, SolPotError::AnswerAlreadyRevealed);
+        require!(!round.has_winner, SolPotError::RoundAlreadyWon);
+        require!(!round.disputed, SolPotError::RoundDisputed);
+        require!(
+            clock.unix_timestamp > round.answer_reveal_deadline(),
+            SolPotError::RoundNotExpired
+        );
+
+        round.disputed = true;
+        round.is_active = false;
+
+        emit!(RoundDisputed { round_id: round.id });
+
+        Ok(())
+    }
+
+    /// Breaks `player_entry.player`'s win streak using a won round they entered
+    /// but didn't win as proof. Anyone may call it; it must happen before the
+    /// entry is reclaimed. Losses older than the player's last win are
+    /// ignored.
+    pub fn reset_streak(ctx: Context<ResetStreak>) -> Result<()> {
+        let clock = Clock::get()?;
+        let round = &ctx.accounts.round;
+        let player = ctx.accounts.player_entry.player;
+
+        require!(
+            round.has_winner
+                && !round.is_underfilled()
+                && round.win_is_final(clock.unix_timestamp)
+                && (!round.allow_multiple_winners
+                    || clock.unix_timestamp >= round.guessing_ends_at()),
+            SolPotError::RoundNotSettled
+        );
+        let won = if round.allow_multiple_winners {
+            ctx.accounts
+                .winners
+                .as_ref()
+                .ok_or(SolPotError::WinnersAccountMismatch)?
+                .players
+                .contains(&player)
+        } else {
+            round.winner == player
+        };
+        require!(!won, SolPotError::PlayerWonRound);
+
+        let round_id = round.id;
+        if let Some(entry) = ctx
+            .accounts
+            .leaderboard
+            .entries
+            .iter_mut()
+            .find(|e| e.player == player)
+        {
+            if round_id > entry.last_win_round_id && entry.current_streak > 0 {
+                entry.current_streak = 0;
+                emit!(StreakReset { player, round_id });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Closes a player's `PlayerEntry` and `GuessRecord` once their round is
+    /// settled, returning the rent to the player. Anyone may call it, so one
+    /// absent player can't keep the round from being closed or restarted.
+    /// On the refund path the entry must have been refunded first so the
+    /// refund isn't forfeited.
+    pub fn reclaim_entry(ctx: Context<ReclaimEntry>) -> Result<()> {
+        let clock = Clock::get()?;
+        let round = &ctx.accounts.round;
+
+        let settled = round.pot_distributed
+            || (round.is_refund_path(clock.unix_timestamp) && ctx.accounts.player_entry.refunded);
+        require!(settled, SolPotError::RoundNotSettled);
+
+        let round = &mut ctx.accounts.round;
+        round.live_entries = round.live_entries.saturating_sub(1);
+
+        emit!(EntryReclaimed {
+            round_id: round.id,
+            player: ctx.accounts.player_entry.player,
+        });
+
+        Ok(())
+    }
+
+    /// Closes a tipper's `TipRecord` once the round is settled, returning
+    /// its rent. On the refund path of a round with `refund_tips`, and once
+    /// every entry has been refunded, it also hands back the tipper's share
+    /// of what's left of the tips: `amount * pool / unrefunded`, where the
+    /// pool is the pot minus the authority's seed and bounties, capped at
+    /// the unrefunded tips. The ratio stays the same from one claim to the
+    /// next, so every tipper takes the same cut if the round came up short.
+    pub fn reclaim_tip(ctx: Context<ReclaimTip>) -> Result<()> {
+        let clock = Clock::get()?;
+        let round = &mut ctx.accounts.round;
+        let tipped = ctx.accounts.tip_record.amount;
+
+        let refund_path = round.is_refund_path(clock.unix_timestamp) && !round.pot_distributed;
+        require!(
+            round.pot_distributed || refund_path,
+            SolPotError::RoundNotSettled
+        );
+
+        let mut refund = 0;
+        if refund_path && round.refund_tips {
+            require!(
+                round.refunded_count == round.player_count,
+                SolPotError::RefundsPending
+            );
+            let unrefunded = round.total_tips - round.refunded_tips;
+            let pool = round
+                .pot_lamports
+                .saturating_sub(round.authority_funded_lamports())
+                .min(unrefunded);
+            refund = (tipped as u128)
+                .checked_mul(pool as u128)
+                .and_then(|v| v.checked_div(unrefunded as u128))
+                .and_then(|v| u64::try_from(v).ok())
+                .ok_or(SolPotError::ArithmeticOverflow)?;
+
+            let round_info = round.to_account_info();
+            require!(
+                available_lamports(&round_info)? >= refund,
+                SolPotError::InsufficientFunds
+            );
+            move_lamports(&round_info, &ctx.accounts.tipper, refund)?;
+            round.pot_lamports = round
+                .pot_lamports
+                .checked_sub(refund)
+                .ok_or(SolPotError::ArithmeticOverflow)?;
+            round.refunded_tips = round
+                .refunded_tips
+                .checked_add(tipped)
+                .ok_or(SolPotError::ArithmeticOverflow)?;
+        }
+        round.live_tips = round.live_tips.saturating_sub(1);
+
+        emit!(TipReclaimed {
+            round_id: round.id,
+            tipper: ctx.accounts.tipper.key(),
+            refund,
+        });
+
+        Ok(())
+    }
+
+    /// Moves an unsolved round's pot into a later open round as a jackpot
+    /// instead of refunding entrants. Entries that were already refunded
+    /// aren't part of the rolled amount. Both rounds must be SOL rounds.
+    pub fn rollover_pot(ctx: Context<RolloverPot>) -> Result<()> {
+        let clock = Clock::get()?;
+        let source = &ctx.accounts.source_round;
+        let target = &ctx.accounts.target_round;
+
+        require!(!source.has_winner, SolPotError::RoundAlreadyWon);
+        require!(!source.disputed, SolPotError::RoundDisputed);
+        require!(!source.cancelled, SolPotError::RoundCancelled);
+        require!(source.answer_settled(), SolPotError::AnswerNotRevealed);
+        // Refundable tips belong to their tippers, not the next round.
+        require!(
+            !source.refund_tips || source.total_tips == 0,
+            SolPotError::TipsPending
+        );
+        require!(
+            !source.pot_distributed,
+            SolPotError::PotAlreadyDistributed
+        );
+        require!(
+            clock.unix_timestamp >= source.guessing_ends_at(),
+            SolPotError::RoundNotExpired
+        );
+        require!(
+            !source.is_token_round(),
+            SolPotError::UnsupportedForTokenRound
+        );
+        require!(
+            target.id != source.id
+                && !target.is_token_round()
+                && target.is_active
+                && !target.has_winner
+                && clock.unix_timestamp < target.expires_at,
+            SolPotError::InvalidRolloverTarget
+        );
+
+        let source_info = source.to_account_info();
+        let amount = std::cmp::min(source.pot_lamports, available_lamports(&source_info)?);
+        require!(target.fits_pot_cap(amount), SolPotError::PotCapReached);
+        move_lamports(&source_info, &target.to_account_info(), amount)?;
+
+        let source = &mut ctx.accounts.source_round;
+        let seeded = std::cmp::min(source.authority_funded_lamports(), amount);
+        source.pot_lamports = 0;
+        source.seeded_lamports = 0;
+        source.bounty_lamports = 0;
+        source.pot_distributed = true;
+        source.is_active = false;
+
+        // The authority's seed and bounties stay the authority's in the
+        // target round, as seed.
+        let target = &mut ctx.accounts.target_round;
+        target.add_to_pot(amount)?;
+        target.seeded_lamports = target
+            .seeded_lamports
+            .checked_add(seeded)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+
+        emit!(PotRolledOver {
+            from_round_id: ctx.accounts.source_round.id,
+            to_round_id: ctx.accounts.target_round.id,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Sweeps a won prize nobody distributed to the authority once the
+    /// round's claim deadline has passed. Until then `distribute_pot` works
+    /// as usual.
+    pub fn reclaim_unclaimed(ctx: Context<ReclaimUnclaimed>) -> Result<()> {
+        let clock = Clock::get()?;
+        let round = &ctx.accounts.round;
+
+        require!(round.has_winner, SolPotError::NoWinner);
+        require!(
+            !round.pot_distributed,
+            SolPotError::PotAlreadyDistributed
+        );
+        require!(!round.is_underfilled(), SolPotError::MinPlayersNotMet);
+        require!(
+            !round.is_token_round(),
+            SolPotError::UnsupportedForTokenRound
+        );
+        let deadline = round
+            .claim_deadline()
+            .ok_or(SolPotError::UnclaimedWindowOpen)?;
+        require!(
+            clock.unix_timestamp > deadline,
+            SolPotError::UnclaimedWindowOpen
+        );
+
+        let round_info = round.to_account_info();
+        let amount = std::cmp::min(round.pot_lamports, available_lamports(&round_info)?);
+        move_lamports(&round_info, &ctx.accounts.authority.to_account_info(), amount)?;
+
+        let round = &mut ctx.accounts.round;
+        round.pot_lamports = 0;
+        round.pot_distributed = true;
+        round.is_active = false;
+
+        emit!(PrizeReclaimed {
+            round_id: round.id,
+            winner: round.winner,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Escape hatch for rounds stuck in an inconsistent state: drains
+    /// everything above rent (or the whole vault, for token rounds) to the
+    /// authority regardless of winner state. Only while the game is paused.
+    pub fn emergency_withdraw(ctx: Context<EmergencyWithdrawRound>) -> Result<()> {
+        require!(ctx.accounts.game_config.paused, SolPotError::GameNotPaused);
+
+        let amount = if ctx.accounts.round.is_token_round() {
+            let vault = ctx
+                .accounts
+                .vault
+                .as_ref()
+                .ok_or(SolPotError::TokenAccountMissing)?;
+            let authority_token_account = ctx
+                .accounts
+                .authority_token_account
+                .as_ref()
+                .ok_or(SolPotError::TokenAccountMissing)?;
+            let token_program = ctx
+                .accounts
+                .token_program
+                .as_ref()
+                .ok_or(SolPotError::TokenAccountMissing)?;
+            transfer_from_vault(
+                &ctx.accounts.round,
+                vault,
+                &authority_token_account.to_account_info(),
+                token_program,
+                vault.amount,
+            )?;
+            vault.amount
+        } else {
+            let round_info = ctx.accounts.round.to_account_info();
+            let amount = available_lamports(&round_info)?;
+            move_lamports(&round_info, &ctx.accounts.authority.to_account_info(), amount)?;
+            amount
+        };
+
+        let round = &mut ctx.accounts.round;
+        round.pot_lamports = 0;
+        round.pot_distributed = true;
+        round.is_active = false;
+
+        emit!(EmergencyWithdraw {
+            round_id: round.id,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Pulls a round that hasn't been won yet, while guesses are still
+    /// open. Entries are refunded one by one through `refund_losers`,
+    /// without waiting for expiry.
+    pub fn cancel_round(ctx: Context<CancelRound>) -> Result<()> {
+        let clock = Clock::get()?;
+        let round = &mut ctx.accounts.round;
+        require_cancellable(round, clock.unix_timestamp)?;
+
+        round.is_active = false;
+        round.cancelled = true;
+
+        emit!(RoundCancelled {
+            round_id: round.id,
+            player_count: round.player_count,
+        });
+
+        Ok(())
+    }
+
+    /// Sets the share of each wrong guess's fee that feeds the jackpot.
+    pub fn update_loss_fee_to_jackpot_bps(
+        ctx: Context<UpdateJackpot>,
+        new_bps: u16,
+    ) -> Result<()> {
+        require!(new_bps <= 10000, SolPotError::InvalidFeeBasisPoints);
+
+        let jackpot = &mut ctx.accounts.jackpot;
+        let old_bps = jackpot.loss_fee_to_jackpot_bps;
+        jackpot.loss_fee_to_jackpot_bps = new_bps;
+
+        emit!(JackpotRateUpdated {
+            old_loss_fee_to_jackpot_bps: old_bps,
+            new_loss_fee_to_jackpot_bps: new_bps,
+        });
+
+        Ok(())
+    }
+
+    /// Folds the whole jackpot into a running SOL round's pot. Like any pot,
+    /// it goes to the house if the round ends up refunded.
+    pub fn claim_jackpot(ctx: Context<ClaimJackpot>) -> Result<()> {
+        let clock = Clock::get()?;
+        let round = &mut ctx.accounts.round;
+        require!(!round.is_token_round(), SolPotError::UnsupportedForTokenRound);
+        require!(!round.cancelled, SolPotError::RoundCancelled);
+        require!(round.is_active, SolPotError::RoundNotActive);
+        require!(!round.has_winner, SolPotError::RoundAlreadyWon);
+        require!(
+            clock.unix_timestamp < round.expires_at,
+            SolPotError::RoundExpired
+        );
+
+        let jackpot = &mut ctx.accounts.jackpot;
+        let amount = jackpot.jackpot_balance;
+        require!(amount > 0, SolPotError::JackpotEmpty);
+        require!(round.fits_pot_cap(amount), SolPotError::PotCapReached);
+
+        move_lamports(&jackpot.to_account_info(), &round.to_account_info(), amount)?;
+        jackpot.jackpot_balance = 0;
+        round.add_to_pot(amount)?;
+
+        emit!(JackpotClaimed {
+            round_id: round.id,
+            amount,
+            pot_lamports: round.pot_lamports,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraws up to the accrued house fees to the game's fee destination.
+    pub fn sweep_fees(ctx: Context<SweepFees>, amount: u64) -> Result<()> {
+        let fee_vault = &mut ctx.accounts.fee_vault;
+        require!(
+            amount <= fee_vault.accrued_fees,
+            SolPotError::InsufficientAccruedFees
+        );
+
+        move_lamports(
+            &fee_vault.to_account_info(),
+            &ctx.accounts.destination,
+            amount,
+        )?;
+        fee_vault.accrued_fees = fee_vault
+            .accrued_fees
+            .checked_sub(amount)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+
+        emit!(FeesSwept {
+            destination: ctx.accounts.destination.key(),
+            amount,
+            remaining_fees: fee_vault.accrued_fees,
+        });
+
+        Ok(())
+    }
+
+    /// Pays the winner of a vesting round whatever has unlocked since their
+    /// last claim.
+    pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+        let clock = Clock::get()?;
+        let prize_vault = &mut ctx.accounts.prize_vault;
+        require!(
+            prize_vault.claimed < prize_vault.total,
+            SolPotError::VaultEmpty
+        );
+        let amount = prize_vault
+            .vested_at(clock.unix_timestamp)
+            .saturating_sub(prize_vault.claimed);
+        require!(amount > 0, SolPotError::NothingToClaim);
+
+        move_lamports(
+            &prize_vault.to_account_info(),
+            &ctx.accounts.winner.to_account_info(),
+            amount,
+        )?;
+        prize_vault.claimed = prize_vault
+            .claimed
+            .checked_add(amount)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+
+        emit!(VestedClaim {
+            round_id: prize_vault.round_id,
+            winner: prize_vault.winner,
+            amount,
+            claimed: prize_vault.claimed,
+            total: prize_vault.total,
+        });
+
+        Ok(())
+    }
+
+    /// Read-only: returns the round's current `RoundPhase` as return data so
+    /// clients don't have to re-derive it from raw fields.
+    pub fn round_phase(ctx: Context<RoundPhaseView>) -> Result<RoundPhase> {
+        let clock = Clock::get()?;
+        Ok(ctx.accounts.round.phase(clock.unix_timestamp))
+    }
+
+    /// Read-only: returns the round's `hint_uri` as return data, failing
+    /// with `RoundSealed` while a sealed round has no entrants yet.
+    pub fn round_hint(ctx: Context<RoundPhaseView>) -> Result<String> {
+        let round = &ctx.accounts.round;
+        require!(!round.is_sealed(), SolPotError::RoundSealed);
+        Ok(round.hint_uri.clone())
+    }
+
+    /// Read-only: runs `distribute_pot`'s split without moving anything and
+    /// emits the amounts as a `DistributionPreview` event. Multi-winner
+    /// rounds need their `Winners` account.
+    pub fn preview_distribution(ctx: Context<PreviewDistribution>) -> Result<()> {
+        let round = &ctx.accounts.round;
+        let available = match ctx.accounts.vault.as_ref() {
+            Some(vault) if round.is_token_round() => vault.amount,
+            None if !round.is_token_round() => available_lamports(&round.to_account_info())?,
+            _ => return err!(SolPotError::TokenAccountMissing),
+        };
+        let plan = plan_payout(
+            round,
+            &ctx.accounts.game_config,
+            ctx.accounts.winners.as_deref(),
+            available,
+        )?;
+
+        emit!(DistributionPreview {
+            round_id: round.id,
+            winner_amount: plan.split.winner_amount,
+            fee_amount: plan.split.fee,
+            runner_up_amount: plan.split.runner_up_amount,
+            charity_amount: plan.donation,
+        });
+
+        Ok(())
+    }
+
+    /// Read-only: emits the `n` best leaderboard entries (by weighted
+    /// `score`) as a `TopPlayers` event, so clients don't have to fetch the
+    /// whole account.
+    /// `n` is clamped to `Leaderboard::MAX_TOP_PLAYERS`.
+    pub fn top_players(ctx: Context<TopPlayersView>, n: u8) -> Result<()> {
+        let n = n.min(Leaderboard::MAX_TOP_PLAYERS) as usize;
+        let entries = &ctx.accounts.leaderboard.entries;
+        emit!(TopPlayers {
+            entries: entries[..n.min(entries.len())].to_vec(),
+        });
+        Ok(())
+    }
+
+    /// Read-only: emits `player`'s leaderboard rank, wins and winnings as a
+    /// `PlayerRank` event. Scans the sorted board, so it costs O(n) in the
+    /// entries ahead of the player, up to `Leaderboard::MAX_RANK_SCAN`.
+    pub fn player_rank(ctx: Context<TopPlayersView>, player: Pubkey) -> Result<()> {
+        let (rank, wins, total_winnings) = match ctx.accounts.leaderboard.rank_of(&player) {
+            Some((rank, entry)) => (rank, entry.wins, entry.total_winnings),
+            None => (0, 0, 0),
+        };
+        emit!(PlayerRank {
+            player,
+            rank,
+            wins,
+            total_winnings,
+        });
+        Ok(())
+    }
+
+    /// Closes a settled round, returning its rent to the game authority.
+    /// Every `PlayerEntry` and `TipRecord` must have been reclaimed with
+    /// `reclaim_entry` and `reclaim_tip` (which anyone may crank) first,
+    /// since those can't be closed once their round is gone.
+    pub fn close_round(ctx: Context<CloseRound>) -> Result<()> {
+        let clock = Clock::get()?;
+        settle_leftover_pot(
+            &mut ctx.accounts.round,
+            &mut ctx.accounts.fee_vault,
+            &ctx.accounts.game_authority,
+            clock.unix_timestamp,
+        )?;
+
+        let round_id = ctx.accounts.round.id;
+        let game_config = &mut ctx.accounts.game_config;
+        game_config.open_rounds = game_config.open_rounds.saturating_sub(1);
+
+        emit!(RoundClosed { round_id });
+
+        Ok(())
+    }
+
+    /// Keeper version of `close_round`: closes each round passed (writable) in
+    /// `remaining_accounts` that is ready to close, and skips the rest, or
+    /// anything that isn't one of this game's rounds, instead of failing the
+    /// batch. Up to `Round::MAX_CLOSE_BATCH_LEN` rounds per call.
+    pub fn close_expired_rounds<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseExpiredRounds<'info>>,
+    ) -> Result<()> {
+        require!(
+            !ctx.remaining_accounts.is_empty()
+                && ctx.remaining_accounts.len() <= Round::MAX_CLOSE_BATCH_LEN,
+            SolPotError::InvalidBatchSize
+        );
+
+        let clock = Clock::get()?;
+        let game_config_key = ctx.accounts.game_config.key();
+        let mut count: u32 = 0;
+        for info in ctx.remaining_accounts {
+            let Ok(mut round) = Account::<Round>::try_from(info) else {
+                continue;
+            };
+            if !info.is_writable
+                || round.game_config != game_config_key
+                || !round.is_closable(clock.unix_timestamp)
+            {
+                continue;
+            }
+
+            settle_leftover_pot(
+                &mut round,
+                &mut ctx.accounts.fee_vault,
+                &ctx.accounts.authority,
+                clock.unix_timestamp,
+            )?;
+            let round_id = round.id;
+            round.close(ctx.accounts.authority.to_account_info())?;
+
+            let game_config = &mut ctx.accounts.game_config;
+            game_config.open_rounds = game_config.open_rounds.saturating_sub(1);
+            count += 1;
+
+            emit!(RoundClosed { round_id });
+        }
+
+        emit!(BatchClosed { count });
+
+        Ok(())
+    }
+
+    /// Reuses a settled round's PDA for a new puzzle instead of closing it
+    /// and creating a new round: the word, expiry and entry fee are reset and
+    /// the round's settings (player cap, guess rules, fee curve...) are kept.
+    /// Bumping `generation` moves players and tippers onto fresh PDAs, so
+    /// the previous run's entries and tip records must all be reclaimed
+    /// first.
+    pub fn restart_round(
+        ctx: Context<RestartRound>,
+        word_hash: [u8; 32],
+        duration_seconds: i64,
+        word_length: u8,
+        hint_uri: String,
+    ) -> Result<()> {
+        require!(!ctx.accounts.game_config.paused, SolPotError::GamePaused);
+        let round = &ctx.accounts.round;
+        ctx.accounts
+            .game_config
+            .validate_round_bounds(round.max_players, duration_seconds)?;
+        require!(
+            hint_uri.len() <= Round::MAX_HINT_URI_LEN,
+            SolPotError::HintUriTooLong
+        );
+        // The restarted round has no entrants, so a sealed one is sealed again.
+        require!(
+            !round.sealed || hint_uri.is_empty(),
+            SolPotError::RoundSealed
+        );
+        require!(
+            ctx.accounts.winners.is_some() == round.allow_multiple_winners,
+            SolPotError::WinnersAccountMismatch
+        );
+        require!(
+            ctx.accounts.participants.is_some() == round.random_fallback,
+            SolPotError::ParticipantsAccountMismatch
+        );
+        require!(
+            ctx.accounts.prize_vault.is_some() == round.is_vesting(),
+            SolPotError::PrizeVaultMismatch
+        );
+        // The last winner must have drained their vesting prize first.
+        if let Some(prize_vault) = ctx.accounts.prize_vault.as_ref() {
+            require!(
+                prize_vault.claimed == prize_vault.total,
+                SolPotError::RoundStillActive
+            );
+        }
+
+        let clock = Clock::get()?;
+        settle_leftover_pot(
+            &mut ctx.accounts.round,
+            &mut ctx.accounts.fee_vault,
+            &ctx.accounts.authority,
+            clock.unix_timestamp,
+        )?;
+
+        let game_config = &ctx.accounts.game_config;
+        game_config.require_entry_fee_floor(game_config.entry_fee_lamports)?;
+        let round = &mut ctx.accounts.round;
+
+        round.generation = round
+            .generation
+            .checked_add(1)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+        round.word_hash = word_hash;
+        round.answers_merkle_root = [0u8; 32];
+        round.answer_commitment = [0u8; 32];
+        round.commit_slot = 0;
+        round.commit_slot_hash = [0u8; 32];
+        round.answer_revealed = false;
+        round.revealed_answer = String::new();
+        round.title = String::new();
+        round.bonus_word_hash = [0u8; 32];
+        round.is_bonus = false;
+        round.word_length = word_length;
+        round.hint_uri = hint_uri;
+        round.is_active = true;
+        round.winner = Pubkey::default();
+        round.has_winner = false;
+        round.won_at = 0;
+        round.pot_lamports = 0;
+        round.seeded_lamports = 0;
+        round.bounty_lamports = 0;
+        round.pot_distributed = false;
+        round.nft_minted = false;
+        round.player_count = 0;
+        round.refunded_count = 0;
+        round.first_entrant = Pubkey::default();
+        round.first_entered_at = 0;
+        round.total_guesses = 0;
+        round.correct_guesses = 0;
+        round.total_tips = 0;
+        round.refunded_tips = 0;
+        round.disputed = false;
+        round.cancelled = false;
+        round.entry_fee_lamports = game_config.entry_fee_lamports;
+        round.created_at = clock.unix_timestamp;
+        round.starts_at = clock.unix_timestamp;
+        round.expires_at = clock
+            .unix_timestamp
+            .checked_add(duration_seconds)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+        round.anti_snipe_extended_seconds = 0;
+
+        if let Some(winners) = ctx.accounts.winners.as_mut() {
+            winners.players.clear();
+        }
+        if let Some(participants) = ctx.accounts.participants.as_mut() {
+            participants.players.clear();
+        }
+        if let Some(hint) = ctx.accounts.hint.as_mut() {
+            hint.revealed_chars.clear();
+        }
+        if let Some(prize_vault) = ctx.accounts.prize_vault.as_mut() {
+            prize_vault.winner = Pubkey::default();
+            prize_vault.total = 0;
+            prize_vault.claimed = 0;
+        }
+        let guess_log = &mut ctx.accounts.guess_log;
+        guess_log.head = 0;
+        guess_log.entries.clear();
+        ctx.accounts.round_leaderboard.entries.clear();
+
+        emit!(RoundRestarted {
+            round_id: round.id,
+            generation: round.generation,
+            expires_at: round.expires_at,
+        });
+
+        Ok(())
+    }
+
+    /// Winds the game down once every round is closed, returning the rent
+    /// of the config, the leaderboard, the fee vault (plus any unswept fees)
+    /// and the jackpot (plus its balance) to the authority.
+    pub fn close_game(ctx: Context<CloseGame>) -> Result<()> {
+        require!(
+            ctx.accounts.game_config.open_rounds == 0,
+            SolPotError::OpenRoundsRemain
+        );
+
+        emit!(GameClosed {
+            authority: ctx.accounts.authority.key(),
+            round_count: ctx.accounts.game_config.round_count,
+            unswept_fees: ctx.accounts.fee_vault.accrued_fees,
+        });
+
+        Ok(())
+    }
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+/// Checks that `player_entry` is a live `PlayerEntry` for this round and
+/// player, failing with `NotEntered` instead of Anchor's account-not-found
+/// error when the player never entered.
+fn require_entered(player_entry: &AccountInfo, round: &Pubkey, player: &Pubkey) -> Result<()> {
+    let data = player_entry.try_borrow_data()?;
+    require!(
+        *player_entry.owner == crate::ID && data.starts_with(PlayerEntry::DISCRIMINATOR),
+        SolPotError::NotEntered
+    );
+    let entry = PlayerEntry::try_deserialize(&mut &data[..])?;
+    require!(
+        entry.round == *round && entry.player == *player,
+        SolPotError::NotEntered
+    );
+    Ok(())
+}
+
+/// Fails with `PlayerBlacklisted` if `player` is on the game's blacklist.
+/// The blacklist PDA may not have been created yet, which bans no one.
+fn require_not_blacklisted(blacklist: &AccountInfo, player: &Pubkey) -> Result<()> {
+    let data = blacklist.try_borrow_data()?;
+    if *blacklist.owner != crate::ID || !data.starts_with(Blacklist::DISCRIMINATOR) {
+        return Ok(());
+    }
+    let blacklist = Blacklist::try_deserialize(&mut &data[..])?;
+    require!(
+        !blacklist.players.contains(player),
+        SolPotError::PlayerBlacklisted
+    );
+    Ok(())
+}
+
+/// Ranks `wins` (player, amount) from round `round_id` on the game's season
+/// leaderboard if a season is running and covers the round. The season PDA
+/// may not have been created yet, in which case nothing is recorded.
+fn record_season_wins(
+    info: &AccountInfo,
+    game_config: &GameConfig,
+    round_id: u64,
+    won_at: i64,
+    wins: &[(Pubkey, u64)],
+) -> Result<()> {
+    if *info.owner != crate::ID {
+        return Ok(());
+    }
+    let mut season = Season::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    if !season.covers(round_id) {
+        return Ok(());
+    }
+    for (player, amount) in wins {
+        record_ranked_win(
+            &mut season.entries,
+            Season::MAX_ENTRIES,
+            *player,
+            *amount,
+            round_id,
+            won_at,
+            game_config,
+        )?;
+    }
+    season.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+}
+
+/// Credits a co-winner's `share` of a split pot to their `PlayerProfile` at
+/// `info`, creating the profile (paid by `payer`) if they don't have one.
+fn credit_profile_win<'info>(
+    info: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    game_config: &Pubkey,
+    player: &Pubkey,
+    share: u64,
+) -> Result<()> {
+    require!(info.is_writable, SolPotError::WinnerAccountsMismatch);
+    let mut profile = if *info.owner == crate::ID {
+        let profile = PlayerProfile::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        // Profiles only ever live at their own PDA, so matching fields pin
+        // down the address.
+        require!(
+            profile.game_config == *game_config && profile.player == *player,
+            SolPotError::WinnerAccountsMismatch
+        );
+        profile
+    } else {
+        let bump = init_pda_account(
+            payer,
+            info,
+            system_program,
+            &[PlayerProfile::SEED, game_config.as_ref(), player.as_ref()],
+            PlayerProfile::SIZE,
+        )?;
+        PlayerProfile {
+            game_config: *game_config,
+            player: *player,
+            bump,
+            ..Default::default()
+        }
+    };
+    profile.wins = profile
+        .wins
+        .checked_add(1)
+        .ok_or(SolPotError::ArithmeticOverflow)?;
+    profile.total_winnings = profile
+        .total_winnings
+        .checked_add(share)
+        .ok_or(SolPotError::ArithmeticOverflow)?;
+    profile.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+}
+
+/// Accounts taking part in the Metaplex Core `CreateV1` CPI.
+struct RewardAssetAccounts<'info> {
+    asset: AccountInfo<'info>,
+    payer: AccountInfo<'info>,
+    winner: AccountInfo<'info>,
+    collection: Option<AccountInfo<'info>>,
+    collection_authority: Option<AccountInfo<'info>>,
+    mpl_core_program: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+}
+
+/// Mints the winner's reward asset and flags the round as minted. Body of
+/// `mint_reward_nft`, shared with `distribute_and_mint`.
+fn mint_reward_asset(
+    game_config: &GameConfig,
+    round: &mut Round,
+    accounts: RewardAssetAccounts,
+    name: &str,
+    uri: &str,
+    seller_fee_basis_points: u16,
+    attributes: &[NftAttribute],
+) -> Result<()> {
+    require!(
+        seller_fee_basis_points <= 10000,
+        SolPotError::InvalidFeeBasisPoints
+    );
+    require!(
+        round.win_is_final(Clock::get()?.unix_timestamp),
+        SolPotError::RoundNotExpired
+    );
+    game_config.require_reward_metadata(round.id, name, uri)?;
+    let royalties = (seller_fee_basis_points > 0)
+        .then_some((seller_fee_basis_points, game_config.authority));
+    let data = core_create_v1_data(name, uri, royalties, attributes);
+
+    // Metaplex Core uses its own program ID as a sentinel for absent optional accounts.
+    let absent = MPL_CORE_PROGRAM_ID;
+
+    let mut account_infos = vec![
+        accounts.asset.clone(),
+        accounts.payer.clone(),
+        accounts.winner.clone(),
+        accounts.system_program.clone(),
+        accounts.mpl_core_program.clone(),
+    ];
+
+    // Assets in a collection inherit its update authority, which has to
+    // sign the CPI; standalone assets default both to the payer.
+    let game_collection = game_config.collection;
+    let (collection_meta, authority_meta) = if game_collection != Pubkey::default() {
+        let collection = accounts
+            .collection
+            .as_ref()
+            .ok_or(SolPotError::CollectionMismatch)?;
+        require_keys_eq!(
+            collection.key(),
+            game_collection,
+            SolPotError::CollectionMismatch
+        );
+        let collection_authority = accounts
+            .collection_authority
+            .as_ref()
+            .ok_or(SolPotError::CollectionMismatch)?;
+        account_infos.push(collection.clone());
+        account_infos.push(collection_authority.clone());
+        (
+            AccountMeta::new(collection.key(), false),
+            AccountMeta::new_readonly(collection_authority.key(), true),
+        )
+    } else {
+        (
+            AccountMeta::new_readonly(absent, false),
+            AccountMeta::new_readonly(absent, false),
+        )
+    };
+
+    let metas = vec![
+        AccountMeta::new(accounts.asset.key(), true),           // 0: asset (writable, signer)
+        collection_meta,                                            // 1: collection (or absent)
+        authority_meta,                                             // 2: authority (collection authority, or absent → payer)
+        AccountMeta::new(accounts.payer.key(), true),           // 3: payer (writable, signer)
+        AccountMeta::new_readonly(accounts.winner.key(), false),// 4: owner (the winner)
+        AccountMeta::new_readonly(absent, false),                   // 5: update_authority (absent → defaults to payer)
+        AccountMeta::new_readonly(accounts.system_program.key(), false), // 6: system_program
+        AccountMeta::new_readonly(absent, false),                   // 7: log_wrapper (absent)
+    ];
+
+    let ix = Instruction {
+        program_id: MPL_CORE_PROGRAM_ID,
+        accounts: metas,
+        data,
+    };
+
+    invoke(&ix, &account_infos)?;
+
+    round.nft_minted = true;
+
+    emit!(NftMinted {
+        round_id: round.id,
+        winner: accounts.winner.key(),
+        mint: accounts.asset.key(),
+    });
+
+    Ok(())
+}
+
+/// Body of `distribute_pot`, shared with `distribute_and_mint`.
+fn pay_out_pot<'info>(
+    accounts: &mut DistributePot<'info>,
+    winner_profile_bump: u8,
+    remaining_accounts: &[AccountInfo<'info>],
+    auto_close: bool,
+) -> Result<()> {
+    let winner_key = accounts.round.winner;
+    let won_at = accounts.round.won_at;
+    let round_id = accounts.round.id;
+    let is_token_round = accounts.round.is_token_round();
+
+    // Underfilled rounds go down the refund path instead.
+    require!(
+        !accounts.round.is_underfilled(),
+        SolPotError::MinPlayersNotMet
+    );
+    require!(
+        accounts.round.won_before_expiry(),
+        SolPotError::RoundExpired
+    );
+    let dispute_ends_at = accounts
+        .round
+        .won_at
+        .saturating_add(accounts.game_config.dispute_seconds);
+    require!(
+        Clock::get()?.unix_timestamp >= dispute_ends_at,
+        SolPotError::DisputePeriodActive
+    );
+    require!(
+        accounts.round.win_is_final(Clock::get()?.unix_timestamp),
+        SolPotError::RoundNotExpired
+    );
+    require!(
+        accounts.prize_vault.is_some() == accounts.round.is_vesting(),
+        SolPotError::PrizeVaultMismatch
+    );
+    if auto_close {
+        require!(!is_token_round, SolPotError::UnsupportedForTokenRound);
+        require!(accounts.authority.is_some(), SolPotError::Unauthorized);
+        // As in `close_round`: entries and tips can't be reclaimed once the
+        // round account is gone.
+        require!(
+            accounts.round.live_entries == 0,
+            SolPotError::EntriesNotReclaimed
+        );
+        require!(accounts.round.live_tips == 0, SolPotError::TipsNotReclaimed);
+    }
+
+    let (other_winners, other_profiles, other_winner_keys): (
+        &[AccountInfo<'info>],
+        &[AccountInfo<'info>],
+        Vec<Pubkey>,
+    ) = if accounts.round.allow_multiple_winners {
+        // Late correct guessers can still join the split until guessing ends.
+        let clock = Clock::get()?;
+        require!(
+            clock.unix_timestamp >= accounts.round.guessing_ends_at(),
+            SolPotError::RoundNotExpired
+        );
+        let winners = accounts
+            .winners
+            .as_ref()
+            .ok_or(SolPotError::WinnersAccountMismatch)?;
+        let other_winner_keys = winners.players[1..].to_vec();
+        require!(
+            remaining_accounts.len() == 2 * other_winner_keys.len(),
+            SolPotError::WinnerAccountsMismatch
+        );
+        let (other_winners, other_profiles) = remaining_accounts.split_at(other_winner_keys.len());
+        // Token rounds pass the winners' token accounts instead.
+        for (info, expected) in other_winners.iter().zip(&other_winner_keys) {
+            require!(info.is_writable, SolPotError::WinnerAccountsMismatch);
+            if is_token_round {
+                require_token_account_of(info, expected, &accounts.round.mint)?;
+            } else {
+                require_keys_eq!(info.key(), *expected, SolPotError::WinnerAccountsMismatch);
+            }
+        }
+        (other_winners, other_profiles, other_winner_keys)
+    } else {
+        (&[], &[], Vec::new())
+    };
+    let winner_count = other_winners.len() as u64 + 1;
+
+    let round_info = accounts.round.to_account_info();
+    let available = if is_token_round {
+        accounts
+            .vault
+            .as_ref()
+            .ok_or(SolPotError::TokenAccountMissing)?
+            .amount
+    } else {
+        available_lamports(&round_info)?
+    };
+    let PayoutPlan {
+        split:
+            PotSplit {
+                fee,
+                runner_up_amount,
+                winner_amount,
+                share,
+                first_share,
+            },
+        runner_up_is_winner,
+        pays_runner_up,
+        donation,
+    } = plan_payout(
+        &accounts.round,
+        &accounts.game_config,
+        accounts.winners.as_deref(),
+        available,
+    )?;
+    require!(
+        !runner_up_is_winner
+            || (accounts.runner_up.is_none() && accounts.runner_up_token_account.is_none()),
+        SolPotError::DuplicateRecipient
+    );
+    let runner_up_key = accounts.round.first_entrant;
+
+    if is_token_round {
+        let vault = accounts
+            .vault
+            .as_ref()
+            .ok_or(SolPotError::TokenAccountMissing)?;
+        let token_program = accounts
+            .token_program
+            .as_ref()
+            .ok_or(SolPotError::TokenAccountMissing)?;
+        let winner_token_account = accounts
+            .winner_token_account
+            .as_ref()
+            .ok_or(SolPotError::TokenAccountMissing)?;
+        let fee_receiver_token_account = accounts
+            .fee_receiver_token_account
+            .as_ref()
+            .ok_or(SolPotError::TokenAccountMissing)?;
+
+        if pays_runner_up {
+            let runner_up_token_account = accounts
+                .runner_up_token_account
+                .as_ref()
+                .ok_or(SolPotError::RunnerUpAccountMissing)?;
+            transfer_from_vault(
+                &accounts.round,
+                vault,
+                &runner_up_token_account.to_account_info(),
+                token_program,
+                runner_up_amount,
+            )?;
+        }
+        transfer_from_vault(
+            &accounts.round,
+            vault,
+            &winner_token_account.to_account_info(),
+            token_program,
+            first_share,
+        )?;
+        for info in other_winners {
+            transfer_from_vault(&accounts.round, vault, info, token_program, share)?;
+        }
+        transfer_from_vault(
+            &accounts.round,
+            vault,
+            &fee_receiver_token_account.to_account_info(),
+            token_program,
+            fee,
+        )?;
+    } else {
+        if pays_runner_up {
+            let runner_up = accounts
+                .runner_up
+                .as_ref()
+                .ok_or(SolPotError::RunnerUpAccountMissing)?;
+            move_lamports(&round_info, &runner_up.to_account_info(), runner_up_amount)?;
+        }
+        if let Some(prize_vault) = accounts.prize_vault.as_mut() {
+            // Vesting rounds lock the prize up; the winner pulls it out
+            // with `claim_vested`.
+            move_lamports(&round_info, &prize_vault.to_account_info(), first_share)?;
+            prize_vault.winner = winner_key;
+            prize_vault.total = first_share;
+            prize_vault.claimed = 0;
+            prize_vault.start = Clock::get()?.unix_timestamp;
+            prize_vault.cliff_seconds = accounts.round.vesting_cliff_seconds;
+            prize_vault.duration_seconds = accounts.round.vesting_duration_seconds;
+        } else {
+            move_lamports(&round_info, &accounts.winner, first_share)?;
+        }
+        for info in other_winners {
+            move_lamports(&round_info, info, share)?;
+        }
+        let fee_vault = &mut accounts.fee_vault;
+        move_lamports(&round_info, &fee_vault.to_account_info(), fee)?;
+        fee_vault.accrued_fees = fee_vault
+            .accrued_fees
+            .checked_add(fee)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+        if accounts.round.charity_mode {
+            let charity_wallet = accounts
+                .charity_wallet
+                .as_ref()
+                .ok_or(SolPotError::CharityWalletMismatch)?;
+            move_lamports(&round_info, &charity_wallet.to_account_info(), donation)?;
+
+            emit!(CharityDonation {
+                round_id,
+                charity_wallet: charity_wallet.key(),
+                amount: donation,
+            });
+        }
+
+        // The split hands out everything above rent; anything left means the
+        // accounting drifted.
+        require!(
+            available_lamports(&round_info)? == 0,
+            SolPotError::DistributionIncomplete
+        );
+    }
+
+    let round = &mut accounts.round;
+    round.pot_distributed = true;
+    round.pot_lamports = 0;
+
+    let game_config = &mut accounts.game_config;
+    game_config.total_paid_out = game_config
+        .total_paid_out
+        .checked_add(winner_amount)
+        .and_then(|v| v.checked_add(runner_up_amount))
+        .ok_or(SolPotError::ArithmeticOverflow)?;
+    game_config.total_fees = game_config
+        .total_fees
+        .checked_add(fee)
+        .ok_or(SolPotError::ArithmeticOverflow)?;
+
+    let game_config_key = accounts.game_config.key();
+    let winner_profile = &mut accounts.winner_profile;
+    winner_profile.game_config = game_config_key;
+    winner_profile.player = winner_key;
+    winner_profile.bump = winner_profile_bump;
+    winner_profile.wins = winner_profile
+        .wins
+        .checked_add(1)
+        .ok_or(SolPotError::ArithmeticOverflow)?;
+    winner_profile.total_winnings = winner_profile
+        .total_winnings
+        .checked_add(first_share)
+        .ok_or(SolPotError::ArithmeticOverflow)?;
+
+    let leaderboard = &mut accounts.leaderboard;
+    leaderboard.record_win(
+        winner_key,
+        first_share,
+        round_id,
+        won_at,
+        &accounts.game_config,
+    )?;
+    if !other_winners.is_empty() {
+        emit!(WinnerPaid {
+            round_id,
+            winner: winner_key,
+            amount: first_share,
+        });
+    }
+    for (player, profile_info) in other_winner_keys.iter().zip(other_profiles) {
+        leaderboard.record_win(*player, share, round_id, won_at, &accounts.game_config)?;
+        credit_profile_win(
+            profile_info,
+            &accounts.payer,
+            &accounts.system_program,
+            &game_config_key,
+            player,
+            share,
+        )?;
+        emit!(WinnerPaid {
+            round_id,
+            winner: *player,
+            amount: share,
+        });
+    }
+    let season_wins: Vec<(Pubkey, u64)> = std::iter::once((winner_key, first_share))
+        .chain(other_winner_keys.iter().map(|player| (*player, share)))
+        .collect();
+    record_season_wins(
+        &accounts.season,
+        &accounts.game_config,
+        round_id,
+        won_at,
+        &season_wins,
+    )?;
+
+    emit!(PotDistributed {
+        round_id,
+        winner: winner_key,
+        winner_amount,
+        fee_amount: fee,
+        winner_count: winner_count as u32,
+        runner_up: if pays_runner_up {
+            runner_up_key
+        } else {
+            Pubkey::default()
+        },
+        runner_up_amount,
+    });
+
+    if auto_close {
+        let clock = Clock::get()?;
+        let round = &accounts.round;
+        require!(
+            round.pot_lamports == 0 && !round.is_refund_path(clock.unix_timestamp),
+            SolPotError::RefundsPending
+        );
+
+        let game_config = &mut accounts.game_config;
+        game_config.open_rounds = game_config.open_rounds.saturating_sub(1);
+
+        let authority = accounts.authority.as_ref().unwrap();
+        accounts.round.close(authority.to_account_info())?;
+
+        emit!(RoundClosed { round_id });
+    }
+
+    Ok(())
+}
+
+/// Checks that `round` is done with (paid out, rolled over, or refunded in
+/// full) and has no entries or tip records left, then empties what remains
+/// of an unsolved round's pot: the authority's seed and bounties to
+/// `seed_receiver` and the rest (guess fees) to the fee vault, never to a
+/// delegate operating the round.
+fn settle_leftover_pot<'info>(
+    round: &mut Account<'info, Round>,
+    fee_vault: &mut Account<'info, FeeVault>,
+    seed_receiver: &AccountInfo<'info>,
+    now: i64,
+) -> Result<()> {
+    let refund_path = round.is_refund_path(now);
+    require!(
+        refund_path || (round.has_winner && round.pot_distributed),
+        SolPotError::RoundStillActive
+    );
+    require!(round.live_entries == 0, SolPotError::EntriesNotReclaimed);
+    require!(round.live_tips == 0, SolPotError::TipsNotReclaimed);
+
+    if refund_path && !round.pot_distributed {
+        require!(
+            round.refunded_count == round.player_count,
+            SolPotError::RefundsPending
+        );
+        require!(!round.tips_pending(), SolPotError::TipsPending);
+
+        let round_info = round.to_account_info();
+        let leftover = std::cmp::min(round.pot_lamports, available_lamports(&round_info)?);
+        let seeded = std::cmp::min(round.authority_funded_lamports(), leftover);
+        move_lamports(&round_info, seed_receiver, seeded)?;
+
+        let remainder = leftover - seeded;
+        move_lamports(&round_info, &fee_vault.to_account_info(), remainder)?;
+        fee_vault.accrued_fees = fee_vault
+            .accrued_fees
+            .checked_add(remainder)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+    }
+
+    round.pot_lamports = 0;
+    round.is_active = false;
+    Ok(())
+}
+
+/// Body of `submit_guess`, shared with `submit_guess_with_memo` and
+/// `submit_guess_bytes`.
+fn process_guess(
+    ctx: Context<SubmitGuess>,
+    guess: Vec<u8>,
+    proof: Vec<[u8; 32]>,
+    allowlist_proof: Vec<[u8; 32]>,
+    dictionary_proof: Vec<[u8; 32]>,
+    memo: String,
+) -> Result<GuessOutcome> {
+    require!(!ctx.accounts.game_config.paused, SolPotError::GamePaused);
+
+    let clock = Clock::get()?;
+    let round = &mut ctx.accounts.round;
+
+    require!(
+        round.reveal_window_seconds == 0,
+        SolPotError::CommitRevealRequired
+    );
+    require_guessing_open(round, clock.unix_timestamp)?;
+    require_allowlisted(round, &ctx.accounts.player.key(), &allowlist_proof)?;
+    require_not_blacklisted(&ctx.accounts.blacklist, &ctx.accounts.player.key())?;
+    require_entered(
+        &ctx.accounts.player_entry,
+        &round.key(),
+        &ctx.accounts.player.key(),
+    )?;
+    require_in_dictionary(round, &guess, &dictionary_proof)?;
+
+    let round_key = round.key();
+    let player_key = ctx.accounts.player.key();
+    let guess_record_info = ctx.accounts.guess_record.to_account_info();
+    let mut guess_record = load_guess_record(
+        &guess_record_info,
+        ctx.accounts
+            .rent_sponsor
+            .as_ref()
+            .unwrap_or(&ctx.accounts.player),
+        &ctx.accounts.system_program,
+        &[
+            GuessRecord::SEED,
+            round_key.as_ref(),
+            &round.generation.to_le_bytes(),
+            player_key.as_ref(),
+        ],
+    )?;
+    let attempt = record_attempt(
+        round,
+        &mut guess_record,
+        &ctx.accounts.player,
+        &ctx.accounts.system_program,
+        clock.unix_timestamp,
+    )?;
+    guess_record.try_serialize(&mut &mut guess_record_info.try_borrow_mut_data()?[..])?;
+    let player_profile = &mut ctx.accounts.player_profile;
+    player_profile.game_config = ctx.accounts.game_config.key();
+    player_profile.player = ctx.accounts.player.key();
+    player_profile.bump = ctx.bumps.player_profile;
+    player_profile.guesses_made = player_profile
+        .guesses_made
+        .checked_add(1)
+        .ok_or(SolPotError::ArithmeticOverflow)?;
+
+    let player = ctx.accounts.player.key();
+    let win_blocked = win_cooldown_active(
+        &ctx.accounts.game_config,
+        ctx.accounts.leaderboard.as_deref(),
+        &ctx.accounts.player_profile,
+        &player,
+        clock.unix_timestamp,
+    )?;
+    let is_correct = settle_guess(
+        round,
+        ctx.accounts.winners.as_deref_mut(),
+        Some(&mut ctx.accounts.player_profile),
+        player,
+        &guess,
+        &proof,
+        clock.unix_timestamp,
+        win_blocked,
+    )?;
+    let round_leaderboard = &mut ctx.accounts.round_leaderboard;
+    round_leaderboard.record_guess(player, attempt);
+    if is_correct {
+        if !win_blocked {
+            round_leaderboard.record_win(player, attempt);
+        }
+    } else {
+        ctx.accounts.guess_log.append(GuessLogEntry {
+            player,
+            guess_hash: answer_hash(round, &guess)?,
+            timestamp: clock.unix_timestamp,
+        });
+        divert_loss_fee(round, &mut ctx.accounts.jackpot, player)?;
+    }
+
+    emit!(GuessResult {
+        round_id: round.id,
+        player,
+        is_correct,
+        attempt,
+        slot: clock.slot,
+        total_guesses: round.total_guesses,
+        correct_guesses: round.correct_guesses,
+        win_cooldown_active: is_correct && win_blocked,
+        memo,
+    });
+
+    Ok(GuessOutcome {
+        is_correct,
+        attempt,
+        win_cooldown_active: is_correct && win_blocked,
+    })
+}
+
+/// Reads the player's `GuessRecord` for this run of the round, creating it
+/// on their first guess with `rent_payer` covering the rent.
+fn load_guess_record<'info>(
+    info: &AccountInfo<'info>,
+    rent_payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    seeds: &[&[u8]],
+) -> Result<GuessRecord> {
+    if *info.owner != crate::ID {
+        let bump = init_pda_account(rent_payer, info, system_program, seeds, GuessRecord::SIZE)?;
+        return Ok(GuessRecord {
+            count: 0,
+            commitment: [0u8; 32],
+            revealed: false,
+            committed_at: 0,
+            bump,
+            last_guess_at: 0,
+        });
+    }
+    GuessRecord::try_deserialize(&mut &info.try_borrow_data()?[..])
+}
+
+/// Drops control characters (newlines, escapes, ...) so a memo renders as a
+/// single plain line.
+fn sanitize_memo(memo: &str) -> String {
+    memo.chars().filter(|c| !c.is_control()).collect::<String>().trim().to_string()
+}
+
+/// Preconditions shared by every instruction that spends a guess attempt.
+fn require_guessing_open(round: &Round, now: i64) -> Result<()> {
+    require!(!round.cancelled, SolPotError::RoundCancelled);
+    // Checked before `is_active` so a late correct guesser learns the round
+    // was won rather than that it is inactive. Multi-winner rounds keep
+    // accepting guesses until guessing closes.
+    require!(
+        round.allow_multiple_winners || !round.has_winner,
+        SolPotError::RoundAlreadyWon
+    );
+    require!(round.is_active, SolPotError::RoundNotActive);
+    require!(round.has_started(now), SolPotError::RoundNotStarted);
+    require!(now < round.guesses_close_at(), SolPotError::RoundExpired);
+    Ok(())
+}
+
+/// Preconditions for `cancel_round`. Once guessing has closed, the round
+/// settles through its winner or the refund path instead.
+fn require_cancellable(round: &Round, now: i64) -> Result<()> {
+    require!(round.is_active, SolPotError::RoundNotActive);
+    require!(!round.has_winner, SolPotError::RoundAlreadyWon);
+    require!(now < round.guesses_close_at(), SolPotError::RoundExpired);
+    Ok(())
+}
+
+/// Counts a guess attempt against the player's limit, enforces the round's
+/// guess cooldown and collects the round's per-guess fee. Returns the
+/// 1-based attempt number.
+fn record_attempt<'info>(
+    round: &mut Account<'info, Round>,
+    guess_record: &mut GuessRecord,
+    player: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    now: i64,
+) -> Result<u32> {
+    require!(
+        guess_record.count < round.max_guesses,
+        SolPotError::GuessLimitReached
+    );
+    if round.guess_cooldown_seconds > 0 && guess_record.count > 0 {
+        require!(
+            now - guess_record.last_guess_at >= round.guess_cooldown_seconds,
+            SolPotError::GuessCooldown
+        );
+    }
+    guess_record.last_guess_at = now;
+    guess_record.count = guess_record
+        .count
+        .checked_add(1)
+        .ok_or(SolPotError::ArithmeticOverflow)?;
+
+    if round.guess_fee_lamports > 0 {
+        require!(
+            round.fits_pot_cap(round.guess_fee_lamports),
+            SolPotError::PotCapReached
+        );
+        transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: player.to_account_info(),
+                    to: round.to_account_info(),
+                },
+            ),
+            round.guess_fee_lamports,
+        )?;
+        let guess_fee_lamports = round.guess_fee_lamports;
+        round.add_to_pot(guess_fee_lamports)?;
+
+        emit!(GuessFeePaid {
+            round_id: round.id,
+            player: player.key(),
+            fee_lamports: round.guess_fee_lamports,
+            pot_lamports: round.pot_lamports,
+        });
+    }
+
+    Ok(guess_record.count)
+}
+
+/// Checks `guess` against the round's answer(s) and records `player` as a
+/// winner if it matches, stamping the win time on their profile for the win
+/// cooldown. Returns whether the guess was correct.
+fn settle_guess(
+    round: &mut Round,
+    winners: Option<&mut Winners>,
+    player_profile: Option<&mut PlayerProfile>,
+    player: Pubkey,
+    guess: &[u8],
+    proof: &[[u8; 32]],
+    guessed_at: i64,
+    win_blocked: bool,
+) -> Result<bool> {
+    let is_correct = is_answer(round, guess, proof)?;
+
+    round.total_guesses = round
+        .total_guesses
+        .checked_add(1)
+        .ok_or(SolPotError::ArithmeticOverflow)?;
+    if is_correct {
+        round.correct_guesses = round
+            .correct_guesses
+            .checked_add(1)
+            .ok_or(SolPotError::ArithmeticOverflow)?;
+        if win_blocked {
+            return Ok(true);
+        }
+        if round.allow_multiple_winners {
+            let winners = winners.ok_or(SolPotError::WinnersAccountMismatch)?;
+            require!(
+                !winners.players.contains(&player),
+                SolPotError::AlreadyWinner
+            );
+            require!(
+                winners.players.len() < Winners::MAX_WINNERS,
+                SolPotError::WinnersFull
+            );
+            winners.players.push(player);
+            if !round.has_winner {
+                round.winner = player;
+                round.has_winner = true;
+                round.won_at = guessed_at;
+            }
+        } else {
+            // Re-checked right before the write: whatever ran earlier in
+            // this instruction, a single-winner round never changes winner.
+            require!(!round.has_winner, SolPotError::RoundAlreadyWon);
+            round.winner = player;
+            round.has_winner = true;
+            round.won_at = guessed_at;
+            round.is_active = false;
+        }
+        if let Some(player_profile) = player_profile {
+            player_profile.last_win_at = std::cmp::max(player_profile.last_win_at, guessed_at);
+        }
+    }
+
+    Ok(is_correct)
+}
+
+/// How `settle_reveal` treated a revealed guess.
+#[derive(Debug, PartialEq, Eq)]
+enum RevealOutcome {
+    /// Scored like any guess. `displaced_winner` is set when a correct
+    /// reveal of an earlier commitment took a single-winner round's win.
+    Settled {
+        is_correct: bool,
+        displaced_winner: Option<Pubkey>,
+    },
+    /// Correct, but committed no earlier than the round's winner (or in a
+    /// win cooldown), so nothing changes.
+    LaterCommitment,
+}
+
+/// `settle_guess` for a guess revealed from a commitment made at
+/// `committed_at`. Once a single-winner round is won, only correct reveals
+/// are accepted: one committed before the winner's commitment replaces
+/// them, any other is a `LaterCommitment` no-op. This makes the winner the
+/// earliest committer whatever order the reveals land in.
+fn settle_reveal(
+    round: &mut Round,
+    winners: Option<&mut Winners>,
+    player_profile: Option<&mut PlayerProfile>,
+    player: Pubkey,
+    guess: &[u8],
+    proof: &[[u8; 32]],
+    committed_at: i64,
+    win_blocked: bool,
+) -> Result<RevealOutcome> {
+    let mut displaced_winner = None;
+    if !round.allow_multiple_winners && round.has_winner {
+        require!(
+            is_answer(round, guess, proof)?,
+            SolPotError::RoundAlreadyWon
+        );
+        if win_blocked || !round.commitment_beats_winner(committed_at) {
+            return Ok(RevealOutcome::LaterCommitment);
+        }
+        displaced_winner = Some(round.winner);
+        round.winner = Pubkey::default();
+        round.has_winner = false;
+    }
+    let is_correct = settle_guess(
+        round,
+        winners,
+        player_profile,
+        player,
+        guess,
+        proof,
+        committed_at,
+        win_blocked,
+    )?;
+    Ok(RevealOutcome::Settled {
+        is_correct,
+        displaced_winner,
+    })
+}
+
+/// Whether the game's win cooldown keeps `player` from winning with a guess
+/// made at `now`: a guessed win recorded on their profile, or a paid-out one
+/// (random draws included) on the leaderboard. Needs the leaderboard
+/// whenever a cooldown is set.
+fn win_cooldown_active(
+    game_config: &GameConfig,
+    leaderboard: Option<&Leaderboard>,
+    player_profile: &PlayerProfile,
+    player: &Pubkey,
+    now: i64,
+) -> Result<bool> {
+    let cooldown_seconds = game_config.win_cooldown_seconds;
+    if cooldown_seconds == 0 {
+        return Ok(false);
+    }
+    let leaderboard = leaderboard.ok_or(SolPotError::LeaderboardRequired)?;
+    Ok(player_profile.in_win_cooldown(cooldown_seconds, now)
+        || leaderboard.in_win_cooldown(player, cooldown_seconds, now))
+}
+
+/// Whether `guess` is the round's answer, or one of its answer set.
+fn is_answer(round: &Round, guess: &[u8], proof: &[[u8; 32]]) -> Result<bool> {
+    let guess_hash = answer_hash(round, guess)?;
+    Ok(match merkle_membership(guess_hash, proof, round.answers_merkle_root)? {
+        Some(is_member) => is_member,
+        None => guess_hash == round.word_hash,
+    })
+}
+
+/// Fails with `NotAllowlisted` unless `player` is on an invite-only round's
+/// allowlist. Open rounds take no proof.
+fn require_allowlisted(round: &Round, player: &Pubkey, proof: &[[u8; 32]]) -> Result<()> {
+    let leaf = hash(player.as_ref()).to_bytes();
+    require!(
+        merkle_membership(leaf, proof, round.allowlist_root)? != Some(false),
+        SolPotError::NotAllowlisted
+    );
+    Ok(())
+}
+
+/// Fails with `WordNotInDictionary` unless `guess` is in the round's
+/// dictionary, when it has one.
+fn require_in_dictionary(round: &Round, guess: &[u8], proof: &[[u8; 32]]) -> Result<()> {
+    let leaf = answer_hash(round, guess)?;
+    require!(
+        merkle_membership(leaf, proof, round.dictionary_root)? != Some(false),
+        SolPotError::WordNotInDictionary
+    );
+    Ok(())
+}
+
+/// Checks `leaf` against an optional merkle `root`, returning `None` when
+/// the root is zeroed (unset). Proofs are capped at
+/// `Round::MAX_MERKLE_PROOF_LEN` and must be empty when there's no root.
+fn merkle_membership(leaf: [u8; 32], proof: &[[u8; 32]], root: [u8; 32]) -> Result<Option<bool>> {
+    let has_root = root != [0u8; 32];
+    require!(
+        proof.len() <= Round::MAX_MERKLE_PROOF_LEN && (has_root || proof.is_empty()),
+        SolPotError::MerkleProofInvalid
+    );
+    Ok(has_root.then(|| verify_merkle_proof(leaf, proof, root)))
+}
+
+/// Hashes a guess in the canonical form for the round's `guess_kind`, so it
+/// can be compared with the round's `word_hash` or answer set.
+fn answer_hash(round: &Round, guess: &[u8]) -> Result<[u8; 32]> {
+    Ok(match round.guess_kind {
+        Round::GUESS_KIND_BYTES => hash(guess).to_bytes(),
+        Round::GUESS_KIND_NUMBER => hash(&canonical_number(guess)?.to_le_bytes()).to_bytes(),
+        Round::GUESS_KIND_HASHED => {
+            require!(guess.len() == 32, SolPotError::InvalidGuessEncoding);
+            hash(guess).to_bytes()
+        }
+        _ => {
+            let guess =
+                std::str::from_utf8(guess).map_err(|_| SolPotError::InvalidGuessEncoding)?;
+            guess_hash(guess, round.normalization_flags)
+        }
+    })
+}
+
+/// Hashes a guess after `normalize_guess`, so it can be compared with the
+/// round's `word_hash` or answer set.
+fn guess_hash(guess: &str, normalization_flags: u8) -> [u8; 32] {
+    hash(normalize_guess(guess, normalization_flags).as_bytes()).to_bytes()
+}
+
+/// Reads a little-endian unsigned number of any width, so `[7]` and
+/// `[7, 0, 0, 0]` are the same guess. Fails unless it fits in a u64.
+fn canonical_number(guess: &[u8]) -> Result<u64> {
+    let significant = guess.len() - guess.iter().rev().take_while(|b| **b == 0).count();
+    require!(significant <= 8, SolPotError::InvalidGuessEncoding);
+    let mut bytes = [0u8; 8];
+    bytes[..significant].copy_from_slice(&guess[..significant]);
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Canonical form a guess is hashed in. Guesses are always lowercased; the
+/// `Round::NORMALIZE_*` flags additionally apply NFKC (folding accents
+/// composed differently and full-width forms), collapse whitespace runs to
+/// a single space and trim the ends, in that order. Off-chain tooling must
+/// hash answers with the same function when building `word_hash`.
+pub fn normalize_guess(guess: &str, flags: u8) -> String {
+    let mut normalized: String = if flags & Round::NORMALIZE_NFKC != 0 {
+        guess.nfkc().collect()
+    } else {
+        guess.to_string()
+    };
+    if flags & Round::NORMALIZE_COLLAPSE_WHITESPACE != 0 {
+        let mut collapsed = String::with_capacity(normalized.len());
+        let mut in_whitespace = false;
+        for c in normalized.chars() {
+            if c.is_whitespace() {
+                if !in_whitespace {
+                    collapsed.push(' ');
+                }
+                in_whitespace = true;
+            } else {
+                collapsed.push(c);
+                in_whitespace = false;
+            }
+        }
+        normalized = collapsed;
+    }
+    if flags & Round::NORMALIZE_TRIM != 0 {
+        normalized = normalized.trim().to_string();
+    }
+    normalized.to_lowercase()
+}
+
+/// Verifies that `leaf` is included under `root`. Pairs are hashed in sorted
+/// order (`hash(min(a, b) || max(a, b))`), so proofs carry no left/right flags.
+pub fn verify_merkle_proof(leaf: [u8; 32], proof: &[[u8; 32]], root: [u8; 32]) -> bool {
+    let computed = proof
+        .iter()
+        .fold(leaf, |node, sibling| hash_merkle_pair(&node, sibling));
+    computed == root
+}
+
+/// Looks `slot` up in raw SlotHashes sysvar data: a u64 entry count, then
+/// (u64 slot, 32-byte hash) entries, newest first.
+fn find_slot_hash(data: &[u8], slot: u64) -> Option<[u8; 32]> {
+    let count = u64::from_le_bytes(data.get(..8)?.try_into().ok()?) as usize;
+    data.get(8..)?
+        .chunks_exact(40)
+        .take(count)
+        .find(|entry| entry[..8] == slot.to_le_bytes())
+        .and_then(|entry| entry[8..].try_into().ok())
+}
+
+fn hash_merkle_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    if a <= b {
+        hashv(&[a.as_ref(), b.as_ref()]).to_bytes()
+    } else {
+        hashv(&[b.as_ref(), a.as_ref()]).to_bytes()
+    }
+}
+
+/// Transfers `amount` of a token round's mint out of its vault, signed by
+/// the round PDA.
+fn transfer_from_vault<'info>(
+    round: &Account<'info, Round>,
+    vault: &Account<'info, TokenAccount>,
+    to: &AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    let id_bytes = round.id.to_le_bytes();
+    let bump = [round.bump];
+    let signer_seeds: &[&[u8]] = &[Round::SEED, round.game_config.as_ref(), &id_bytes, &bump];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            token::Transfer {
+                from: vault.to_account_info(),
+                to: to.clone(),
+                authority: round.to_account_info(),
+            },
+            &[signer_seeds],
+        ),
+        amount,
+    )
+}
+
+/// Checks that `info` is a token account of `mint` owned by `owner`.
+fn require_token_account_of(info: &AccountInfo, owner: &Pubkey, mint: &Pubkey) -> Result<()> {
+    require_keys_eq!(*info.owner, token::ID, SolPotError::TokenMintMismatch);
+    let data = info.try_borrow_data()?;
+    let account = TokenAccount::try_deserialize(&mut &data[..])?;
+    require_keys_eq!(account.mint, *mint, SolPotError::TokenMintMismatch);
+    require_keys_eq!(account.owner, *owner, SolPotError::WinnerAccountsMismatch);
+    Ok(())
+}
+
+/// How `distribute_pot` carves up a won pot.
+#[derive(Debug, PartialEq, Eq)]
+struct PotSplit {
+    /// Truncated protocol fee plus everything held beyond the pot.
+    fee: u64,
+    runner_up_amount: u64,
+    /// Total across all winners.
+    winner_amount: u64,
+    /// Paid to each winner after the first.
+    share: u64,
+    /// Paid to the first winner, including the split's remainder.
+    first_share: u64,
+}
+
+/// Splits `available` (the round's balance above rent, or its token vault)
+/// for a pot of `pot`. The fee and runner-up share round down in the
+/// winners' favor; the surplus of `available` over `pot` is added to the
+/// fee so nothing is left behind. Returns `None` on overflow.
+fn split_pot(
+    pot: u64,
+    available: u64,
+    fee_bps: u16,
+    runner_up_bps: u16,
+    winner_count: u64,
+) -> Option<PotSplit> {
+    let distributable = std::cmp::min(pot, available);
+    let surplus = available - distributable;
+
+    let fee = distributable.checked_mul(fee_bps as u64)? / 10000;
+    let runner_up_amount = distributable.checked_mul(runner_up_bps as u64)? / 10000;
+    let winner_amount = distributable
+        .checked_sub(fee)?
+        .checked_sub(runner_up_amount)?;
+    let share = winner_amount.checked_div(winner_count)?;
+    let first_share = winner_amount - share * (winner_count - 1);
+
+    Some(PotSplit {
+        fee: fee.checked_add(surplus)?,
+        runner_up_amount,
+        winner_amount,
+        share,
+        first_share,
+    })
+}
+
+/// A won round's payout as `distribute_pot` would make it now.
+#[derive(Debug, PartialEq, Eq)]
+struct PayoutPlan {
+    split: PotSplit,
+    /// The first entrant is among the winners, so gets no runner-up share.
+    runner_up_is_winner: bool,
+    pays_runner_up: bool,
+    /// A charity round's share for its charity wallet, in place of the fee.
+    donation: u64,
+}
+
+/// Works out how `distribute_pot` splits `available` (the round's balance
+/// above rent, or its token vault). `preview_distribution` runs the same
+/// function, so the preview always matches the real payout.
+fn plan_payout(
+    round: &Round,
+    game_config: &GameConfig,
+    winners: Option<&Winners>,
+    available: u64,
+) -> Result<PayoutPlan> {
+    // The house already put up its half of a bonus pot. A charity round's
+    // share is split off in the fee's place and redirected below.
+    let fee_bps = if round.charity_mode {
+        round.charity_basis_points
+    } else if round.is_bonus {
+        0
+    } else {
+        round.fee_basis_points(game_config.fee_basis_points)
+    };
+    let winner_count = if round.allow_multiple_winners {
+        winners
+            .ok_or(SolPotError::WinnersAccountMismatch)?
+            .players
+            .len()
+            .max(1) as u64
+    } else {
+        1
+    };
+
+    let runner_up_key = round.first_entrant;
+    let runner_up_is_winner = runner_up_key == round.winner
+        || winners.is_some_and(|winners| winners.players.contains(&runner_up_key));
+    let pays_runner_up = game_config.runner_up_basis_points > 0
+        && runner_up_key != Pubkey::default()
+        && !runner_up_is_winner;
+
+    require_distributable(round.pot_lamports, available, game_config.dust_tolerance_lamports)?;
+    let mut split = split_pot(
+        round.pot_lamports,
+        available,
+        fee_bps,
+        if pays_runner_up {
+            game_config.runner_up_basis_points
+        } else {
+            0
+        },
+        winner_count,
+    )
+    .ok_or(SolPotError::ArithmeticOverflow)?;
+    let donation = if round.charity_mode {
+        std::mem::take(&mut split.fee)
+    } else {
+        0
+    };
+
+    Ok(PayoutPlan {
+        split,
+        runner_up_is_winner,
+        pays_runner_up,
+        donation,
+    })
+}
+
+/// Refuses to pay out a pot the round can't actually cover (beyond
+/// `dust_tolerance`), rather than quietly shorting the winner and marking
+/// the pot distributed.
+fn require_distributable(pot: u64, available: u64, dust_tolerance: u64) -> Result<()> {
+    require!(
+        pot.saturating_sub(available) <= dust_tolerance,
+        SolPotError::InsufficientDistributable
+    );
+    Ok(())
+}
+
+/// Lamports held by a program-owned account above its rent-exempt minimum.
+fn available_lamports(info: &AccountInfo) -> Result<u64> {
+    surplus_over_rent(&Rent::get()?, info.lamports(), info.data_len())
+}
+
+/// What `lamports` leaves over the rent-exempt minimum for `data_len` bytes.
+fn surplus_over_rent(rent: &Rent, lamports: u64, data_len: usize) -> Result<u64> {
+    let available = lamports
+        .checked_sub(rent.minimum_balance(data_len))
+        .ok_or(SolPotError::ArithmeticOverflow)?;
+    Ok(available)
+}
+
+/// Decodes leaderboard account `data` in the current layout, if the bytes
+/// form a consistent board in it: the entries fit `capacity`, each has its
+/// streaks within its wins, and `bump` follows them. Reading a board in the
+/// wrong layout misaligns every entry after the first, and the bump along
+/// with them.
+fn decode_leaderboard(data: &[u8], bump: u8) -> Option<Leaderboard> {
+    let mut rest = data.get(8..)?;
+    let game_config = Pubkey::deserialize(&mut rest).ok()?;
+    let capacity = u32::deserialize(&mut rest).ok()?;
+    let entries = Vec::<LeaderboardEntry>::deserialize(&mut rest).ok()?;
+    let stored_bump = u8::deserialize(&mut rest).ok()?;
+    let consistent = stored_bump == bump
+        && entries.len() <= capacity as usize
+        && entries
+            .iter()
+            .all(|e| e.current_streak <= e.best_streak && e.best_streak <= e.wins);
+    consistent.then_some(Leaderboard {
+        game_config,
+        capacity,
+        entries,
+        bump,
+    })
+}
+
+/// Decodes leaderboard account `data` in the original program's layout:
+/// `game_config`, then `LegacyLeaderboardEntry`s, then `bump`, with room
+/// for `Leaderboard::INITIAL_CAPACITY` entries. `None` unless the entries
+/// fit and `bump` follows them.
+fn decode_legacy_leaderboard(data: &[u8], bump: u8) -> Option<Leaderboard> {
+    let mut rest = data.get(8..)?;
+    let game_config = Pubkey::deserialize(&mut rest).ok()?;
+    let entries = Vec::<LegacyLeaderboardEntry>::deserialize(&mut rest).ok()?;
+    let stored_bump = u8::deserialize(&mut rest).ok()?;
+    let capacity = Leaderboard::INITIAL_CAPACITY;
+    (stored_bump == bump && entries.len() <= capacity as usize).then(|| Leaderboard {
+        game_config,
+        capacity,
+        entries: entries.into_iter().map(Into::into).collect(),
+        bump,
+    })
+}
+
+/// Grows the program-owned `account` to at least `new_len` bytes, with
+/// `payer` topping up its rent. The added bytes are zeroed, which is how
+/// fields appended to a layout read before migration fills them in.
+fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    new_len: usize,
+) -> Result<()> {
+    if account.data_len() >= new_len {
+        return Ok(());
+    }
+
+    let shortfall = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(account.lamports());
+    if shortfall > 0 {
+        transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: payer.to_account_info(),
+                    to: account.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    account.realloc(new_len, true)?;
+    Ok(())
+}
+
+/// Creates the program-owned PDA `target` derived from `seeds`, funded by
+/// `payer`, and returns its bump. The caller serializes the account data.
+fn init_pda_account<'info>(
+    payer: &Signer<'info>,
+    target: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    seeds: &[&[u8]],
+    space: usize,
+) -> Result<u8> {
+    let (expected, bump) = Pubkey::find_program_address(seeds, &crate::ID);
+    require_keys_eq!(target.key(), expected, SolPotError::PdaMismatch);
+    require!(target.is_writable, SolPotError::PdaMismatch);
+
+    let bump_seed = [bump];
+    let mut signer_seeds = seeds.to_vec();
+    signer_seeds.push(&bump_seed);
+    let rent = Rent::get()?.minimum_balance(space);
+    let funded = target.lamports();
+    if funded == 0 {
+        create_account(
+            CpiContext::new_with_signer(
+                system_program.to_account_info(),
+                CreateAccount {
+                    from: payer.to_account_info(),
+                    to: target.clone(),
+                },
+                &[&signer_seeds[..]],
+            ),
+            rent,
+            space as u64,
+            &crate::ID,
+        )?;
+        return Ok(bump);
+    }
+
+    // Lamports sent to the address ahead of time would make
+    // `create_account` fail; like Anchor's `init`, top the account up and
+    // take it over instead. An account that already holds data fails here.
+    if funded < rent {
+        transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: payer.to_account_info(),
+                    to: target.clone(),
+                },
+            ),
+            rent - funded,
+        )?;
+    }
+    allocate(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            Allocate {
+                account_to_allocate: target.clone(),
+            },
+            &[&signer_seeds[..]],
+        ),
+        space as u64,
+    )?;
+    assign(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            Assign {
+                account_to_assign: target.clone(),
+            },
+            &[&signer_seeds[..]],
+        ),
+        &crate::ID,
+    )?;
+
+    Ok(bump)
+}
+
+/// Credits a referral to `referrer`'s `ReferralStats`, creating the PDA on
+/// their first referral.
+fn record_referral<'info>(
+    payer: &Signer<'info>,
+    stats_info: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    referrer: Pubkey,
+    amount: u64,
+) -> Result<()> {
+    let mut stats = if stats_info.data_is_empty() {
+        let bump = init_pda_account(
+            payer,
+            stats_info,
+            system_program,
+            &[ReferralStats::SEED, referrer.as_ref()],
+            ReferralStats::SIZE,
+        )?;
+        ReferralStats {
+            referrer,
+            bump,
+            ..Default::default()
+        }
+    } else {
+        require_keys_eq!(*stats_info.owner, crate::ID, SolPotError::PdaMismatch);
+        let stats = ReferralStats::try_deserialize(&mut &stats_info.try_borrow_data()?[..])?;
+        require_keys_eq!(stats.referrer, referrer, SolPotError::PdaMismatch);
+        stats
+    };
+
+    stats.referral_count = stats
+        .referral_count
+        .checked_add(1)
+        .ok_or(SolPotError::ArithmeticOverflow)?;
+    stats.total_earned = stats
+        .total_earned
+        .checked_add(amount)
+        .ok_or(SolPotError::ArithmeticOverflow)?;
+    stats.try_serialize(&mut &mut stats_info.try_borrow_mut_data()?[..])?;
+    Ok(())
+}
+
+/// Moves the jackpot's share of a wrong guess's fee out of the round's pot.
+fn divert_loss_fee<'info>(
+    round: &mut Account<'info, Round>,
+    jackpot: &mut Account<'info, Jackpot>,
+    player: Pubkey,
+) -> Result<()> {
+    let amount = round
+        .guess_fee_lamports
+        .checked_mul(jackpot.loss_fee_to_jackpot_bps as u64)
+        .and_then(|v| v.checked_div(10000))
+        .ok_or(SolPotError::ArithmeticOverflow)?;
+    if amount == 0 {
+        return Ok(());
+    }
+
+    move_lamports(&round.to_account_info(), &jackpot.to_account_info(), amount)?;
+    round.pot_lamports = round
+        .pot_lamports
+        .checked_sub(amount)
+        .ok_or(SolPotError::ArithmeticOverflow)?;
+    jackpot.jackpot_balance = jackpot
+        .jackpot_balance
+        .checked_add(amount)
+        .ok_or(SolPotError::ArithmeticOverflow)?;
+
+    emit!(JackpotContribution {
+        round_id: round.id,
+        player,
+        amount,
+        jackpot_balance: jackpot.jackpot_balance,
+    });
+    Ok(())
+}
+
+/// Moves lamports out of a program-owned account by adjusting balances
+/// directly (the System `transfer` CPI can't debit accounts with data).
+fn move_lamports<'info>(
+    from: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    **from.try_borrow_mut_lamports()? = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(SolPotError::ArithmeticOverflow)?;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(SolPotError::ArithmeticOverflow)?;
+    Ok(())
+}
+
+/// Builds Metaplex Core `CreateV1` instruction data by hand:
+/// discriminator (0) + data_state (0 = AccountState) + name + uri +
+/// `plugins: Option<Vec<PluginAuthorityPair>>`. Plugins are always `Some`,
+/// possibly empty, to match the SDK's serialization. `royalties` is
+/// `(basis_points, creator)` with the creator taking 100%.
+pub fn core_create_v1_data(
+    name: &str,
+    uri: &str,
+    royalties: Option<(u16, Pubkey)>,
+    attributes: &[NftAttribute],
+) -> Vec<u8> {
+    const ROYALTIES_PLUGIN: u8 = 0;
+    const ATTRIBUTES_PLUGIN: u8 = 6;
+    const RULE_SET_NONE: u8 = 0;
+    const OPTION_NONE: u8 = 0;
+    const OPTION_SOME: u8 = 1;
+
+    let mut data: Vec<u8> = vec![0u8, 0u8];
+    push_borsh_string(&mut data, name);
+    push_borsh_string(&mut data, uri);
+
+    let plugin_count = royalties.is_some() as u32 + !attributes.is_empty() as u32;
+    data.push(OPTION_SOME);
+    data.extend_from_slice(&plugin_count.to_le_bytes());
+
+    // Each PluginAuthorityPair is a Plugin followed by `authority: None`,
+    // which leaves the plugin's default authority in place.
+    if let Some((basis_points, creator)) = royalties {
+        data.push(ROYALTIES_PLUGIN);
+        data.extend_from_slice(&basis_points.to_le_bytes());
+        data.extend_from_slice(&1u32.to_le_bytes()); // creators: Vec<Creator>
+        data.extend_from_slice(creator.as_ref());
+        data.push(100); // percentage
+        data.push(RULE_SET_NONE);
+        data.push(OPTION_NONE);
+    }
+    if !attributes.is_empty() {
+        data.push(ATTRIBUTES_PLUGIN);
+        data.extend_from_slice(&(attributes.len() as u32).to_le_bytes());
+        for attribute in attributes {
+            push_borsh_string(&mut data, &attribute.key);
+            push_borsh_string(&mut data, &attribute.value);
+        }
+        data.push(OPTION_NONE);
+    }
+
+    data
+}
+
+/// Borsh `String`: u32 LE length followed by the bytes.
+fn push_borsh_string(data: &mut Vec<u8>, value: &str) {
+    data.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    data.extend_from_slice(value.as_bytes());
+}
+
+// ── Account Contexts ────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct InitializeGame<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = GameConfig::SIZE,
+        seeds = [GameConfig::SEED],
+        bump,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Leaderboard::SIZE,
+        seeds = [Leaderboard::SEED, game_config.key().as_ref()],
+        bump,
+    )]
+    pub leaderboard: Account<'info, Leaderboard>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = FeeVault::SIZE,
+        seeds = [FeeVault::SEED, game_config.key().as_ref()],
+        bump,
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Jackpot::SIZE,
+        seeds = [Jackpot::SEED, game_config.key().as_ref()],
+        bump,
+    )]
+    pub jackpot: Account<'info, Jackpot>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ManageBlacklist<'info> {
+    #[account(
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+        has_one = authority @ SolPotError::Unauthorized,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = Blacklist::SIZE,
+        seeds = [Blacklist::SEED, game_config.key().as_ref()],
+        bump,
+    )]
+    pub blacklist: Box<Account<'info, Blacklist>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct StartSeason<'info> {
+    #[account(
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+        has_one = authority @ SolPotError::Unauthorized,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = Season::SIZE,
+        seeds = [Season::SEED, game_config.key().as_ref()],
+        bump,
+    )]
+    pub season: Box<Account<'info, Season>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EndSeason<'info> {
+    #[account(
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+        has_one = authority @ SolPotError::Unauthorized,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [Season::SEED, game_config.key().as_ref()],
+        bump = season.bump,
+    )]
+    pub season: Box<Account<'info, Season>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+        has_one = authority @ SolPotError::Unauthorized,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(new_max: u32)]
+pub struct ResizeLeaderboard<'info> {
+    #[account(
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+        has_one = authority @ SolPotError::Unauthorized,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [Leaderboard::SEED, game_config.key().as_ref()],
+        bump = leaderboard.bump,
+        realloc = Leaderboard::space(new_max),
+        realloc::payer = authority,
+        realloc::zero = false,
+    )]
+    pub leaderboard: Account<'info, Leaderboard>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateGameConfig<'info> {
+    /// CHECK: May not deserialize until migrated; `migrate_game_config`
+    /// checks the owner, discriminator and stored authority by hand
+    #[account(
+        mut,
+        seeds = [GameConfig::SEED],
+        bump,
+    )]
+    pub game_config: UncheckedAccount<'info>,
+
+    /// CHECK: The game's `FeeVault` PDA; created by `migrate_game_config`
+    /// if it doesn't exist yet
+    #[account(
+        mut,
+        seeds = [FeeVault::SEED, game_config.key().as_ref()],
+        bump,
+    )]
+    pub fee_vault: UncheckedAccount<'info>,
+
+    /// CHECK: The game's `Jackpot` PDA; created by `migrate_game_config`
+    /// if it doesn't exist yet
+    #[account(
+        mut,
+        seeds = [Jackpot::SEED, game_config.key().as_ref()],
+        bump,
+    )]
+    pub jackpot: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateRound<'info> {
+    #[account(
+        mut,
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+        has_one = authority @ SolPotError::Unauthorized,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    /// CHECK: May not deserialize until migrated; `migrate_round` checks
+    /// the owner, discriminator and PDA by hand
+    #[account(mut)]
+    pub round: UncheckedAccount<'info>,
+
+    /// CHECK: The round's `GuessLog` PDA; created by `migrate_round` if it
+    /// doesn't exist yet
+    #[account(
+        mut,
+        seeds = [GuessLog::SEED, round.key().as_ref()],
+        bump,
+    )]
+    pub guess_log: UncheckedAccount<'info>,
+
+    /// CHECK: The round's `RoundLeaderboard` PDA; created by
+    /// `migrate_round` if it doesn't exist yet
+    #[account(
+        mut,
+        seeds = [RoundLeaderboard::SEED, round.key().as_ref()],
+        bump,
+    )]
+    pub round_leaderboard: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpgradeLeaderboard<'info> {
+    #[account(
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+        has_one = authority @ SolPotError::Unauthorized,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    /// CHECK: May not deserialize until upgraded; `upgrade_leaderboard`
+    /// checks the owner and discriminator by hand
+    #[account(
+        mut,
+        seeds = [Leaderboard::SEED, game_config.key().as_ref()],
+        bump,
+    )]
+    pub leaderboard: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthorityTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(params: CreateRoundParams)]
+pub struct CreateRound<'info> {
+    #[account(
+        mut,
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+        has_one = authority,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Round::SIZE,
+        seeds = [
+            Round::SEED,
+            game_config.key().as_ref(),
+            &game_config.round_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub round: Box<Account<'info, Round>>,
+
+    /// Only provided for rounds that allow multiple winners.
+    #[account(
+        init,
+        payer = authority,
+        space = Winners::SIZE,
+        seeds = [Winners::SEED, round.key().as_ref()],
+        bump,
+    )]
+    pub winners: Option<Account<'info, Winners>>,
+
+    /// Only provided for random-fallback rounds.
+    #[account(
+        init,
+        payer = authority,
+        space = Participants::SIZE,
+        seeds = [Participants::SEED, round.key().as_ref()],
+        bump,
+    )]
+    pub participants: Option<Box<Account<'info, Participants>>>,
+
+    /// Only provided for rounds with a vesting prize.
+    #[account(
+        init,
+        payer = authority,
+        space = PrizeVault::SIZE,
+        seeds = [PrizeVault::SEED, round.key().as_ref()],
+        bump,
+    )]
+    pub prize_vault: Option<Box<Account<'info, PrizeVault>>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = GuessLog::SIZE,
+        seeds = [GuessLog::SEED, round.key().as_ref()],
+        bump,
+    )]
+    pub guess_log: Box<Account<'info, GuessLog>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = RoundLeaderboard::space(params.max_players),
+        seeds = [RoundLeaderboard::SEED, round.key().as_ref()],
+        bump,
+    )]
+    pub round_leaderboard: Box<Account<'info, RoundLeaderboard>>,
+
+    /// Token games only: the game mint.
+    pub mint: Option<Box<Account<'info, Mint>>>,
+
+    /// Token games only: the round's pot vault.
+    #[account(
+        init,
+        payer = authority,
+        seeds = [Round::VAULT_SEED, round.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = round,
+    )]
+    pub vault: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Option<Program<'info, Token>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BatchCreateRounds<'info> {
+    #[account(
+        mut,
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+        has_one = authority,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(expected_fee: u64, referrer: Option<Pubkey>)]
+pub struct EnterRound<'info> {
+    #[account(
+        mut,
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            Round::SEED,
+            round.game_config.as_ref(),
+            &round.id.to_le_bytes(),
+        ],
+        bump = round.bump,
+        constraint = round.game_config == game_config.key(),
+    )]
+    pub round: Box<Account<'info, Round>>,
+
+    /// CHECK: Created by `enter_round`, which fails with `AlreadyEntered`
+    /// if it exists; paid for by `rent_sponsor` when one signs
+    #[account(
+        mut,
+        seeds = [
+            PlayerEntry::SEED,
+            round.key().as_ref(),
+            &round.generation.to_le_bytes(),
+            player.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub player_entry: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = PlayerProfile::SIZE,
+        seeds = [
+            PlayerProfile::SEED,
+            game_config.key().as_ref(),
+            player.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub player_profile: Box<Account<'info, PlayerProfile>>,
+
+    /// CHECK: The game's `Blacklist` PDA, checked by
+    /// `require_not_blacklisted`; it may not exist yet
+    #[account(
+        seeds = [Blacklist::SEED, game_config.key().as_ref()],
+        bump,
+    )]
+    pub blacklist: UncheckedAccount<'info>,
+
+    /// Required for random-fallback rounds.
+    #[account(
+        mut,
+        seeds = [Participants::SEED, round.key().as_ref()],
+        bump = participants.bump,
+    )]
+    pub participants: Option<Box<Account<'info, Participants>>>,
+
+    #[account(
+        mut,
+        seeds = [RoundLeaderboard::SEED, round.key().as_ref()],
+        bump = round_leaderboard.bump,
+    )]
+    pub round_leaderboard: Box<Account<'info, RoundLeaderboard>>,
+
+    /// Only passed to claim the loyalty discount.
+    #[account(
+        seeds = [Leaderboard::SEED, game_config.key().as_ref()],
+        bump = leaderboard.bump,
+    )]
+    pub leaderboard: Option<Box<Account<'info, Leaderboard>>>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// Pays the `PlayerEntry` rent instead of the player, so operators can
+    /// subsidize onboarding. The rent still goes to the player when the
+    /// entry is closed.
+    #[account(mut)]
+    pub rent_sponsor: Option<Signer<'info>>,
+
+    /// Token games only: pays the entry fee.
+    #[account(
+        mut,
+        constraint = player_token_account.owner == player.key() @ SolPotError::Unauthorized,
+        constraint = player_token_account.mint == round.mint @ SolPotError::TokenMintMismatch,
+    )]
+    pub player_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Token games only.
+    #[account(mut, address = round.vault)]
+    pub vault: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Gated rounds only: the player's account for the round's gate mint.
+    pub gate_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// CHECK: Receives the referral cut; verified against `referrer`
+    #[account(
+        mut,
+        constraint = Some(referrer_account.key()) == referrer @ SolPotError::Unauthorized,
+    )]
+    pub referrer_account: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: The referrer's `ReferralStats` PDA, created on first use and
+    /// verified in the handler
+    #[account(mut)]
+    pub referral_stats: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitGuess<'info> {
+    #[account(
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            Round::SEED,
+            round.game_config.as_ref(),
+            &round.id.to_le_bytes(),
+        ],
+        bump = round.bump,
+        constraint = round.game_config == game_config.key(),
+    )]
+    pub round: Account<'info, Round>,
+
+    /// CHECK: Verified by `require_entered` so a missing entry surfaces as
+    /// `NotEntered`
+    #[account(
+        seeds = [
+            PlayerEntry::SEED,
+            round.key().as_ref(),
+            &round.generation.to_le_bytes(),
+            player.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub player_entry: UncheckedAccount<'info>,
+
+    /// CHECK: Loaded, or created on the player's first guess, by
+    /// `load_guess_record`; paid for by `rent_sponsor` when one signs
+    #[account(
+        mut,
+        seeds = [
+            GuessRecord::SEED,
+            round.key().as_ref(),
+            &round.generation.to_le_bytes(),
+            player.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub guess_record: UncheckedAccount<'info>,
+
+    /// Normally created by `enter_round`; players who entered before
+    /// profiles existed get theirs on their next guess.
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = PlayerProfile::SIZE,
+        seeds = [
+            PlayerProfile::SEED,
+            game_config.key().as_ref(),
+            player.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub player_profile: Box<Account<'info, PlayerProfile>>,
+
+    /// CHECK: The game's `Blacklist` PDA, checked by
+    /// `require_not_blacklisted`; it may not exist yet
+    #[account(
+        seeds = [Blacklist::SEED, game_config.key().as_ref()],
+        bump,
+    )]
+    pub blacklist: UncheckedAccount<'info>,
+
+    /// Required for rounds that allow multiple winners.
+    #[account(
+        mut,
+        seeds = [Winners::SEED, round.key().as_ref()],
+        bump = winners.bump,
+    )]
+    pub winners: Option<Account<'info, Winners>>,
+
+    #[account(
+        mut,
+        seeds = [GuessLog::SEED, round.key().as_ref()],
+        bump = guess_log.bump,
+    )]
+    pub guess_log: Box<Account<'info, GuessLog>>,
+
+    #[account(
+        mut,
+        seeds = [RoundLeaderboard::SEED, round.key().as_ref()],
+        bump = round_leaderboard.bump,
+    )]
+    pub round_leaderboard: Box<Account<'info, RoundLeaderboard>>,
+
+    #[account(
+        mut,
+        seeds = [Jackpot::SEED, game_config.key().as_ref()],
+        bump = jackpot.bump,
+    )]
+    pub jackpot: Box<Account<'info, Jackpot>>,
+
+    /// Required while the game has a win cooldown.
+    #[account(
+        seeds = [Leaderboard::SEED, game_config.key().as_ref()],
+        bump = leaderboard.bump,
+    )]
+    pub leaderboard: Option<Account<'info, Leaderboard>>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// Pays the `GuessRecord` rent on the player's first guess instead of
+    /// the player, as in `EnterRound`.
+    #[account(mut)]
+    pub rent_sponsor: Option<Signer<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CommitGuess<'info> {
+    #[account(
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            Round::SEED,
+            round.game_config.as_ref(),
+            &round.id.to_le_bytes(),
+        ],
+        bump = round.bump,
+        constraint = round.game_config == game_config.key(),
+    )]
+    pub round: Account<'info, Round>,
+
+    #[account(
+        seeds = [
+            PlayerEntry::SEED,
+            round.key().as_ref(),
+            &round.generation.to_le_bytes(),
+            player.key().as_ref(),
+        ],
+        bump,
+        has_one = player,
+        has_one = round,
+    )]
+    pub player_entry: Account<'info, PlayerEntry>,
+
+    /// CHECK: The game's `Blacklist` PDA, checked by
+    /// `require_not_blacklisted`; it may not exist yet
+    #[account(
+        seeds = [Blacklist::SEED, game_config.key().as_ref()],
+        bump,
+    )]
+    pub blacklist: UncheckedAccount<'info>,
+
+    /// CHECK: Loaded, or created on the player's first attempt, by
+    /// `load_guess_record`; paid for by `rent_sponsor` when one signs
+    #[account(
+        mut,
+        seeds = [
+            GuessRecord::SEED,
+            round.key().as_ref(),
+            &round.generation.to_le_bytes(),
+            player.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub guess_record: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [RoundLeaderboard::SEED, round.key().as_ref()],
+        bump = round_leaderboard.bump,
+    )]
+    pub round_leaderboard: Box<Account<'info, RoundLeaderboard>>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// Pays the `GuessRecord` rent on the player's first attempt instead of
+    /// the player, as in `EnterRound`.
+    #[account(mut)]
+    pub rent_sponsor: Option<Signer<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevealGuess<'info> {
+    #[account(
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            Round::SEED,
+            round.game_config.as_ref(),
+            &round.id.to_le_bytes(),
+        ],
+        bump = round.bump,
+        constraint = round.game_config == game_config.key(),
+    )]
+    pub round: Account<'info, Round>,
+
+    #[account(
+        mut,
+        seeds = [
+            GuessRecord::SEED,
+            round.key().as_ref(),
+            &round.generation.to_le_bytes(),
+            player.key().as_ref(),
+        ],
+        bump = guess_record.bump,
+    )]
+    pub guess_record: Account<'info, GuessRecord>,
+
+    /// CHECK: The game's `Blacklist` PDA, checked by
+    /// `require_not_blacklisted`; it may not exist yet
+    #[account(
+        seeds = [Blacklist::SEED, game_config.key().as_ref()],
+        bump,
+    )]
+    pub blacklist: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = PlayerProfile::SIZE,
+        seeds = [
+            PlayerProfile::SEED,
+            game_config.key().as_ref(),
+            player.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub player_profile: Box<Account<'info, PlayerProfile>>,
+
+    /// Required for rounds that allow multiple winners.
+    #[account(
+        mut,
+        seeds = [Winners::SEED, round.key().as_ref()],
+        bump = winners.bump,
+    )]
+    pub winners: Option<Account<'info, Winners>>,
+
+    #[account(
+        mut,
+        seeds = [GuessLog::SEED, round.key().as_ref()],
+        bump = guess_log.bump,
+    )]
+    pub guess_log: Box<Account<'info, GuessLog>>,
+
+    #[account(
+        mut,
+        seeds = [RoundLeaderboard::SEED, round.key().as_ref()],
+        bump = round_leaderboard.bump,
+    )]
+    pub round_leaderboard: Box<Account<'info, RoundLeaderboard>>,
+
+    #[account(
+        mut,
+        seeds = [Jackpot::SEED, round.game_config.as_ref()],
+        bump = jackpot.bump,
+    )]
+    pub jackpot: Box<Account<'info, Jackpot>>,
+
+    /// Required while the game has a win cooldown.
+    #[account(
+        seeds = [Leaderboard::SEED, game_config.key().as_ref()],
+        bump = leaderboard.bump,
+    )]
+    pub leaderboard: Option<Account<'info, Leaderboard>>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DistributePot<'info> {
+    #[account(
+        mut,
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            Round::SEED,
+            round.game_config.as_ref(),
+            &round.id.to_le_bytes(),
+        ],
+        bump = round.bump,
+        constraint = round.game_config == game_config.key(),
+        constraint = round.has_winner @ SolPotError::NoWinner,
+        constraint = !round.pot_distributed @ SolPotError::PotAlreadyDistributed,
+    )]
+    pub round: Box<Account<'info, Round>>,
+
+    /// CHECK: Winner account verified against round.winner
+    #[account(
+        mut,
+        constraint = winner.key() == round.winner @ SolPotError::Unauthorized,
+    )]
+    pub winner: AccountInfo<'info>,
+
+    /// Created here if the winner has none yet, e.g. when they entered
+    /// before profiles existed.
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = PlayerProfile::SIZE,
+        seeds = [
+            PlayerProfile::SEED,
+            game_config.key().as_ref(),
+            round.winner.as_ref(),
+        ],
+        bump,
+    )]
+    pub winner_profile: Box<Account<'info, PlayerProfile>>,
+
+    /// CHECK: The game's `Season` PDA, which may not exist yet; checked by
+    /// `record_season_wins`
+    #[account(
+        mut,
+        seeds = [Season::SEED, game_config.key().as_ref()],
+        bump,
+    )]
+    pub season: UncheckedAccount<'info>,
+
+    /// Collects the house cut of SOL rounds.
+    #[account(
+        mut,
+        seeds = [FeeVault::SEED, game_config.key().as_ref()],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+
+    /// CHECK: Runner-up verified against round.first_entrant
+    #[account(
+        mut,
+        constraint = runner_up.key() == round.first_entrant @ SolPotError::Unauthorized,
+    )]
+    pub runner_up: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Charity rounds only; verified against round.charity_wallet
+    #[account(
+        mut,
+        constraint = charity_wallet.key() == round.charity_wallet @ SolPotError::CharityWalletMismatch,
+    )]
+    pub charity_wallet: Option<UncheckedAccount<'info>>,
+
+    /// Required for rounds that allow multiple winners.
+    #[account(
+        seeds = [Winners::SEED, round.key().as_ref()],
+        bump = winners.bump,
+    )]
+    pub winners: Option<Account<'info, Winners>>,
+
+    #[account(
+        mut,
+        seeds = [Leaderboard::SEED, game_config.key().as_ref()],
+        bump = leaderboard.bump,
+    )]
+    pub leaderboard: Account<'info, Leaderboard>,
+
+    /// Token games only.
+    #[account(mut, address = round.vault)]
+    pub vault: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Token games only.
+    #[account(
+        mut,
+        constraint = winner_token_account.owner == round.winner @ SolPotError::Unauthorized,
+        constraint = winner_token_account.mint == round.mint @ SolPotError::TokenMintMismatch,
+    )]
+    pub winner_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Token games only.
+    #[account(
+        mut,
+        constraint = fee_receiver_token_account.owner == game_config.fee_destination @ SolPotError::Unauthorized,
+        constraint = fee_receiver_token_account.mint == round.mint @ SolPotError::TokenMintMismatch,
+    )]
+    pub fee_receiver_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Token games only.
+    #[account(
+        mut,
+        constraint = runner_up_token_account.owner == round.first_entrant @ SolPotError::Unauthorized,
+        constraint = runner_up_token_account.mint == round.mint @ SolPotError::TokenMintMismatch,
+    )]
+    pub runner_up_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Required for rounds with a vesting prize.
+    #[account(
+        mut,
+        seeds = [PrizeVault::SEED, round.key().as_ref()],
+        bump = prize_vault.bump,
+    )]
+    pub prize_vault: Option<Box<Account<'info, PrizeVault>>>,
+
+    /// Required with `auto_close`; receives the round's rent.
+    #[account(
+        mut,
+        constraint = authority.key() == game_config.authority @ SolPotError::Unauthorized,
+    )]
+    pub authority: Option<Signer<'info>>,
+
+    /// Whoever calls; pays for any winner profiles created on the way.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Option<Program<'info, Token>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DrawRandomWinner<'info> {
+    #[account(
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+        has_one = authority,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            Round::SEED,
+            round.game_config.as_ref(),
+            &round.id.to_le_bytes(),
+        ],
+        bump = round.bump,
+        constraint = round.game_config == game_config.key(),
+    )]
+    pub round: Box<Account<'info, Round>>,
+
+    #[account(
+        seeds = [Participants::SEED, round.key().as_ref()],
+        bump = participants.bump,
+    )]
+    pub participants: Box<Account<'info, Participants>>,
+
+    /// CHECK: SlotHashes sysvar, verified by address and read manually
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MintRewardNft<'info> {
+    #[account(
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            Round::SEED,
+            round.game_config.as_ref(),
+            &round.id.to_le_bytes(),
+        ],
+        bump = round.bump,
+        constraint = round.has_winner @ SolPotError::NoWinner,
+        constraint = !round.nft_minted @ SolPotError::NftAlreadyMinted,
+    )]
+    pub round: Box<Account<'info, Round>>,
+
+    /// CHECK: New Metaplex Core asset account (created by CPI)
+    #[account(mut, signer)]
+    pub asset: AccountInfo<'info>,
+
+    /// CHECK: Winner account verified against round.winner
+    #[account(
+        constraint = winner.key() == round.winner @ SolPotError::Unauthorized,
+    )]
+    pub winner: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Required when the game has a collection; verified against
+    /// game_config.collection
+    #[account(mut)]
+    pub collection: Option<UncheckedAccount<'info>>,
+
+    /// The collection's update authority. Required with `collection`.
+    pub collection_authority: Option<Signer<'info>>,
+
+    /// CHECK: Metaplex Core program verified by address constraint
+    #[account(address = MPL_CORE_PROGRAM_ID)]
+    pub mpl_core_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DistributeAndMint<'info> {
+    pub distribute: DistributePot<'info>,
+
+    /// CHECK: New Metaplex Core asset account (created by CPI)
+    #[account(mut, signer)]
+    pub asset: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Required when the game has a collection; verified against
+    /// game_config.collection
+    #[account(mut)]
+    pub collection: Option<UncheckedAccount<'info>>,
+
+    /// The collection's update authority. Required with `collection`.
+    pub collection_authority: Option<Signer<'info>>,
+
+    /// CHECK: Metaplex Core program verified by address constraint
+    #[account(address = MPL_CORE_PROGRAM_ID)]
+    pub mpl_core_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetRoundAuthority<'info> {
+    #[account(
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+        has_one = authority,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            Round::SEED,
+            round.game_config.as_ref(),
+            &round.id.to_le_bytes(),
+        ],
+        bump = round.bump,
+        constraint = round.game_config == game_config.key(),
+    )]
+    pub round: Account<'info, Round>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExtendRound<'info> {
+    #[account(
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            Round::SEED,
+            round.game_config.as_ref(),
+            &round.id.to_le_bytes(),
+        ],
+        bump = round.bump,
+        constraint = round.game_config == game_config.key(),
+    )]
+    pub round: Account<'info, Round>,
+
+    /// The game authority or the round's delegate.
+    #[account(
+        constraint = round.is_operator(&game_config, &authority.key()) @ SolPotError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CommitAnswer<'info> {
+    #[account(
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+        has_one = authority,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            Round::SEED,
+            round.game_config.as_ref(),
+            &round.id.to_le_bytes(),
+        ],
+        bump = round.bump,
+        constraint = round.game_config == game_config.key(),
+    )]
+    pub round: Box<Account<'info, Round>>,
+
+    /// CHECK: SlotHashes sysvar, verified by address and read manually
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RevealAnswer<'info> {
+    #[account(
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+        has_one = authority,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            Round::SEED,
+            round.game_config.as_ref(),
+            &round.id.to_le_bytes(),
+        ],
+        bump = round.bump,
+        constraint = round.game_config == game_config.key(),
+    )]
+    pub round: Box<Account<'info, Round>>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MarkDisputed<'info> {
+    #[account(
+        mut,
+        seeds = [
+            Round::SEED,
+            round.game_config.as_ref(),
+            &round.id.to_le_bytes(),
+        ],
+        bump = round.bump,
+    )]
+    pub round: Box<Account<'info, Round>>,
+}
+
+#[derive(Accounts)]
+#[instruction(hint_uri: Option<String>, title: Option<String>)]
+pub struct UpdateRoundMetadata<'info> {
+    #[account(
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            Round::SEED,
+            round.game_config.as_ref(),
+            &round.id.to_le_bytes(),
+        ],
+        bump = round.bump,
+        constraint = round.game_config == game_config.key(),
+        realloc = Round::space_with_title(title.as_ref().map_or(round.title.len(), String::len)),
+        realloc::payer = authority,
+        realloc::zero = false,
+    )]
+    pub round: Account<'info, Round>,
+
+    /// The game authority or the round's delegate; pays for a longer title.
+    #[account(
+        mut,
+        constraint = round.is_operator(&game_config, &authority.key()) @ SolPotError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SpawnBonusRound<'info> {
+    #[account(
+        mut,
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+    )]
+    pub game_config: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            Round::SEED,
+            round.game_config.as_ref(),
+            &round.id.to_le_bytes(),
+        ],
+        bump = round.bump,
+        constraint = round.game_config == game_config.key(),
+    )]
+    pub round: Box<Account<'info, Round>>,
+
+    #[account(
+        init,
+        payer = winner,
+        space = Round::SIZE,
+        seeds = [
+            Round::SEED,
+            game_config.key().as_ref(),
+            &game_config.round_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub bonus_round: Box<Account<'info, Round>>,
+
+    /// The winner's entry into the bonus round (generation 0).
+    #[account(
+        init,
+        payer = winner,
+        space = PlayerEntry::SIZE,
+        seeds = [
+            PlayerEntry::SEED,
+            bonus_round.key().as_ref(),
+            &0u32.to_le_bytes(),
+            winner.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub player_entry: Box<Account<'info, PlayerEntry>>,
+
+    #[account(
+        init,
+        payer = winner,
+        space = GuessLog::SIZE,
+        seeds = [GuessLog::SEED, bonus_round.key().as_ref()],
+        bump,
+    )]
+    pub guess_log: Box<Account<'info, GuessLog>>,
+
+    #[account(
+        init,
+        payer = winner,
+        space = RoundLeaderboard::space(1),
+        seeds = [RoundLeaderboard::SEED, bonus_round.key().as_ref()],
+        bump,
+    )]
+    pub round_leaderboard: Box<Account<'info, RoundLeaderboard>>,
+
+    /// Takes the fee on the stake and puts up the house's match.
+    #[account(
+        mut,
+        seeds = [FeeVault::SEED, game_config.key().as_ref()],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    #[account(
+        mut,
+        seeds = [Leaderboard::SEED, game_config.key().as_ref()],
+        bump = leaderboard.bump,
+    )]
+    pub leaderboard: Box<Account<'info, Leaderboard>>,
+
+    #[account(mut)]
+    pub winner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BuildAnswerSet<'info> {
+    #[account(
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+        has_one = authority,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            Round::SEED,
+            round.game_config.as_ref(),
+            &round.id.to_le_bytes(),
+        ],
+        bump = round.bump,
+        constraint = round.game_config == game_config.key(),
+    )]
+    pub round: Box<Account<'info, Round>>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = AnswerSetBuilder::SIZE,
+        seeds = [AnswerSetBuilder::SEED, round.key().as_ref()],
+        bump,
+    )]
+    pub builder: Box<Account<'info, AnswerSetBuilder>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetWordHash<'info> {
+    #[account(
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+        has_one = authority,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            Round::SEED,
+            round.game_config.as_ref(),
+            &round.id.to_le_bytes(),
+        ],
+        bump = round.bump,
+        constraint = round.game_config == game_config.key(),
+    )]
+    pub round: Account<'info, Round>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReconcilePot<'info> {
+    #[account(
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+        has_one = authority,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            Round::SEED,
+            round.game_config.as_ref(),
+            &round.id.to_le_bytes(),
+        ],
+        bump = round.bump,
+        constraint = round.game_config == game_config.key(),
+        constraint = !round.pot_distributed @ SolPotError::PotAlreadyDistributed,
+    )]
+    pub round: Box<Account<'info, Round>>,
+
+    #[account(
+        mut,
+        seeds = [FeeVault::SEED, game_config.key().as_ref()],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RevealHint<'info> {
+    #[account(
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+        has_one = authority,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(
+        seeds = [
+            Round::SEED,
+            round.game_config.as_ref(),
+            &round.id.to_le_bytes(),
+        ],
+        bump = round.bump,
+        constraint = round.game_config == game_config.key(),
+    )]
+    pub round: Box<Account<'info, Round>>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = Hint::SIZE,
+        seeds = [Hint::SEED, round.key().as_ref()],
+        bump,
+    )]
+    pub hint: Account<'info, Hint>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ResetStreak<'info> {
+    #[account(
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(
+        seeds = [
+            Round::SEED,
+            round.game_config.as_ref(),
+            &round.id.to_le_bytes(),
+        ],
+        bump = round.bump,
+        constraint = round.game_config == game_config.key(),
+    )]
+    pub round: Box<Account<'info, Round>>,
+
+    #[account(
+        seeds = [
+            PlayerEntry::SEED,
+            round.key().as_ref(),
+            &round.generation.to_le_bytes(),
+            player_entry.player.as_ref(),
+        ],
+        bump = player_entry.bump,
+        has_one = round,
+    )]
+    pub player_entry: Account<'info, PlayerEntry>,
+
+    /// Required for rounds that allow multiple winners.
+    #[account(
+        seeds = [Winners::SEED, round.key().as_ref()],
+        bump = winners.bump,
+    )]
+    pub winners: Option<Account<'info, Winners>>,
+
+    #[account(
+        mut,
+        seeds = [Leaderboard::SEED, game_config.key().as_ref()],
+        bump = leaderboard.bump,
+    )]
+    pub leaderboard: Account<'info, Leaderboard>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimEntry<'info> {
+    #[account(
+        mut,
+        seeds = [
+            Round::SEED,
+            round.game_config.as_ref(),
+            &round.id.to_le_bytes(),
+        ],
+        bump = round.bump,
+    )]
+    pub round: Box<Account<'info, Round>>,
+
+    #[account(
+        mut,
+        close = player,
+        seeds = [
+            PlayerEntry::SEED,
+            round.key().as_ref(),
+            &round.generation.to_le_bytes(),
+            player.key().as_ref(),
+        ],
+        bump = player_entry.bump,
+        has_one = player,
+        has_one = round,
+    )]
+    pub player_entry: Account<'info, PlayerEntry>,
+
+    /// Only present if the player guessed in this round.
+    #[account(
+        mut,
+        close = player,
+        seeds = [
+            GuessRecord::SEED,
+            round.key().as_ref(),
+            &round.generation.to_le_bytes(),
+            player.key().as_ref(),
+        ],
+        bump = guess_record.bump,
+    )]
+    pub guess_record: Option<Account<'info, GuessRecord>>,
+
+    /// CHECK: The entry's player, bound by `has_one`; receives the rent
+    #[account(mut)]
+    pub player: UncheckedAccount<'info>,
+
+    pub closer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RolloverPot<'info> {
+    #[account(
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+        has_one = authority,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            Round::SEED,
+            source_round.game_config.as_ref(),
+            &source_round.id.to_le_bytes(),
+        ],
+        bump = source_round.bump,
+        constraint = source_round.game_config == game_config.key(),
+    )]
+    pub source_round: Box<Account<'info, Round>>,
+
+    #[account(
+        mut,
+        seeds = [
+            Round::SEED,
+            target_round.game_config.as_ref(),
+            &target_round.id.to_le_bytes(),
+        ],
+        bump = target_round.bump,
+        constraint = target_round.game_config == game_config.key(),
+    )]
+    pub target_round: Box<Account<'info, Round>>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimUnclaimed<'info> {
+    #[account(
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+        has_one = authority,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            Round::SEED,
+            round.game_config.as_ref(),
+            &round.id.to_le_bytes(),
+        ],
+        bump = round.bump,
+        constraint = round.game_config == game_config.key(),
+    )]
+    pub round: Box<Account<'info, Round>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct EmergencyWithdrawRound<'info> {
+    #[account(
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+        has_one = authority,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            Round::SEED,
+            round.game_config.as_ref(),
+            &round.id.to_le_bytes(),
+        ],
+        bump = round.bump,
+        constraint = round.game_config == game_config.key(),
+    )]
+    pub round: Box<Account<'info, Round>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Token games only.
+    #[account(mut, address = round.vault)]
+    pub vault: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Token games only.
+    #[account(
+        mut,
+        constraint = authority_token_account.owner == authority.key() @ SolPotError::Unauthorized,
+        constraint = authority_token_account.mint == round.mint @ SolPotError::TokenMintMismatch,
+    )]
+    pub authority_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+#[derive(Accounts)]
+pub struct CancelRound<'info> {
+    #[account(
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            Round::SEED,
+            round.game_config.as_ref(),
+            &round.id.to_le_bytes(),
+        ],
+        bump = round.bump,
+        constraint = round.game_config == game_config.key(),
+    )]
+    pub round: Box<Account<'info, Round>>,
+
+    /// The game authority or the round's delegate.
+    #[account(
+        constraint = round.is_operator(&game_config, &authority.key()) @ SolPotError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+}
 
-        require!(
-            expired_no_winner || won_and_distributed,
-            SolPotError::RoundStillActive
-        );
+#[derive(Accounts)]
+pub struct UpdateJackpot<'info> {
+    #[account(
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+        has_one = authority @ SolPotError::Unauthorized,
+    )]
+    pub game_config: Account<'info, GameConfig>,
 
-        if !ctx.accounts.round.has_winner && ctx.accounts.round.pot_lamports > 0 {
-            let round_info = ctx.accounts.round.to_account_info();
-            let rent = Rent::get()?;
-            let min_balance = rent.minimum_balance(round_info.data_len());
-            let available = round_info
-                .lamports()
-                .checked_sub(min_balance)
-                .ok_or(SolPotError::ArithmeticOverflow)?;
-            let refund = std::cmp::min(ctx.accounts.round.pot_lamports, available);
+    #[account(
+        mut,
+        seeds = [Jackpot::SEED, game_config.key().as_ref()],
+        bump = jackpot.bump,
+    )]
+    pub jackpot: Account<'info, Jackpot>,
 
-            **round_info.try_borrow_mut_lamports()? = round_info
-                .lamports()
-                .checked_sub(refund)
-                .ok_or(SolPotError::ArithmeticOverflow)?;
+    pub authority: Signer<'info>,
+}
 
-            let authority_info = ctx.accounts.authority.to_account_info();
-            **authority_info.try_borrow_mut_lamports()? = authority_info
-                .lamports()
-                .checked_add(refund)
-                .ok_or(SolPotError::ArithmeticOverflow)?;
-        }
+#[derive(Accounts)]
+pub struct ClaimJackpot<'info> {
+    #[account(
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+        has_one = authority @ SolPotError::Unauthorized,
+    )]
+    pub game_config: Account<'info, GameConfig>,
 
-        let round_id = ctx.accounts.round.id;
-        let round = &mut ctx.accounts.round;
-        round.pot_lamports = 0;
-        round.is_active = false;
+    #[account(
+        mut,
+        seeds = [
+            Round::SEED,
+            round.game_config.as_ref(),
+            &round.id.to_le_bytes(),
+        ],
+        bump = round.bump,
+        constraint = round.game_config == game_config.key(),
+    )]
+    pub round: Box<Account<'info, Round>>,
 
-        emit!(RoundClosed { round_id });
+    #[account(
+        mut,
+        seeds = [Jackpot::SEED, game_config.key().as_ref()],
+        bump = jackpot.bump,
+    )]
+    pub jackpot: Account<'info, Jackpot>,
 
-        Ok(())
-    }
+    pub authority: Signer<'info>,
 }
 
-// ── Account Contexts ────────────────────────────────────────────────────────
-
 #[derive(Accounts)]
-pub struct InitializeGame<'info> {
+pub struct SweepFees<'info> {
     #[account(
-        init,
-        payer = authority,
-        space = GameConfig::SIZE,
         seeds = [GameConfig::SEED],
-        bump,
+        bump = game_config.bump,
+        has_one = authority @ SolPotError::Unauthorized,
     )]
     pub game_config: Account<'info, GameConfig>,
 
     #[account(
-        init,
-        payer = authority,
-        space = Leaderboard::SIZE,
-        seeds = [Leaderboard::SEED, game_config.key().as_ref()],
-        bump,
+        mut,
+        seeds = [FeeVault::SEED, game_config.key().as_ref()],
+        bump = fee_vault.bump,
     )]
-    pub leaderboard: Account<'info, Leaderboard>,
+    pub fee_vault: Account<'info, FeeVault>,
 
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    /// CHECK: Verified against game_config.fee_destination
+    #[account(
+        mut,
+        address = game_config.fee_destination @ SolPotError::Unauthorized,
+    )]
+    pub destination: UncheckedAccount<'info>,
 
-    pub system_program: Program<'info, System>,
+    pub authority: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct CreateRound<'info> {
+pub struct ClaimVested<'info> {
+    /// Keyed by the round, which `distribute_pot` may already have closed.
     #[account(
         mut,
+        seeds = [PrizeVault::SEED, prize_vault.round.as_ref()],
+        bump = prize_vault.bump,
+        has_one = winner @ SolPotError::Unauthorized,
+    )]
+    pub prize_vault: Account<'info, PrizeVault>,
+
+    #[account(mut)]
+    pub winner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RoundPhaseView<'info> {
+    #[account(
+        seeds = [
+            Round::SEED,
+            round.game_config.as_ref(),
+            &round.id.to_le_bytes(),
+        ],
+        bump = round.bump,
+    )]
+    pub round: Box<Account<'info, Round>>,
+}
+
+#[derive(Accounts)]
+pub struct PreviewDistribution<'info> {
+    #[account(
         seeds = [GameConfig::SEED],
         bump = game_config.bump,
-        has_one = authority,
     )]
     pub game_config: Account<'info, GameConfig>,
 
     #[account(
-        init,
-        payer = authority,
-        space = Round::SIZE,
         seeds = [
             Round::SEED,
-            game_config.key().as_ref(),
-            &game_config.round_count.to_le_bytes(),
+            round.game_config.as_ref(),
+            &round.id.to_le_bytes(),
         ],
-        bump,
+        bump = round.bump,
+        constraint = round.game_config == game_config.key(),
+        constraint = round.has_winner @ SolPotError::NoWinner,
+        constraint = !round.pot_distributed @ SolPotError::PotAlreadyDistributed,
     )]
-    pub round: Account<'info, Round>,
+    pub round: Box<Account<'info, Round>>,
 
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    /// Required for rounds that allow multiple winners.
+    #[account(
+        seeds = [Winners::SEED, round.key().as_ref()],
+        bump = winners.bump,
+    )]
+    pub winners: Option<Account<'info, Winners>>,
 
-    pub system_program: Program<'info, System>,
+    /// Token games only.
+    #[account(address = round.vault)]
+    pub vault: Option<Box<Account<'info, TokenAccount>>>,
 }
 
 #[derive(Accounts)]
-pub struct EnterRound<'info> {
+pub struct TopPlayersView<'info> {
+    #[account(
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    #[account(
+        seeds = [Leaderboard::SEED, game_config.key().as_ref()],
+        bump = leaderboard.bump,
+    )]
+    pub leaderboard: Account<'info, Leaderboard>,
+}
+
+#[derive(Accounts)]
+pub struct CloseRound<'info> {
     #[account(
+        mut,
         seeds = [GameConfig::SEED],
         bump = game_config.bump,
     )]
@@ -612,6 +9169,7 @@ pub struct EnterRound<'info> {
 
     #[account(
         mut,
+        close = game_authority,
         seeds = [
             Round::SEED,
             round.game_config.as_ref(),
@@ -622,27 +9180,58 @@ pub struct EnterRound<'info> {
     )]
     pub round: Account<'info, Round>,
 
+    /// Receives whatever is left of an unsolved round's pot after refunds.
     #[account(
-        init,
-        payer = player,
-        space = PlayerEntry::SIZE,
-        seeds = [
-            PlayerEntry::SEED,
-            round.key().as_ref(),
-            player.key().as_ref(),
-        ],
-        bump,
+        mut,
+        seeds = [FeeVault::SEED, game_config.key().as_ref()],
+        bump = fee_vault.bump,
     )]
-    pub player_entry: Account<'info, PlayerEntry>,
+    pub fee_vault: Account<'info, FeeVault>,
 
-    #[account(mut)]
-    pub player: Signer<'info>,
+    /// CHECK: Game authority verified by address; receives the seed back
+    /// and the round's rent
+    #[account(mut, address = game_config.authority @ SolPotError::Unauthorized)]
+    pub game_authority: UncheckedAccount<'info>,
 
-    pub system_program: Program<'info, System>,
+    /// The game authority or the round's delegate.
+    #[account(
+        constraint = round.is_operator(&game_config, &authority.key()) @ SolPotError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct SubmitGuess<'info> {
+pub struct CloseExpiredRounds<'info> {
+    #[account(
+        mut,
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+        has_one = authority,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
+    /// Receives whatever is left of unsolved rounds' pots after refunds.
+    #[account(
+        mut,
+        seeds = [FeeVault::SEED, game_config.key().as_ref()],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+
+    /// Receives the seeds back and the rounds' rent.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RestartRound<'info> {
+    #[account(
+        seeds = [GameConfig::SEED],
+        bump = game_config.bump,
+        has_one = authority,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+
     #[account(
         mut,
         seeds = [
@@ -651,48 +9240,109 @@ pub struct SubmitGuess<'info> {
             &round.id.to_le_bytes(),
         ],
         bump = round.bump,
+        constraint = round.game_config == game_config.key(),
     )]
-    pub round: Account<'info, Round>,
+    pub round: Box<Account<'info, Round>>,
 
+    /// Required for rounds that allow multiple winners.
     #[account(
-        seeds = [
-            PlayerEntry::SEED,
-            round.key().as_ref(),
-            player.key().as_ref(),
-        ],
-        bump,
-        has_one = player,
-        has_one = round,
+        mut,
+        seeds = [Winners::SEED, round.key().as_ref()],
+        bump = winners.bump,
     )]
-    pub player_entry: Account<'info, PlayerEntry>,
+    pub winners: Option<Account<'info, Winners>>,
 
+    /// Required for rounds with a random fallback.
     #[account(
-        init,
-        payer = player,
-        space = GuessRecord::SIZE,
-        seeds = [
-            GuessRecord::SEED,
-            round.key().as_ref(),
-            player.key().as_ref(),
-        ],
-        bump,
+        mut,
+        seeds = [Participants::SEED, round.key().as_ref()],
+        bump = participants.bump,
     )]
-    pub guess_record: Account<'info, GuessRecord>,
+    pub participants: Option<Box<Account<'info, Participants>>>,
 
-    #[account(mut)]
-    pub player: Signer<'info>,
+    /// Required for rounds with a vesting prize.
+    #[account(
+        mut,
+        seeds = [PrizeVault::SEED, round.key().as_ref()],
+        bump = prize_vault.bump,
+    )]
+    pub prize_vault: Option<Box<Account<'info, PrizeVault>>>,
 
-    pub system_program: Program<'info, System>,
+    /// Only present if hints were revealed in an earlier run.
+    #[account(
+        mut,
+        seeds = [Hint::SEED, round.key().as_ref()],
+        bump = hint.bump,
+    )]
+    pub hint: Option<Account<'info, Hint>>,
+
+    #[account(
+        mut,
+        seeds = [GuessLog::SEED, round.key().as_ref()],
+        bump = guess_log.bump,
+    )]
+    pub guess_log: Box<Account<'info, GuessLog>>,
+
+    #[account(
+        mut,
+        seeds = [RoundLeaderboard::SEED, round.key().as_ref()],
+        bump = round_leaderboard.bump,
+    )]
+    pub round_leaderboard: Box<Account<'info, RoundLeaderboard>>,
+
+    /// Receives whatever is left of an unsolved round's pot after refunds.
+    #[account(
+        mut,
+        seeds = [FeeVault::SEED, game_config.key().as_ref()],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct DistributePot<'info> {
+pub struct CloseGame<'info> {
     #[account(
+        mut,
+        close = authority,
         seeds = [GameConfig::SEED],
         bump = game_config.bump,
+        has_one = authority,
     )]
     pub game_config: Account<'info, GameConfig>,
 
+    #[account(
+        mut,
+        close = authority,
+        seeds = [Leaderboard::SEED, game_config.key().as_ref()],
+        bump = leaderboard.bump,
+    )]
+    pub leaderboard: Account<'info, Leaderboard>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [FeeVault::SEED, game_config.key().as_ref()],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [Jackpot::SEED, game_config.key().as_ref()],
+        bump = jackpot.bump,
+    )]
+    pub jackpot: Account<'info, Jackpot>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RefundLosers<'info> {
     #[account(
         mut,
         seeds = [
@@ -701,36 +9351,44 @@ pub struct DistributePot<'info> {
             &round.id.to_le_bytes(),
         ],
         bump = round.bump,
-        constraint = round.game_config == game_config.key(),
-        constraint = round.has_winner @ SolPotError::NoWinner,
-        constraint = !round.pot_distributed @ SolPotError::PotAlreadyDistributed,
     )]
     pub round: Account<'info, Round>,
 
-    /// CHECK: Winner account verified against round.winner
     #[account(
         mut,
-        constraint = winner.key() == round.winner @ SolPotError::Unauthorized,
+        seeds = [
+            PlayerEntry::SEED,
+            round.key().as_ref(),
+            &round.generation.to_le_bytes(),
+            player.key().as_ref(),
+        ],
+        bump = player_entry.bump,
+        has_one = player,
+        has_one = round,
     )]
-    pub winner: AccountInfo<'info>,
+    pub player_entry: Account<'info, PlayerEntry>,
 
-    /// CHECK: Fee receiver verified against game_config.authority
-    #[account(
-        mut,
-        constraint = fee_receiver.key() == game_config.authority @ SolPotError::Unauthorized,
-    )]
-    pub fee_receiver: AccountInfo<'info>,
+    /// CHECK: Refund recipient verified against player_entry.player
+    #[account(mut)]
+    pub player: AccountInfo<'info>,
 
+    /// Token games only: receives the refund.
     #[account(
         mut,
-        seeds = [Leaderboard::SEED, game_config.key().as_ref()],
-        bump = leaderboard.bump,
+        constraint = player_token_account.owner == player.key() @ SolPotError::Unauthorized,
+        constraint = player_token_account.mint == round.mint @ SolPotError::TokenMintMismatch,
     )]
-    pub leaderboard: Account<'info, Leaderboard>,
+    pub player_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Token games only.
+    #[account(mut, address = round.vault)]
+    pub vault: Option<Box<Account<'info, TokenAccount>>>,
+
+    pub token_program: Option<Program<'info, Token>>,
 }
 
 #[derive(Accounts)]
-pub struct MintRewardNft<'info> {
+pub struct TipRound<'info> {
     #[account(
         seeds = [GameConfig::SEED],
         bump = game_config.bump,
@@ -745,37 +9403,36 @@ pub struct MintRewardNft<'info> {
             &round.id.to_le_bytes(),
         ],
         bump = round.bump,
-        constraint = round.has_winner @ SolPotError::NoWinner,
-        constraint = !round.nft_minted @ SolPotError::NftAlreadyMinted,
+        constraint = round.game_config == game_config.key(),
     )]
     pub round: Box<Account<'info, Round>>,
 
-    /// CHECK: New Metaplex Core asset account (created by CPI)
-    #[account(mut, signer)]
-    pub asset: AccountInfo<'info>,
-
-    /// CHECK: Winner account verified against round.winner
     #[account(
-        constraint = winner.key() == round.winner @ SolPotError::Unauthorized,
+        init_if_needed,
+        payer = tipper,
+        space = TipRecord::SIZE,
+        seeds = [
+            TipRecord::SEED,
+            round.key().as_ref(),
+            &round.generation.to_le_bytes(),
+            tipper.key().as_ref(),
+        ],
+        bump,
     )]
-    pub winner: AccountInfo<'info>,
+    pub tip_record: Account<'info, TipRecord>,
 
     #[account(mut)]
-    pub payer: Signer<'info>,
-
-    /// CHECK: Metaplex Core program verified by address constraint
-    #[account(address = MPL_CORE_PROGRAM_ID)]
-    pub mpl_core_program: AccountInfo<'info>,
+    pub tipper: Signer<'info>,
 
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct CloseRound<'info> {
+pub struct AddBounty<'info> {
     #[account(
         seeds = [GameConfig::SEED],
         bump = game_config.bump,
-        has_one = authority,
+        has_one = authority @ SolPotError::Unauthorized,
     )]
     pub game_config: Account<'info, GameConfig>,
 
@@ -789,9 +9446,1033 @@ pub struct CloseRound<'info> {
         bump = round.bump,
         constraint = round.game_config == game_config.key(),
     )]
-    pub round: Account<'info, Round>,
+    pub round: Box<Account<'info, Round>>,
 
-    /// CHECK: Authority receives refunded SOL if round expired without winner
     #[account(mut)]
     pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimTip<'info> {
+    #[account(
+        mut,
+        seeds = [
+            Round::SEED,
+            round.game_config.as_ref(),
+            &round.id.to_le_bytes(),
+        ],
+        bump = round.bump,
+    )]
+    pub round: Box<Account<'info, Round>>,
+
+    #[account(
+        mut,
+        close = tipper,
+        seeds = [
+            TipRecord::SEED,
+            round.key().as_ref(),
+            &round.generation.to_le_bytes(),
+            tipper.key().as_ref(),
+        ],
+        bump = tip_record.bump,
+        has_one = tipper,
+        has_one = round,
+    )]
+    pub tip_record: Account<'info, TipRecord>,
+
+    /// CHECK: Refund and rent recipient verified against tip_record.tipper
+    #[account(mut)]
+    pub tipper: AccountInfo<'info>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf(word: &str) -> [u8; 32] {
+        hash(word.as_bytes()).to_bytes()
+    }
+
+    #[test]
+    fn normalize_guess_without_flags_only_lowercases() {
+        assert_eq!(normalize_guess("  SoLana ", 0), "  solana ");
+    }
+
+    #[test]
+    fn normalize_guess_trims_and_collapses_whitespace() {
+        let flags = Round::NORMALIZE_TRIM | Round::NORMALIZE_COLLAPSE_WHITESPACE;
+        assert_eq!(normalize_guess("\t New \n  York  ", flags), "new york");
+        assert_eq!(
+            normalize_guess(" New   York ", Round::NORMALIZE_COLLAPSE_WHITESPACE),
+            " new york "
+        );
+    }
+
+    #[test]
+    fn normalize_guess_applies_nfkc() {
+        // Decomposed "e" + combining acute accent composes to "é".
+        assert_eq!(
+            normalize_guess("Cafe\u{301}", Round::NORMALIZE_NFKC),
+            "caf\u{e9}"
+        );
+        // Full-width letters and the ideographic space fold to ASCII.
+        assert_eq!(
+            normalize_guess("\u{ff33}\u{ff2f}\u{ff2c}\u{3000}", Round::NORMALIZE_ALL),
+            "sol"
+        );
+        assert_eq!(
+            guess_hash("\u{ff33}\u{ff2f}\u{ff2c}", Round::NORMALIZE_NFKC),
+            hash(b"sol").to_bytes()
+        );
+    }
+
+    #[test]
+    fn guesses_hash_by_round_kind() {
+        let mut round = Round {
+            normalization_flags: Round::NORMALIZE_TRIM,
+            ..Default::default()
+        };
+        assert_eq!(
+            answer_hash(&round, b" Sol ").unwrap(),
+            guess_hash("sol", 0)
+        );
+        assert!(answer_hash(&round, &[0xff, 0xfe]).is_err());
+
+        round.guess_kind = Round::GUESS_KIND_BYTES;
+        assert_eq!(answer_hash(&round, b" Sol ").unwrap(), hash(b" Sol ").to_bytes());
+
+        round.guess_kind = Round::GUESS_KIND_NUMBER;
+        let forty_two = hash(&42u64.to_le_bytes()).to_bytes();
+        assert_eq!(answer_hash(&round, &[42]).unwrap(), forty_two);
+        assert_eq!(answer_hash(&round, &[42, 0, 0, 0, 0, 0, 0, 0, 0, 0]).unwrap(), forty_two);
+        assert_eq!(canonical_number(&[]).unwrap(), 0);
+        assert!(canonical_number(&[0, 0, 0, 0, 0, 0, 0, 0, 1]).is_err());
+
+        round.guess_kind = Round::GUESS_KIND_HASHED;
+        let inner = guess_hash("a long secret phrase", 0);
+        round.word_hash = hash(&inner).to_bytes();
+        assert!(is_answer(&round, &inner, &[]).unwrap());
+        // The public `word_hash` itself is not a winning guess.
+        assert!(!is_answer(&round, &round.word_hash, &[]).unwrap());
+        assert!(answer_hash(&round, b"a long secret phrase").is_err());
+    }
+
+    #[test]
+    fn leaderboard_insertion_matches_full_sort() {
+        let mut leaderboard = Leaderboard {
+            capacity: 12,
+            ..Default::default()
+        };
+        let mut sorted = leaderboard.entries.clone();
+        let game_config = GameConfig {
+            leaderboard_win_weight: 1,
+            ..Default::default()
+        };
+
+        let players: Vec<Pubkey> = (0..16u8).map(|i| Pubkey::new_from_array([i; 32])).collect();
+        let mut seed = 7u64;
+        for round_id in 0..500 {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let player = players[(seed >> 33) as usize % players.len()];
+            leaderboard
+                .record_win(player, round_id, round_id, round_id as i64, &game_config)
+                .unwrap();
+
+            // The previous implementation: update in place, then stable-sort.
+            if let Some(entry) = sorted.iter_mut().find(|e| e.player == player) {
+                entry.wins += 1;
+                entry.total_winnings += round_id;
+                entry.current_streak += 1;
+                entry.best_streak = entry.best_streak.max(entry.current_streak);
+                entry.last_win_round_id = round_id;
+                entry.last_win_at = round_id as i64;
+                entry.score += 1;
+            } else if sorted.len() < 12 {
+                sorted.push(LeaderboardEntry {
+                    player,
+                    wins: 1,
+                    total_winnings: round_id,
+                    current_streak: 1,
+                    best_streak: 1,
+                    last_win_round_id: round_id,
+                    last_win_at: round_id as i64,
+                    score: 1,
+                });
+            }
+            sorted.sort_by(|a, b| b.score.cmp(&a.score));
+
+            assert_eq!(leaderboard.entries, sorted);
+        }
+    }
+
+    #[test]
+    fn leaderboard_resorts_after_weight_change() {
+        let mut leaderboard = Leaderboard {
+            capacity: 4,
+            ..Default::default()
+        };
+        let mut game_config = GameConfig {
+            leaderboard_win_weight: 1,
+            ..Default::default()
+        };
+        let grinder = Pubkey::new_from_array([1; 32]);
+        let whale = Pubkey::new_from_array([2; 32]);
+        let newcomer = Pubkey::new_from_array([3; 32]);
+
+        for round_id in 0..3 {
+            leaderboard
+                .record_win(grinder, LAMPORTS_PER_SOL / 10, round_id, 0, &game_config)
+                .unwrap();
+        }
+        leaderboard
+            .record_win(whale, 10 * LAMPORTS_PER_SOL, 3, 0, &game_config)
+            .unwrap();
+        let order = |board: &Leaderboard| {
+            board.entries.iter().map(|e| e.player).collect::<Vec<_>>()
+        };
+        assert_eq!(order(&leaderboard), vec![grinder, whale]);
+
+        // Weighting winnings puts the whale ahead at the next payout.
+        game_config.leaderboard_winnings_weight = 1;
+        leaderboard
+            .record_win(newcomer, LAMPORTS_PER_SOL, 4, 0, &game_config)
+            .unwrap();
+        assert_eq!(order(&leaderboard), vec![whale, grinder, newcomer]);
+        assert_eq!(leaderboard.entries[0].score, 11);
+        assert_eq!(leaderboard.entries[1].score, 3);
+        assert_eq!(leaderboard.entries[2].score, 2);
+
+        let rank = |player: &Pubkey| leaderboard.rank_of(player).map(|(rank, _)| rank);
+        assert_eq!(rank(&whale), Some(1));
+        assert_eq!(rank(&newcomer), Some(3));
+        assert_eq!(rank(&Pubkey::new_from_array([4; 32])), None);
+    }
+
+    #[test]
+    fn legacy_leaderboard_upgrades_to_the_current_layout() {
+        let game_config = Pubkey::new_unique();
+        let bump = 254;
+        // A board as the original program wrote it: `game_config`, 44-byte
+        // `{player, wins, total_winnings}` entries, `bump`, then the unused
+        // room for 50 entries.
+        let mut old = Leaderboard::DISCRIMINATOR.to_vec();
+        old.extend_from_slice(game_config.as_ref());
+        old.extend_from_slice(&2u32.to_le_bytes());
+        let entries = [(7u8, 3u32, 3 * LAMPORTS_PER_SOL), (8, 1, LAMPORTS_PER_SOL)];
+        for (byte, wins, winnings) in entries {
+            old.extend_from_slice(&[byte; 32]);
+            old.extend_from_slice(&wins.to_le_bytes());
+            old.extend_from_slice(&winnings.to_le_bytes());
+        }
+        old.push(bump);
+        old.resize(8 + 32 + 4 + 50 * 44 + 1, 0);
+
+        // Old bytes only read in the old layout.
+        assert!(decode_leaderboard(&old, bump).is_none());
+        let upgraded = decode_legacy_leaderboard(&old, bump).unwrap();
+        assert_eq!(upgraded.game_config, game_config);
+        assert_eq!(upgraded.capacity, Leaderboard::INITIAL_CAPACITY);
+        assert_eq!(upgraded.bump, bump);
+        let expected = vec![
+            LeaderboardEntry {
+                player: Pubkey::new_from_array([7; 32]),
+                wins: 3,
+                total_winnings: 3 * LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+            LeaderboardEntry {
+                player: Pubkey::new_from_array([8; 32]),
+                wins: 1,
+                total_winnings: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        ];
+        assert_eq!(upgraded.entries, expected);
+        assert!(decode_legacy_leaderboard(&old, bump - 1).is_none());
+
+        // Written back out, the board reads in the new layout only, and as
+        // the account itself.
+        let mut new = vec![0; Leaderboard::space(upgraded.capacity)];
+        upgraded.try_serialize(&mut &mut new[..]).unwrap();
+        assert!(decode_legacy_leaderboard(&new, bump).is_none());
+        let reread = decode_leaderboard(&new, bump).unwrap();
+        assert_eq!(reread.entries, expected);
+        let account = Leaderboard::try_deserialize(&mut &new[..]).unwrap();
+        assert_eq!(account.entries, expected);
+    }
+
+    #[test]
+    fn baseline_game_and_round_play_after_migration() {
+        // `GameConfig` and `Round` bytes as the original program wrote them,
+        // zero-extended the way `grow_account` does.
+        let authority = Pubkey::new_unique();
+        let entry_fee = LAMPORTS_PER_SOL / 20;
+        let mut config_bytes = GameConfig::DISCRIMINATOR.to_vec();
+        (authority, 1u64, entry_fee, 250u16, 255u8)
+            .serialize(&mut config_bytes)
+            .unwrap();
+        assert_eq!(config_bytes.len(), 8 + 32 + 8 + 8 + 2 + 1);
+        config_bytes.resize(GameConfig::SIZE, 0);
+
+        let game_config_key = Pubkey::new_unique();
+        let word_hash = hash(b"solana").to_bytes();
+        let mut round_bytes = Round::DISCRIMINATOR.to_vec();
+        (
+            (0u64, game_config_key, word_hash, true),
+            (Pubkey::default(), false, 2 * entry_fee, false, false),
+            (2u32, 10u32, 1_000i64, 4_600i64, entry_fee, 254u8),
+        )
+            .serialize(&mut round_bytes)
+            .unwrap();
+        assert_eq!(round_bytes.len(), 157);
+        round_bytes.resize(Round::SIZE, 0);
+
+        let mut game_config = GameConfig::try_deserialize(&mut &config_bytes[..]).unwrap();
+        game_config.apply_migration_defaults(game_config.schema_version);
+        let mut round = Round::try_deserialize(&mut &round_bytes[..]).unwrap();
+        round.apply_migration_defaults(round.schema_version);
+        assert_eq!(game_config.schema_version, GameConfig::SCHEMA_VERSION);
+        assert_eq!(round.schema_version, Round::SCHEMA_VERSION);
+        assert_eq!(game_config.fee_destination, authority);
+        assert!(game_config.validate_round_bounds(10, 3_600).is_ok());
+
+        // Entering: the snapshotted fee still applies and the pot is uncapped.
+        let now = 2_000;
+        assert_eq!(round.current_entry_fee(), entry_fee);
+        assert!(round.fits_pot_cap(entry_fee));
+        round.add_to_pot(entry_fee).unwrap();
+
+        // Guessing: a first attempt is within the guess limit, and the
+        // original lowercase hash still matches.
+        assert!(require_guessing_open(&round, now).is_ok());
+        assert!(0 < round.max_guesses);
+        let player = Pubkey::new_unique();
+        assert!(settle_guess(&mut round, None, None, player, b"Solana", &[], now, false).unwrap());
+        assert_eq!(round.winner, player);
+
+        // Distributing.
+        assert!(round.won_before_expiry() && round.win_is_final(now));
+        assert!(!round.is_underfilled());
+        let split = split_pot(
+            round.pot_lamports,
+            round.pot_lamports,
+            game_config.fee_basis_points,
+            game_config.runner_up_basis_points,
+            1,
+        )
+        .unwrap();
+        assert_eq!(split.winner_amount + split.fee, 3 * entry_fee);
+    }
+
+    #[test]
+    fn season_ranks_only_the_rounds_it_covers() {
+        let game_config = GameConfig {
+            leaderboard_win_weight: 1,
+            ..Default::default()
+        };
+        let mut season = Season {
+            is_active: true,
+            start_round: 5,
+            end_round: 8,
+            ..Default::default()
+        };
+        assert!(!season.covers(4));
+        assert!(season.covers(5));
+        assert!(season.covers(7));
+        assert!(!season.covers(8));
+
+        let players: Vec<Pubkey> = (0..=Season::MAX_ENTRIES)
+            .map(|_| Pubkey::new_unique())
+            .collect();
+        let mut win = |player: Pubkey, round_id: u64| {
+            record_ranked_win(
+                &mut season.entries,
+                Season::MAX_ENTRIES,
+                player,
+                1,
+                round_id,
+                0,
+                &game_config,
+            )
+            .unwrap()
+        };
+        for player in &players {
+            win(*player, 5);
+        }
+        // The board is full, so the last player is left off; a second win
+        // moves a player to the top.
+        win(players[3], 6);
+        assert_eq!(season.entries.len(), Season::MAX_ENTRIES);
+        assert!(!season
+            .entries
+            .iter()
+            .any(|e| e.player == players[Season::MAX_ENTRIES]));
+        assert_eq!(season.entries[0].player, players[3]);
+        assert_eq!(season.entries[0].wins, 2);
+
+        // A newcomer who outscores the last entry takes its place.
+        let last = season.entries[Season::MAX_ENTRIES - 1].player;
+        record_ranked_win(
+            &mut season.entries,
+            Season::MAX_ENTRIES,
+            players[Season::MAX_ENTRIES],
+            2 * LAMPORTS_PER_SOL,
+            6,
+            0,
+            &GameConfig {
+                leaderboard_win_weight: 1,
+                leaderboard_winnings_weight: 1,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(season.entries.len(), Season::MAX_ENTRIES);
+        assert_eq!(season.entries[0].player, players[Season::MAX_ENTRIES]);
+        assert!(!season.entries.iter().any(|e| e.player == last));
+
+        season.is_active = false;
+        assert!(!season.covers(6));
+    }
+
+    #[test]
+    fn fairness_salt_must_come_from_the_commit_slot_hash() {
+        let mut data = 2u64.to_le_bytes().to_vec();
+        for (slot, byte) in [(101u64, 0xbb), (100u64, 0xaa)] {
+            data.extend_from_slice(&slot.to_le_bytes());
+            data.extend_from_slice(&[byte; 32]);
+        }
+        assert_eq!(find_slot_hash(&data, 100), Some([0xaa; 32]));
+        assert_eq!(find_slot_hash(&data, 102), None);
+
+        let nonce = [7u8; 16];
+        let mut salt = [0u8; 16];
+        salt.copy_from_slice(&hashv(&[[0xaa; 32].as_ref(), nonce.as_ref()]).to_bytes()[..16]);
+        let mut round = Round {
+            commit_slot: 100,
+            commit_slot_hash: [0xaa; 32],
+            ..Default::default()
+        };
+        assert!(round.salt_binds_commit_slot(&salt, &nonce));
+        assert!(!round.salt_binds_commit_slot(&salt, &[8u8; 16]));
+
+        round.commit_slot_hash = [0xbb; 32];
+        assert!(!round.salt_binds_commit_slot(&salt, &nonce));
+        round.commit_slot = 0;
+        assert!(round.salt_binds_commit_slot(&[0u8; 16], &nonce));
+    }
+
+    #[test]
+    fn rounds_close_once_settled_and_reclaimed() {
+        let mut round = Round {
+            is_active: true,
+            expires_at: 100,
+            player_count: 2,
+            live_entries: 2,
+            ..Default::default()
+        };
+        assert!(!round.is_closable(50));
+        // Expired without a winner, but entries still to refund and reclaim.
+        assert!(!round.is_closable(100));
+        round.refunded_count = 2;
+        assert!(!round.is_closable(100));
+        round.live_entries = 0;
+        assert!(round.is_closable(100));
+
+        round.refund_tips = true;
+        round.total_tips = 10;
+        assert!(!round.is_closable(100));
+
+        let mut won = Round {
+            has_winner: true,
+            expires_at: 100,
+            ..Default::default()
+        };
+        assert!(!won.is_closable(100));
+        won.pot_distributed = true;
+        assert!(won.is_closable(50));
+        won.live_tips = 1;
+        assert!(!won.is_closable(50));
+    }
+
+    #[test]
+    fn grace_period_extends_guessing_but_not_entry() {
+        let mut round = Round {
+            is_active: true,
+            expires_at: 100,
+            grace_seconds: 30,
+            ..Default::default()
+        };
+        assert_eq!(round.guesses_close_at(), 130);
+        assert!(require_guessing_open(&round, 99).is_ok());
+        assert!(require_guessing_open(&round, 100).is_ok());
+        assert!(require_guessing_open(&round, 129).is_ok());
+        assert!(require_guessing_open(&round, 130).is_err());
+
+        // A win in the grace period is still paid; refunds wait for it.
+        round.won_at = 130;
+        assert!(round.won_before_expiry());
+        round.won_at = 131;
+        assert!(!round.won_before_expiry());
+        assert!(!round.is_refund_path(129));
+        assert!(round.is_refund_path(130));
+
+        round.grace_seconds = 0;
+        assert!(require_guessing_open(&round, 100).is_err());
+    }
+
+    #[test]
+    fn rounds_cannot_be_cancelled_once_guessing_closes() {
+        let mut round = Round {
+            is_active: true,
+            expires_at: 100,
+            grace_seconds: 30,
+            ..Default::default()
+        };
+        assert!(require_cancellable(&round, 99).is_ok());
+        assert!(require_cancellable(&round, 129).is_ok());
+        assert!(require_cancellable(&round, 130).is_err());
+        assert!(require_cancellable(&round, 1_000).is_err());
+
+        round.has_winner = true;
+        assert!(require_cancellable(&round, 99).is_err());
+        round.has_winner = false;
+        round.is_active = false;
+        assert!(require_cancellable(&round, 99).is_err());
+    }
+
+    #[test]
+    fn guess_outcome_return_data_layout() {
+        let outcome = GuessOutcome {
+            is_correct: true,
+            attempt: 0x0102_0304,
+            win_cooldown_active: false,
+        };
+        let bytes = outcome.try_to_vec().unwrap();
+        assert_eq!(bytes, vec![1, 0x04, 0x03, 0x02, 0x01, 0]);
+        assert_eq!(GuessOutcome::try_from_slice(&bytes).unwrap(), outcome);
+    }
+
+    #[test]
+    fn late_entries_extend_the_round_up_to_the_cap() {
+        let mut round = Round {
+            expires_at: 1_000,
+            anti_snipe_seconds: 60,
+            max_anti_snipe_seconds: 150,
+            ..Default::default()
+        };
+        // Just outside the window, then just inside it.
+        assert_eq!(round.apply_anti_snipe(939), None);
+        assert_eq!(round.expires_at, 1_000);
+        assert_eq!(round.apply_anti_snipe(940), Some(1_000));
+        assert_eq!(round.expires_at, 1_060);
+
+        assert_eq!(round.apply_anti_snipe(1_059), Some(1_060));
+        assert_eq!(round.expires_at, 1_120);
+        // Only 30 seconds of the cap are left.
+        assert_eq!(round.apply_anti_snipe(1_100), Some(1_120));
+        assert_eq!(round.expires_at, 1_150);
+        assert_eq!(round.anti_snipe_extended_seconds, 150);
+        assert_eq!(round.apply_anti_snipe(1_149), None);
+        assert_eq!(round.expires_at, 1_150);
+
+        round.anti_snipe_seconds = 0;
+        round.max_anti_snipe_seconds = i64::MAX;
+        assert_eq!(round.apply_anti_snipe(1_149), None);
+    }
+
+    #[test]
+    fn pot_cap_admits_entries_up_to_exactly_the_cap() {
+        let entry_fee = 50_000_000;
+        let mut round = Round {
+            max_pot_lamports: 3 * entry_fee,
+            ..Default::default()
+        };
+        for _ in 0..3 {
+            assert!(round.fits_pot_cap(entry_fee));
+            round.pot_lamports += entry_fee;
+        }
+        assert_eq!(round.pot_lamports, round.max_pot_lamports);
+        assert!(!round.fits_pot_cap(entry_fee));
+        assert!(!round.fits_pot_cap(1));
+
+        round.max_pot_lamports = 0;
+        assert!(round.fits_pot_cap(u64::MAX - round.pot_lamports));
+    }
+
+    #[test]
+    fn memo_is_flattened_to_one_line() {
+        assert_eq!(sanitize_memo("gg\nez\u{7}"), "ggez");
+        assert_eq!(sanitize_memo("  nice try  "), "nice try");
+        assert_eq!(sanitize_memo("so close 🔥"), "so close 🔥");
+    }
+
+    #[test]
+    fn win_cooldown_runs_from_last_win() {
+        let mut leaderboard = Leaderboard {
+            capacity: 4,
+            ..Default::default()
+        };
+        let winner = Pubkey::new_from_array([1; 32]);
+        let newcomer = Pubkey::new_from_array([2; 32]);
+        leaderboard
+            .record_win(winner, 100, 0, 1_000, &GameConfig::default())
+            .unwrap();
+
+        assert!(leaderboard.in_win_cooldown(&winner, 60, 1_059));
+        assert!(!leaderboard.in_win_cooldown(&winner, 60, 1_060));
+        assert!(!leaderboard.in_win_cooldown(&newcomer, 60, 1_000));
+
+        let profile = PlayerProfile::default();
+        let mut game_config = GameConfig::default();
+        assert!(!win_cooldown_active(&game_config, None, &profile, &winner, 1_000).unwrap());
+        game_config.win_cooldown_seconds = 60;
+        assert!(win_cooldown_active(&game_config, None, &profile, &winner, 1_000).is_err());
+        assert!(
+            win_cooldown_active(&game_config, Some(&leaderboard), &profile, &winner, 1_030)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn win_cooldown_starts_when_the_win_is_made() {
+        let mut round = Round {
+            is_active: true,
+            expires_at: 1_000,
+            ..Default::default()
+        };
+        round.word_hash = answer_hash(&round, b"solana").unwrap();
+        let winner = Pubkey::new_from_array([1; 32]);
+        let mut profile = PlayerProfile::default();
+        assert!(settle_guess(
+            &mut round,
+            None,
+            Some(&mut profile),
+            winner,
+            b"solana",
+            &[],
+            500,
+            false,
+        )
+        .unwrap());
+        assert_eq!(profile.last_win_at, 500);
+
+        // Not paid out yet, so not on the leaderboard, but still cooling down.
+        let leaderboard = Leaderboard::default();
+        let game_config = GameConfig {
+            win_cooldown_seconds: 60,
+            ..Default::default()
+        };
+        assert!(
+            win_cooldown_active(&game_config, Some(&leaderboard), &profile, &winner, 559).unwrap()
+        );
+        assert!(
+            !win_cooldown_active(&game_config, Some(&leaderboard), &profile, &winner, 560).unwrap()
+        );
+    }
+
+    #[test]
+    fn drained_round_refuses_to_distribute() {
+        let rent = Rent::default();
+        let min_balance = rent.minimum_balance(Round::SIZE);
+        let pot = 1_000_000;
+
+        // Drained down to a single lamport above rent.
+        let available = surplus_over_rent(&rent, min_balance + 1, Round::SIZE).unwrap();
+        assert_eq!(available, 1);
+        assert!(require_distributable(pot, available, 0).is_err());
+        assert!(require_distributable(pot, available, pot - 2).is_err());
+        assert!(require_distributable(pot, available, pot - 1).is_ok());
+
+        // Fully funded, with a few stray lamports on top of the pot.
+        let available = surplus_over_rent(&rent, min_balance + pot + 5, Round::SIZE).unwrap();
+        assert!(require_distributable(pot, available, 0).is_ok());
+        let split = split_pot(pot, available, 0, 0, 1).unwrap();
+        assert_eq!(split.winner_amount, pot);
+        assert_eq!(split.fee, 5);
+
+        // Below rent there is nothing to pay out at all.
+        assert!(surplus_over_rent(&rent, min_balance - 1, Round::SIZE).is_err());
+    }
+
+    #[test]
+    fn pot_split_leaves_round_at_rent_minimum() {
+        let min_balance = Rent::default().minimum_balance(Round::SIZE);
+        // 1001 lamports at 2.5% is a 25.025 fee, the three-way split of the
+        // rest leaves a 1-lamport remainder, and 1 stray lamport sits
+        // outside the pot.
+        let pot = 1_001;
+        let mut lamports = min_balance + pot + 1;
+
+        let split = split_pot(pot, lamports - min_balance, 250, 0, 3).unwrap();
+        assert_eq!(
+            split,
+            PotSplit {
+                fee: 26,
+                runner_up_amount: 0,
+                winner_amount: 976,
+                share: 325,
+                first_share: 326,
+            }
+        );
+
+        lamports -= split.first_share + 2 * split.share + split.runner_up_amount + split.fee;
+        assert_eq!(lamports, min_balance);
+    }
+
+    #[test]
+    fn reward_metadata_must_follow_template() {
+        let mut game_config = GameConfig::default();
+        assert!(game_config
+            .require_reward_metadata(7, "Anything", "https://example.com")
+            .is_ok());
+
+        game_config.nft_name_prefix = "SolPot Winner #".to_string();
+        game_config.nft_uri_prefix = "https://arweave.net/solpot-winner-".to_string();
+        assert!(game_config
+            .require_reward_metadata(7, "SolPot Winner #7", "https://arweave.net/solpot-winner-7")
+            .is_ok());
+        assert!(game_config
+            .require_reward_metadata(7, "SolPot Winner #8", "https://arweave.net/solpot-winner-7")
+            .is_err());
+        assert!(game_config
+            .require_reward_metadata(7, "SolPot Winner #7", "https://arweave.net/solpot-winner-70")
+            .is_err());
+    }
+
+    #[test]
+    fn fee_override_takes_a_smaller_cut_than_the_game() {
+        let game_fee_bps = 250;
+        let hard_round = Round {
+            fee_basis_points_override: Some(100),
+            ..Default::default()
+        };
+        let plain_round = Round::default();
+        assert_eq!(hard_round.fee_basis_points(game_fee_bps), 100);
+        assert_eq!(plain_round.fee_basis_points(game_fee_bps), game_fee_bps);
+
+        let pot = 1_000_000;
+        let hard = split_pot(pot, pot, hard_round.fee_basis_points(game_fee_bps), 0, 1).unwrap();
+        let plain = split_pot(pot, pot, plain_round.fee_basis_points(game_fee_bps), 0, 1).unwrap();
+        assert_eq!(hard.fee, 10_000);
+        assert_eq!(plain.fee, 25_000);
+        assert_eq!(hard.winner_amount, plain.winner_amount + 15_000);
+    }
+
+    #[test]
+    fn only_the_current_authority_operates_undelegated_rounds() {
+        let old_authority = Pubkey::new_unique();
+        let new_authority = Pubkey::new_unique();
+        let round = Round::default();
+        let game_config = GameConfig {
+            authority: new_authority,
+            ..Default::default()
+        };
+        assert!(round.is_operator(&game_config, &new_authority));
+        assert!(!round.is_operator(&game_config, &old_authority));
+        assert!(!round.is_operator(&game_config, &Pubkey::default()));
+    }
+
+    #[test]
+    fn full_guess_log_keeps_accepting_guesses() {
+        let entry = |timestamp| GuessLogEntry {
+            player: Pubkey::default(),
+            guess_hash: [0u8; 32],
+            timestamp,
+        };
+        let mut log = GuessLog::default();
+        for timestamp in 0..=GuessLog::MAX_ENTRIES as i64 {
+            log.append(entry(timestamp));
+        }
+        assert_eq!(log.entries.len(), GuessLog::MAX_ENTRIES);
+        assert_eq!(log.entries[0].timestamp, 0);
+
+        log.overwrite_oldest = true;
+        log.append(entry(100));
+        assert_eq!(log.entries[0].timestamp, 100);
+        assert_eq!(log.head, 1);
+    }
+
+    #[test]
+    fn earliest_commitment_wins_whatever_the_reveal_order() {
+        let mut round = Round {
+            is_active: true,
+            reveal_window_seconds: 600,
+            ..Default::default()
+        };
+        round.word_hash = answer_hash(&round, b"solana").unwrap();
+        let early = Pubkey::new_unique();
+        let middle = Pubkey::new_unique();
+        let late = Pubkey::new_unique();
+
+        // The later committer reveals first and wins for now.
+        let outcome =
+            settle_reveal(&mut round, None, None, late, b"solana", &[], 300, false).unwrap();
+        assert_eq!(
+            outcome,
+            RevealOutcome::Settled {
+                is_correct: true,
+                displaced_winner: None,
+            }
+        );
+        assert_eq!(round.winner, late);
+
+        // An earlier commitment revealed afterwards takes the win.
+        let outcome =
+            settle_reveal(&mut round, None, None, early, b"solana", &[], 100, false).unwrap();
+        assert_eq!(
+            outcome,
+            RevealOutcome::Settled {
+                is_correct: true,
+                displaced_winner: Some(late),
+            }
+        );
+        assert_eq!(round.winner, early);
+        assert_eq!(round.won_at, 100);
+        assert!(round.has_winner && !round.is_active);
+
+        // Payouts, bonus rounds, streak resets and mints wait until no
+        // earlier commitment can still be revealed.
+        assert!(!round.win_is_final(699));
+        assert!(round.win_is_final(700));
+
+        // Later commitments, and wrong reveals, don't change anything.
+        let outcome =
+            settle_reveal(&mut round, None, None, middle, b"solana", &[], 200, false).unwrap();
+        assert_eq!(outcome, RevealOutcome::LaterCommitment);
+        assert!(settle_reveal(&mut round, None, None, middle, b"bitcoin", &[], 50, false).is_err());
+        assert_eq!(round.winner, early);
+
+        // Nor does anything once the pot is paid out.
+        round.pot_distributed = true;
+        let outcome =
+            settle_reveal(&mut round, None, None, middle, b"solana", &[], 50, false).unwrap();
+        assert_eq!(outcome, RevealOutcome::LaterCommitment);
+        assert_eq!(round.winner, early);
+    }
+
+    #[test]
+    fn scheduled_round_opens_at_starts_at() {
+        let round = Round {
+            is_active: true,
+            created_at: 100,
+            starts_at: 500,
+            expires_at: 800,
+            ..Default::default()
+        };
+        assert!(!round.has_started(499));
+        assert_eq!(round.phase(499), RoundPhase::Pending);
+        assert!(require_guessing_open(&round, 499).is_err());
+
+        assert!(round.has_started(500));
+        assert_eq!(round.phase(500), RoundPhase::Active);
+        assert!(require_guessing_open(&round, 500).is_ok());
+        assert!(require_guessing_open(&round, 800).is_err());
+    }
+
+    #[test]
+    fn first_entry_unseals_a_sealed_round() {
+        let mut round = Round {
+            sealed: true,
+            ..Default::default()
+        };
+        assert!(round.is_sealed());
+        round.player_count = 1;
+        assert!(!round.is_sealed());
+
+        round.sealed = false;
+        round.player_count = 0;
+        assert!(!round.is_sealed());
+    }
+
+    #[test]
+    fn round_nonce_blocks_resubmission_within_the_window() {
+        let nonce = [7u8; 16];
+        let game_config = GameConfig {
+            last_round_nonce: nonce,
+            last_round_created_at: 1_000,
+            ..Default::default()
+        };
+        let retry_until = 1_000 + Round::NONCE_WINDOW_SECONDS;
+        assert!(game_config
+            .require_fresh_round_nonce(&nonce, 1_001)
+            .is_err());
+        assert!(game_config
+            .require_fresh_round_nonce(&nonce, retry_until - 1)
+            .is_err());
+        assert!(game_config
+            .require_fresh_round_nonce(&nonce, retry_until)
+            .is_ok());
+        assert!(game_config
+            .require_fresh_round_nonce(&[8u8; 16], 1_001)
+            .is_ok());
+
+        // A zero nonce opts out of the guard.
+        let game_config = GameConfig::default();
+        assert!(game_config.require_fresh_round_nonce(&[0u8; 16], 0).is_ok());
+    }
+
+    #[test]
+    fn charity_round_donates_its_share_instead_of_a_fee() {
+        let game_config = GameConfig {
+            fee_basis_points: 250,
+            ..Default::default()
+        };
+        let mut round = Round {
+            pot_lamports: 1_000_000,
+            charity_mode: true,
+            charity_wallet: Pubkey::new_unique(),
+            charity_basis_points: 2_000,
+            ..Default::default()
+        };
+        assert_eq!(round.fee_basis_points(game_config.fee_basis_points), 0);
+
+        // Lamports beyond the pot go to the charity as well.
+        let plan = plan_payout(&round, &game_config, None, 1_000_005).unwrap();
+        assert_eq!(plan.split.fee, 0);
+        assert_eq!(plan.donation, 200_005);
+        assert_eq!(plan.split.winner_amount, 800_000);
+
+        round.charity_mode = false;
+        let plan = plan_payout(&round, &game_config, None, 1_000_000).unwrap();
+        assert_eq!(plan.split.fee, 25_000);
+        assert_eq!(plan.donation, 0);
+    }
+
+    #[test]
+    fn prize_vault_vests_linearly_after_cliff() {
+        let vault = PrizeVault {
+            total: 1_000,
+            start: 100,
+            cliff_seconds: 10,
+            duration_seconds: 40,
+            ..Default::default()
+        };
+        assert_eq!(vault.vested_at(50), 0);
+        assert_eq!(vault.vested_at(109), 0);
+        assert_eq!(vault.vested_at(110), 250);
+        assert_eq!(vault.vested_at(120), 500);
+        assert_eq!(vault.vested_at(140), 1_000);
+        assert_eq!(vault.vested_at(i64::MAX), 1_000);
+    }
+
+    #[test]
+    fn merkle_proof_accepts_every_leaf() {
+        let leaves = [leaf("colour"), leaf("color"), leaf("hue")];
+        let ab = hash_merkle_pair(&leaves[0], &leaves[1]);
+        let root = hash_merkle_pair(&ab, &leaves[2]);
+
+        assert!(verify_merkle_proof(leaves[0], &[leaves[1], leaves[2]], root));
+        assert!(verify_merkle_proof(leaves[1], &[leaves[0], leaves[2]], root));
+        assert!(verify_merkle_proof(leaves[2], &[ab], root));
+    }
+
+    #[test]
+    fn merkle_proof_rejects_wrong_leaf_or_path() {
+        let leaves = [leaf("colour"), leaf("color"), leaf("hue")];
+        let ab = hash_merkle_pair(&leaves[0], &leaves[1]);
+        let root = hash_merkle_pair(&ab, &leaves[2]);
+
+        assert!(!verify_merkle_proof(leaf("shade"), &[leaves[1], leaves[2]], root));
+        assert!(!verify_merkle_proof(leaves[0], &[leaves[2], leaves[1]], root));
+        assert!(!verify_merkle_proof(leaves[0], &[], root));
+    }
+
+    #[test]
+    fn answer_set_builder_matches_level_by_level_root() {
+        for n in 1..=9u8 {
+            let leaves: Vec<[u8; 32]> = (0..n).map(|i| leaf(&i.to_string())).collect();
+
+            let mut level = leaves.clone();
+            while level.len() > 1 {
+                level = level
+                    .chunks(2)
+                    .map(|pair| match pair {
+                        [a, b] => hash_merkle_pair(a, b),
+                        [a] => *a,
+                        _ => unreachable!(),
+                    })
+                    .collect();
+            }
+
+            let mut builder = AnswerSetBuilder::default();
+            for leaf in &leaves {
+                builder.push(*leaf).unwrap();
+            }
+            assert_eq!(builder.root(), Some(level[0]), "{n} leaves");
+        }
+        assert_eq!(AnswerSetBuilder::default().root(), None);
+    }
+
+    #[test]
+    fn single_leaf_tree_needs_no_proof() {
+        assert!(verify_merkle_proof(leaf("solana"), &[], leaf("solana")));
+    }
+
+    #[test]
+    fn round_phase_follows_lifecycle() {
+        let mut round = Round {
+            is_active: true,
+            created_at: 100,
+            expires_at: 200,
+            player_count: 1,
+            live_entries: 1,
+            ..Default::default()
+        };
+        assert_eq!(round.phase(50), RoundPhase::Pending);
+        assert_eq!(round.phase(150), RoundPhase::Active);
+        assert_eq!(round.phase(250), RoundPhase::Expired);
+
+        round.has_winner = true;
+        round.is_active = false;
+        assert_eq!(round.phase(150), RoundPhase::Won);
+        round.pot_distributed = true;
+        assert_eq!(round.phase(150), RoundPhase::Distributed);
+        round.live_entries = 0;
+        assert_eq!(round.phase(150), RoundPhase::Closed);
+    }
+
+    #[test]
+    fn wins_must_not_postdate_expiry() {
+        let mut round = Round {
+            expires_at: 200,
+            won_at: 199,
+            ..Default::default()
+        };
+        assert!(round.won_before_expiry());
+        round.won_at = 200;
+        assert!(round.won_before_expiry());
+        round.won_at = 201;
+        assert!(!round.won_before_expiry());
+    }
+
+    #[test]
+    fn create_v1_data_without_plugins() {
+        let data = core_create_v1_data("ab", "u", None, &[]);
+        assert_eq!(
+            data,
+            vec![0, 0, 2, 0, 0, 0, b'a', b'b', 1, 0, 0, 0, b'u', 1, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn create_v1_data_with_royalties_and_attributes() {
+        let creator = Pubkey::new_from_array([7u8; 32]);
+        let attributes = [NftAttribute {
+            key: "id".to_string(),
+            value: "3".to_string(),
+        }];
+        let data = core_create_v1_data("", "", Some((500, creator)), &attributes);
+
+        let mut expected = vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        expected.extend_from_slice(&[1, 2, 0, 0, 0]);
+        // Royalties { basis_points, creators: [creator @ 100%], rule_set: None }
+        expected.extend_from_slice(&[0, 0xf4, 0x01, 1, 0, 0, 0]);
+        expected.extend_from_slice(&[7u8; 32]);
+        expected.extend_from_slice(&[100, 0, 0]);
+        // Attributes { attribute_list: [("id", "3")] }
+        expected.extend_from_slice(&[6, 1, 0, 0, 0, 2, 0, 0, 0, b'i', b'd', 1, 0, 0, 0, b'3', 0]);
+        assert_eq!(data, expected);
+    }
 }
@@ -1,8 +1,14 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
-import { PublicKey, SystemProgram, Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import {
+  PublicKey,
+  SystemProgram,
+  Keypair,
+  LAMPORTS_PER_SOL,
+  Transaction,
+} from "@solana/web3.js";
 import { expect } from "chai";
-import { createHash } from "crypto";
+import { createHash, randomBytes } from "crypto";
 
 // IDL type will be generated after `anchor build`
 // import { Solpot } from "../target/types/solpot";
@@ -12,9 +18,12 @@ describe("solpot", () => {
   anchor.setProvider(provider);
 
   const program = anchor.workspace.Solpot as Program<any>;
+  // Test harness that calls `submit_guess` via CPI
+  const guessRelay = anchor.workspace.GuessRelay as Program<any>;
   const authority = provider.wallet as anchor.Wallet;
 
   const ENTRY_FEE = new anchor.BN(0.05 * LAMPORTS_PER_SOL);
+  const MIN_ENTRY_FEE = new anchor.BN(0.01 * LAMPORTS_PER_SOL);
   const FEE_BPS = 250; // 2.5%
   const SECRET_WORD = "solana";
   const WORD_HASH = createHash("sha256").update(SECRET_WORD).digest();
@@ -34,15 +43,53 @@ describe("solpot", () => {
     program.programId
   );
 
+  const [feeVaultPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("fee_vault"), gameConfigPda.toBuffer()],
+    program.programId
+  );
+
+  const [jackpotPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("jackpot"), gameConfigPda.toBuffer()],
+    program.programId
+  );
+
+  const [blacklistPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("blacklist"), gameConfigPda.toBuffer()],
+    program.programId
+  );
+
+  const [seasonPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("season"), gameConfigPda.toBuffer()],
+    program.programId
+  );
+
+  const SEASON_PRIZE = new anchor.BN(LAMPORTS_PER_SOL / 10);
+
+  const profilePda = (player: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("profile"), gameConfigPda.toBuffer(), player.toBuffer()],
+      program.programId
+    )[0];
+
   let roundPda: PublicKey;
   let roundBump: number;
+  let guessLogPda: PublicKey;
+  let roundLeaderboardPda: PublicKey;
+  let tipper: Keypair;
+  // Entered player whose guesses all miss
+  let wrongGuesser: Keypair;
+  // Wins the first round, and so the season
+  let roundWinner: Keypair;
+  let tipRecordPda: PublicKey;
 
   it("Initializes the game", async () => {
     const tx = await program.methods
-      .initializeGame(ENTRY_FEE, FEE_BPS)
+      .initializeGame(ENTRY_FEE, FEE_BPS, null, MIN_ENTRY_FEE)
       .accountsStrict({
         gameConfig: gameConfigPda,
         leaderboard: leaderboardPda,
+        feeVault: feeVaultPda,
+        jackpot: jackpotPda,
         authority: authority.publicKey,
         systemProgram: SystemProgram.programId,
       })
@@ -59,6 +106,61 @@ describe("solpot", () => {
       ENTRY_FEE.toNumber()
     );
     expect(gameConfig.feeBasisPoints).to.equal(FEE_BPS);
+    expect(gameConfig.minEntryFee.toNumber()).to.equal(
+      MIN_ENTRY_FEE.toNumber()
+    );
+    expect(gameConfig.schemaVersion).to.equal(3);
+
+    // Already on the current layout, so migrating changes nothing
+    await program.methods
+      .migrateGameConfig()
+      .accountsStrict({
+        gameConfig: gameConfigPda,
+        feeVault: feeVaultPda,
+        jackpot: jackpotPda,
+        authority: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    const migrated = await (program.account as any).gameConfig.fetch(gameConfigPda);
+    expect(migrated.schemaVersion).to.equal(3);
+    expect(migrated.feeDestination.toBase58()).to.equal(
+      gameConfig.feeDestination.toBase58()
+    );
+  });
+
+  it("Starts a season covering the next rounds", async () => {
+    await program.methods
+      .startSeason(new anchor.BN(3), SEASON_PRIZE, 1)
+      .accountsStrict({
+        gameConfig: gameConfigPda,
+        season: seasonPda,
+        authority: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const season = await (program.account as any).season.fetch(seasonPda);
+    expect(season.seasonId).to.equal(1);
+    expect(season.isActive).to.be.true;
+    expect(season.startRound.toNumber()).to.equal(0);
+    expect(season.endRound.toNumber()).to.equal(3);
+    expect(season.prizePool.toNumber()).to.equal(SEASON_PRIZE.toNumber());
+
+    try {
+      await program.methods
+        .startSeason(new anchor.BN(3), new anchor.BN(0), 1)
+        .accountsStrict({
+          gameConfig: gameConfigPda,
+          season: seasonPda,
+          authority: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      expect.fail("a second season should be rejected while one runs");
+    } catch (err: any) {
+      expect(err.error.errorCode.code).to.equal("SeasonActive");
+    }
   });
 
   it("Creates a round", async () => {
@@ -71,17 +173,69 @@ describe("solpot", () => {
       ],
       program.programId
     );
+    [guessLogPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("guess_log"), roundPda.toBuffer()],
+      program.programId
+    );
+    [roundLeaderboardPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("round_leaderboard"), roundPda.toBuffer()],
+      program.programId
+    );
 
     const tx = await program.methods
-      .createRound(
-        Array.from(WORD_HASH) as number[],
-        10, // max_players
-        new anchor.BN(3600) // 1 hour duration
-      )
+      .createRound({
+        wordHash: Array.from(WORD_HASH) as number[],
+        maxPlayers: 10,
+        durationSeconds: new anchor.BN(3600), // 1 hour duration
+        allowMultipleWinners: false,
+        maxGuesses: 3,
+        guessFeeLamports: new anchor.BN(0),
+        revealWindowSeconds: new anchor.BN(0), // plain guessing
+        answersMerkleRoot: new Array(32).fill(0), // single answer
+        minPlayers: 0,
+        randomFallback: false,
+        guessLogOverwrite: false,
+        gateMint: null,
+        wordLength: SECRET_WORD.length,
+        hintUri: "",
+        claimWindowSeconds: new anchor.BN(0), // prize never lapses
+        answerCommitment: new Array(32).fill(0), // none
+        feeIncrementLamports: new anchor.BN(0), // flat fee
+        maxEntryFeeLamports: new anchor.BN(0),
+        guessCooldownSeconds: new anchor.BN(0), // no cooldown
+        normalizationFlags: 0, // lowercase only
+        milestoneLamports: new anchor.BN(0), // no milestones
+        vestingDurationSeconds: new anchor.BN(0), // instant payout
+        vestingCliffSeconds: new anchor.BN(0),
+        allowlistRoot: new Array(32).fill(0), // open round
+        seedPot: new anchor.BN(0),
+        dictionaryRoot: new Array(32).fill(0), // any word
+        refundTips: false, // forfeited on a no-winner close
+        feeBasisPointsOverride: null, // game default
+        maxPotLamports: new anchor.BN(0), // uncapped
+        commitSlot: new anchor.BN(0), // no fairness proof
+        guessKind: 0, // string
+        graceSeconds: new anchor.BN(0), // guessing closes at expiry
+        antiSnipeSeconds: new anchor.BN(0), // no anti-sniping
+        maxAntiSnipeSeconds: new anchor.BN(0),
+        charityWallet: null, // not a fundraiser
+        charityBasisPoints: 0,
+        nonce: Array.from(randomBytes(16)), // retry key
+        sealed: false, // hint public from the start
+        startsAt: new anchor.BN(0), // open immediately
+      })
       .accountsStrict({
         gameConfig: gameConfigPda,
         round: roundPda,
+        winners: null,
+        participants: null,
+        prizeVault: null,
+        guessLog: guessLogPda,
+        roundLeaderboard: roundLeaderboardPda,
+        mint: null,
+        vault: null,
         authority: authority.publicKey,
+        tokenProgram: null,
         systemProgram: SystemProgram.programId,
       })
       .rpc();
@@ -93,6 +247,20 @@ describe("solpot", () => {
     expect(round.isActive).to.be.true;
     expect(round.hasWinner).to.be.false;
     expect(round.playerCount).to.equal(0);
+    expect(round.guessKind).to.equal(0);
+    expect(round.startsAt.toNumber()).to.equal(round.createdAt.toNumber());
+
+    const phase = await program.methods
+      .roundPhase()
+      .accountsStrict({ round: roundPda })
+      .view();
+    expect(phase).to.deep.equal({ active: {} });
+
+    const hint = await program.methods
+      .roundHint()
+      .accountsStrict({ round: roundPda })
+      .view();
+    expect(hint).to.equal("");
   });
 
   it("Player enters the round", async () => {
@@ -108,18 +276,31 @@ describe("solpot", () => {
       [
         Buffer.from("player_entry"),
         roundPda.toBuffer(),
+        Buffer.alloc(4), // round generation
         player.publicKey.toBuffer(),
       ],
       program.programId
     );
 
     const tx = await program.methods
-      .enterRound()
+      .enterRound(ENTRY_FEE, null, [])
       .accountsStrict({
         gameConfig: gameConfigPda,
         round: roundPda,
         playerEntry: playerEntryPda,
+        playerProfile: profilePda(player.publicKey),
+        blacklist: blacklistPda,
+        participants: null,
+        roundLeaderboard: roundLeaderboardPda,
+        leaderboard: null,
         player: player.publicKey,
+        rentSponsor: null,
+        playerTokenAccount: null,
+        vault: null,
+        gateTokenAccount: null,
+        referrerAccount: null,
+        referralStats: null,
+        tokenProgram: null,
         systemProgram: SystemProgram.programId,
       })
       .signers([player])
@@ -132,7 +313,34 @@ describe("solpot", () => {
     expect(round.potLamports.toNumber()).to.equal(ENTRY_FEE.toNumber());
   });
 
-  it("Player submits incorrect guess", async () => {
+  it("Raises a live round's player limit but never lowers it", async () => {
+    await program.methods
+      .setMaxPlayers(12)
+      .accountsStrict({
+        gameConfig: gameConfigPda,
+        round: roundPda,
+        authority: authority.publicKey,
+      })
+      .rpc();
+    const round = await (program.account as any).round.fetch(roundPda);
+    expect(round.maxPlayers).to.equal(12);
+
+    try {
+      await program.methods
+        .setMaxPlayers(11)
+        .accountsStrict({
+          gameConfig: gameConfigPda,
+          round: roundPda,
+          authority: authority.publicKey,
+        })
+        .rpc();
+      expect.fail("lowering the limit should be rejected");
+    } catch (err: any) {
+      expect(err.error.errorCode.code).to.equal("CannotReduceBelowCount");
+    }
+  });
+
+  it("Rejects a guess from a player who never entered", async () => {
     const player = Keypair.generate();
     const airdropSig = await provider.connection.requestAirdrop(
       player.publicKey,
@@ -144,6 +352,138 @@ describe("solpot", () => {
       [
         Buffer.from("player_entry"),
         roundPda.toBuffer(),
+        Buffer.alloc(4), // round generation
+        player.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const [guessRecordPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("guess_record"),
+        roundPda.toBuffer(),
+        Buffer.alloc(4), // round generation
+        player.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+
+    try {
+      await program.methods
+        .submitGuess(SECRET_WORD, [], [], [])
+        .accountsStrict({
+          gameConfig: gameConfigPda,
+          round: roundPda,
+          playerEntry: playerEntryPda,
+          playerProfile: profilePda(player.publicKey),
+          blacklist: blacklistPda,
+          guessRecord: guessRecordPda,
+          winners: null,
+          guessLog: guessLogPda,
+          roundLeaderboard: roundLeaderboardPda,
+          jackpot: jackpotPda,
+          leaderboard: null,
+          player: player.publicKey,
+          rentSponsor: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([player])
+        .rpc();
+      expect.fail("guess without an entry should be rejected");
+    } catch (err: any) {
+      expect(err.error.errorCode.code).to.equal("NotEntered");
+    }
+  });
+
+  it("Keeps blacklisted wallets out", async () => {
+    const player = Keypair.generate();
+    const airdropSig = await provider.connection.requestAirdrop(
+      player.publicKey,
+      2 * LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdropSig);
+
+    const [playerEntryPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("player_entry"),
+        roundPda.toBuffer(),
+        Buffer.alloc(4), // round generation
+        player.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+
+    const manageAccounts = {
+      gameConfig: gameConfigPda,
+      blacklist: blacklistPda,
+      authority: authority.publicKey,
+      systemProgram: SystemProgram.programId,
+    };
+    await program.methods
+      .addToBlacklist(player.publicKey)
+      .accountsStrict(manageAccounts)
+      .rpc();
+
+    try {
+      await program.methods
+        .enterRound(ENTRY_FEE, null, [])
+        .accountsStrict({
+          gameConfig: gameConfigPda,
+          round: roundPda,
+          playerEntry: playerEntryPda,
+          playerProfile: profilePda(player.publicKey),
+          blacklist: blacklistPda,
+          participants: null,
+          roundLeaderboard: roundLeaderboardPda,
+          leaderboard: null,
+          player: player.publicKey,
+          rentSponsor: null,
+          playerTokenAccount: null,
+          vault: null,
+          gateTokenAccount: null,
+          referrerAccount: null,
+          referralStats: null,
+          tokenProgram: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([player])
+        .rpc();
+      expect.fail("a blacklisted wallet should not be able to enter");
+    } catch (err: any) {
+      expect(err.error.errorCode.code).to.equal("PlayerBlacklisted");
+    }
+
+    await program.methods
+      .removeFromBlacklist(player.publicKey)
+      .accountsStrict(manageAccounts)
+      .rpc();
+    const blacklist = await (program.account as any).blacklist.fetch(
+      blacklistPda
+    );
+    expect(blacklist.players.length).to.equal(0);
+  });
+
+  it("Player submits incorrect guess", async () => {
+    const player = (wrongGuesser = Keypair.generate());
+    const airdropSig = await provider.connection.requestAirdrop(
+      player.publicKey,
+      2 * LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdropSig);
+
+    const [playerEntryPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("player_entry"),
+        roundPda.toBuffer(),
+        Buffer.alloc(4), // round generation
+        player.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const [guessRecordPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("guess_record"),
+        roundPda.toBuffer(),
+        Buffer.alloc(4), // round generation
         player.publicKey.toBuffer(),
       ],
       program.programId
@@ -151,72 +491,386 @@ describe("solpot", () => {
 
     // Enter round first
     await program.methods
-      .enterRound()
+      .enterRound(ENTRY_FEE, null, [])
       .accountsStrict({
         gameConfig: gameConfigPda,
         round: roundPda,
         playerEntry: playerEntryPda,
+        playerProfile: profilePda(player.publicKey),
+        blacklist: blacklistPda,
+        participants: null,
+        roundLeaderboard: roundLeaderboardPda,
+        leaderboard: null,
         player: player.publicKey,
+        rentSponsor: null,
+        playerTokenAccount: null,
+        vault: null,
+        gateTokenAccount: null,
+        referrerAccount: null,
+        referralStats: null,
+        tokenProgram: null,
         systemProgram: SystemProgram.programId,
       })
       .signers([player])
       .rpc();
 
-    // Submit wrong guess
+    // Submit wrong guess; the authority sponsors the guess record's rent
+    const balanceBefore = await provider.connection.getBalance(player.publicKey);
     const tx = await program.methods
-      .submitGuess("ethereum")
+      .submitGuess("ethereum", [], [], [])
       .accountsStrict({
+        gameConfig: gameConfigPda,
         round: roundPda,
         playerEntry: playerEntryPda,
+        playerProfile: profilePda(player.publicKey),
+        blacklist: blacklistPda,
+        guessRecord: guessRecordPda,
+        winners: null,
+        guessLog: guessLogPda,
+        roundLeaderboard: roundLeaderboardPda,
+        jackpot: jackpotPda,
+        leaderboard: null,
         player: player.publicKey,
+        rentSponsor: authority.publicKey,
+        systemProgram: SystemProgram.programId,
       })
       .signers([player])
       .rpc();
 
     console.log("Wrong guess tx:", tx);
+    expect(await provider.connection.getBalance(player.publicKey)).to.equal(
+      balanceBefore
+    );
 
     const round = await (program.account as any).round.fetch(roundPda);
     expect(round.hasWinner).to.be.false;
     expect(round.isActive).to.be.true;
-  });
+    expect(round.totalGuesses).to.equal(1);
+    expect(round.correctGuesses).to.equal(0);
 
-  it("Player submits correct guess and wins", async () => {
-    const player = Keypair.generate();
-    const airdropSig = await provider.connection.requestAirdrop(
-      player.publicKey,
-      2 * LAMPORTS_PER_SOL
+    const guessLog = await (program.account as any).guessLog.fetch(guessLogPda);
+    expect(guessLog.entries.length).to.equal(1);
+    expect(guessLog.entries[0].player.toBase58()).to.equal(
+      player.publicKey.toBase58()
     );
-    await provider.connection.confirmTransaction(airdropSig);
+    expect(Buffer.from(guessLog.entries[0].guessHash)).to.deep.equal(
+      createHash("sha256").update("ethereum").digest()
+    );
+  });
 
+  it("Player submits a guess as raw bytes", async () => {
+    const player = wrongGuesser;
     const [playerEntryPda] = PublicKey.findProgramAddressSync(
       [
         Buffer.from("player_entry"),
         roundPda.toBuffer(),
+        Buffer.alloc(4), // round generation
+        player.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const [guessRecordPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("guess_record"),
+        roundPda.toBuffer(),
+        Buffer.alloc(4), // round generation
         player.publicKey.toBuffer(),
       ],
       program.programId
     );
 
-    // Enter
+    // A string round hashes the raw bytes the same as the string
     await program.methods
-      .enterRound()
+      .submitGuessBytes(Buffer.from("bitcoin"), [], [], [])
       .accountsStrict({
         gameConfig: gameConfigPda,
         round: roundPda,
         playerEntry: playerEntryPda,
+        playerProfile: profilePda(player.publicKey),
+        blacklist: blacklistPda,
+        guessRecord: guessRecordPda,
+        winners: null,
+        guessLog: guessLogPda,
+        roundLeaderboard: roundLeaderboardPda,
+        jackpot: jackpotPda,
+        leaderboard: null,
         player: player.publicKey,
+        rentSponsor: null,
         systemProgram: SystemProgram.programId,
       })
       .signers([player])
       .rpc();
 
+    const round = await (program.account as any).round.fetch(roundPda);
+    expect(round.totalGuesses).to.equal(2);
+    expect(round.correctGuesses).to.equal(0);
+
+    const guessLog = await (program.account as any).guessLog.fetch(guessLogPda);
+    expect(guessLog.entries.length).to.equal(2);
+    expect(Buffer.from(guessLog.entries[1].guessHash)).to.deep.equal(
+      createHash("sha256").update("bitcoin").digest()
+    );
+  });
+
+  it("Relays a guess through a calling program", async () => {
+    const player = wrongGuesser;
+    const [playerEntryPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("player_entry"),
+        roundPda.toBuffer(),
+        Buffer.alloc(4), // round generation
+        player.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const [guessRecordPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("guess_record"),
+        roundPda.toBuffer(),
+        Buffer.alloc(4), // round generation
+        player.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+
+    // A calling program reads the outcome from `submit_guess`'s return
+    // data; simulated so the attempt isn't spent
+    const relayed = await guessRelay.methods
+      .relayGuess("dogecoin")
+      .accountsStrict({
+        gameConfig: gameConfigPda,
+        round: roundPda,
+        playerEntry: playerEntryPda,
+        guessRecord: guessRecordPda,
+        playerProfile: profilePda(player.publicKey),
+        blacklist: blacklistPda,
+        guessLog: guessLogPda,
+        roundLeaderboard: roundLeaderboardPda,
+        jackpot: jackpotPda,
+        player: player.publicKey,
+        solpotProgram: program.programId,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([player])
+      .simulate();
+    const outcome = relayed.events.find(
+      (e: any) => e.name === "guessRelayed"
+    )!.data as any;
+    expect(outcome.isCorrect).to.be.false;
+    expect(outcome.attempt).to.equal(3);
+  });
+
+  it("Reconciles lamports sent straight to the round", async () => {
+    const donation = 0.01 * LAMPORTS_PER_SOL;
+    const before = await (program.account as any).round.fetch(roundPda);
+
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: authority.publicKey,
+          toPubkey: roundPda,
+          lamports: donation,
+        })
+      )
+    );
+
+    await program.methods
+      .reconcilePot(false)
+      .accountsStrict({
+        gameConfig: gameConfigPda,
+        round: roundPda,
+        feeVault: feeVaultPda,
+        authority: authority.publicKey,
+      })
+      .rpc();
+
+    const after = await (program.account as any).round.fetch(roundPda);
+    expect(after.potLamports.toNumber()).to.equal(
+      before.potLamports.toNumber() + donation
+    );
+
+    // Nothing left over to reconcile
+    try {
+      await program.methods
+        .reconcilePot(true)
+        .accountsStrict({
+          gameConfig: gameConfigPda,
+          round: roundPda,
+          feeVault: feeVaultPda,
+          authority: authority.publicKey,
+        })
+        .rpc();
+      expect.fail("reconciling twice should find no surplus");
+    } catch (err: any) {
+      expect(err.error.errorCode.code).to.equal("NoSurplus");
+    }
+  });
+
+  it("Spectator tips the pot without entering", async () => {
+    tipper = Keypair.generate();
+    const airdropSig = await provider.connection.requestAirdrop(
+      tipper.publicKey,
+      LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdropSig);
+    [tipRecordPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("tip"),
+        roundPda.toBuffer(),
+        Buffer.alloc(4), // round generation
+        tipper.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const tip = new anchor.BN(0.02 * LAMPORTS_PER_SOL);
+    const before = await (program.account as any).round.fetch(roundPda);
+
+    await program.methods
+      .tipRound(tip)
+      .accountsStrict({
+        gameConfig: gameConfigPda,
+        round: roundPda,
+        tipRecord: tipRecordPda,
+        tipper: tipper.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([tipper])
+      .rpc();
+
+    const after = await (program.account as any).round.fetch(roundPda);
+    expect(after.potLamports.toNumber()).to.equal(
+      before.potLamports.toNumber() + tip.toNumber()
+    );
+    expect(after.totalTips.toNumber()).to.equal(tip.toNumber());
+    expect(after.liveTips).to.equal(1);
+    expect(after.playerCount).to.equal(before.playerCount);
+
+    const tipRecord = await (program.account as any).tipRecord.fetch(tipRecordPda);
+    expect(tipRecord.amount.toNumber()).to.equal(tip.toNumber());
+  });
+
+  it("Authority adds a bounty to the pot", async () => {
+    const bounty = new anchor.BN(0.03 * LAMPORTS_PER_SOL);
+    const before = await (program.account as any).round.fetch(roundPda);
+
+    await program.methods
+      .addBounty(bounty)
+      .accountsStrict({
+        gameConfig: gameConfigPda,
+        round: roundPda,
+        authority: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const after = await (program.account as any).round.fetch(roundPda);
+    expect(after.potLamports.toNumber()).to.equal(
+      before.potLamports.toNumber() + bounty.toNumber()
+    );
+    expect(after.bountyLamports.toNumber()).to.equal(bounty.toNumber());
+    expect(after.totalTips.toNumber()).to.equal(before.totalTips.toNumber());
+  });
+
+  it("Player submits correct guess and wins", async () => {
+    const player = (roundWinner = Keypair.generate());
+    const airdropSig = await provider.connection.requestAirdrop(
+      player.publicKey,
+      2 * LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdropSig);
+
+    const [playerEntryPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("player_entry"),
+        roundPda.toBuffer(),
+        Buffer.alloc(4), // round generation
+        player.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const [guessRecordPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("guess_record"),
+        roundPda.toBuffer(),
+        Buffer.alloc(4), // round generation
+        player.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+
+    // A rival enters too and will guess right just after the winner
+    const rival = Keypair.generate();
+    const rivalAirdropSig = await provider.connection.requestAirdrop(
+      rival.publicKey,
+      2 * LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(rivalAirdropSig);
+    const [rivalEntryPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("player_entry"),
+        roundPda.toBuffer(),
+        Buffer.alloc(4), // round generation
+        rival.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const [rivalGuessRecordPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("guess_record"),
+        roundPda.toBuffer(),
+        Buffer.alloc(4), // round generation
+        rival.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+
+    for (const [entrant, entryPda] of [
+      [player, playerEntryPda],
+      [rival, rivalEntryPda],
+    ] as [Keypair, PublicKey][]) {
+      await program.methods
+        .enterRound(ENTRY_FEE, null, [])
+        .accountsStrict({
+          gameConfig: gameConfigPda,
+          round: roundPda,
+          playerEntry: entryPda,
+          playerProfile: profilePda(entrant.publicKey),
+          blacklist: blacklistPda,
+          participants: null,
+          roundLeaderboard: roundLeaderboardPda,
+          leaderboard: null,
+          player: entrant.publicKey,
+          rentSponsor: null,
+          playerTokenAccount: null,
+          vault: null,
+          gateTokenAccount: null,
+          referrerAccount: null,
+          referralStats: null,
+          tokenProgram: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([entrant])
+        .rpc();
+    }
+
     // Correct guess
     const tx = await program.methods
-      .submitGuess(SECRET_WORD)
+      .submitGuess(SECRET_WORD, [], [], [])
       .accountsStrict({
+        gameConfig: gameConfigPda,
         round: roundPda,
         playerEntry: playerEntryPda,
+        playerProfile: profilePda(player.publicKey),
+        blacklist: blacklistPda,
+        guessRecord: guessRecordPda,
+        winners: null,
+        guessLog: guessLogPda,
+        roundLeaderboard: roundLeaderboardPda,
+        jackpot: jackpotPda,
+        leaderboard: null,
         player: player.publicKey,
+        rentSponsor: null,
+        systemProgram: SystemProgram.programId,
       })
       .signers([player])
       .rpc();
@@ -228,15 +882,136 @@ describe("solpot", () => {
     expect(round.isActive).to.be.false;
     expect(round.winner.toBase58()).to.equal(player.publicKey.toBase58());
 
+    // The rival's correct guess lands after the round is already won
+    try {
+      await program.methods
+        .submitGuess(SECRET_WORD, [], [], [])
+        .accountsStrict({
+          gameConfig: gameConfigPda,
+          round: roundPda,
+          playerEntry: rivalEntryPda,
+          playerProfile: profilePda(rival.publicKey),
+          blacklist: blacklistPda,
+          guessRecord: rivalGuessRecordPda,
+          winners: null,
+          guessLog: guessLogPda,
+          roundLeaderboard: roundLeaderboardPda,
+          jackpot: jackpotPda,
+          leaderboard: null,
+          player: rival.publicKey,
+          rentSponsor: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([rival])
+        .rpc();
+      expect.fail("second correct guess should be rejected");
+    } catch (err: any) {
+      expect(err.error.errorCode.code).to.equal("RoundAlreadyWon");
+    }
+    const roundAfterRival = await (program.account as any).round.fetch(roundPda);
+    expect(roundAfterRival.winner.toBase58()).to.equal(
+      player.publicKey.toBase58()
+    );
+    // The earlier wrong guesses and this win count; the rejected one doesn't
+    expect(roundAfterRival.totalGuesses).to.equal(3);
+    expect(roundAfterRival.correctGuesses).to.equal(1);
+
+    // Entering the won (and so inactive) round reports that it was won
+    const latecomer = Keypair.generate();
+    const latecomerAirdropSig = await provider.connection.requestAirdrop(
+      latecomer.publicKey,
+      2 * LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(latecomerAirdropSig);
+    const [latecomerEntryPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("player_entry"),
+        roundPda.toBuffer(),
+        Buffer.alloc(4), // round generation
+        latecomer.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    try {
+      await program.methods
+        .enterRound(ENTRY_FEE, null, [])
+        .accountsStrict({
+          gameConfig: gameConfigPda,
+          round: roundPda,
+          playerEntry: latecomerEntryPda,
+          playerProfile: profilePda(latecomer.publicKey),
+          blacklist: blacklistPda,
+          participants: null,
+          roundLeaderboard: roundLeaderboardPda,
+          leaderboard: null,
+          player: latecomer.publicKey,
+          rentSponsor: null,
+          playerTokenAccount: null,
+          vault: null,
+          gateTokenAccount: null,
+          referrerAccount: null,
+          referralStats: null,
+          tokenProgram: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([latecomer])
+        .rpc();
+      expect.fail("entering a won round should be rejected");
+    } catch (err: any) {
+      expect(err.error.errorCode.code).to.equal("RoundAlreadyWon");
+    }
+
+    const roundLeaderboard = await (program.account as any).roundLeaderboard.fetch(
+      roundLeaderboardPda
+    );
+    const winnerEntry = roundLeaderboard.entries.find(
+      (e: any) => e.player.toBase58() === player.publicKey.toBase58()
+    );
+    expect(winnerEntry.guessesToWin).to.equal(1);
+
+    // Preview the payout, then check distribution pays exactly that
+    const simulated = await program.methods
+      .previewDistribution()
+      .accountsStrict({
+        gameConfig: gameConfigPda,
+        round: roundPda,
+        winners: null,
+        vault: null,
+      })
+      .simulate();
+    const preview = simulated.events.find(
+      (e: any) => e.name === "distributionPreview"
+    )!.data as any;
+    const winnerBalanceBefore = await provider.connection.getBalance(
+      player.publicKey
+    );
+    const feeVaultBefore = await (program.account as any).feeVault.fetch(
+      feeVaultPda
+    );
+
     // Distribute pot
     const distributeTx = await program.methods
-      .distributePot()
+      .distributePot(false)
       .accountsStrict({
         gameConfig: gameConfigPda,
         round: roundPda,
         winner: player.publicKey,
-        feeReceiver: authority.publicKey,
+        winnerProfile: profilePda(player.publicKey),
+        season: seasonPda,
+        feeVault: feeVaultPda,
+        runnerUp: null,
+        charityWallet: null,
+        winners: null,
         leaderboard: leaderboardPda,
+        vault: null,
+        winnerTokenAccount: null,
+        feeReceiverTokenAccount: null,
+        runnerUpTokenAccount: null,
+        prizeVault: null,
+        authority: null,
+        payer: authority.publicKey,
+        tokenProgram: null,
+        systemProgram: SystemProgram.programId,
       })
       .rpc();
 
@@ -246,13 +1021,88 @@ describe("solpot", () => {
     expect(roundAfter.potDistributed).to.be.true;
     expect(roundAfter.potLamports.toNumber()).to.equal(0);
 
+    // Everything above rent went out
+    const roundInfo = await provider.connection.getAccountInfo(roundPda);
+    const rentExempt =
+      await provider.connection.getMinimumBalanceForRentExemption(
+        roundInfo!.data.length
+      );
+    expect(roundInfo!.lamports).to.equal(rentExempt);
+
+    // A second distribution is rejected
+    try {
+      await program.methods
+        .distributePot(false)
+        .accountsStrict({
+          gameConfig: gameConfigPda,
+          round: roundPda,
+          winner: player.publicKey,
+          winnerProfile: profilePda(player.publicKey),
+          season: seasonPda,
+          feeVault: feeVaultPda,
+          runnerUp: null,
+          charityWallet: null,
+          winners: null,
+          leaderboard: leaderboardPda,
+          vault: null,
+          winnerTokenAccount: null,
+          feeReceiverTokenAccount: null,
+          runnerUpTokenAccount: null,
+          prizeVault: null,
+          authority: null,
+          payer: authority.publicKey,
+          tokenProgram: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      expect.fail("second distribution should be rejected");
+    } catch (err: any) {
+      expect(err.error.errorCode.code).to.equal("PotAlreadyDistributed");
+    }
+    const roundInfoAfter = await provider.connection.getAccountInfo(roundPda);
+    expect(roundInfoAfter!.lamports).to.equal(rentExempt);
+
+    const profile = await (program.account as any).playerProfile.fetch(
+      profilePda(player.publicKey)
+    );
+    expect(profile.roundsEntered).to.equal(1);
+    expect(profile.guessesMade).to.equal(1);
+    expect(profile.wins).to.equal(1);
+    expect(profile.totalWagered.toNumber()).to.equal(ENTRY_FEE.toNumber());
+    expect(profile.totalWinnings.toNumber()).to.be.greaterThan(0);
+
+    const feeVault = await (program.account as any).feeVault.fetch(feeVaultPda);
+    expect(feeVault.accruedFees.toNumber()).to.be.greaterThan(0);
+    expect(
+      feeVault.accruedFees.sub(feeVaultBefore.accruedFees).toNumber()
+    ).to.equal(preview.feeAmount.toNumber());
+    expect(
+      (await provider.connection.getBalance(player.publicKey)) -
+        winnerBalanceBefore
+    ).to.equal(preview.winnerAmount.toNumber());
+
+    await program.methods
+      .sweepFees(feeVault.accruedFees)
+      .accountsStrict({
+        gameConfig: gameConfigPda,
+        feeVault: feeVaultPda,
+        destination: authority.publicKey,
+        authority: authority.publicKey,
+      })
+      .rpc();
+
+    const feeVaultAfter = await (program.account as any).feeVault.fetch(feeVaultPda);
+    expect(feeVaultAfter.accruedFees.toNumber()).to.equal(0);
+
     // Mint NFT reward using Metaplex Core
     const assetKeypair = Keypair.generate();
 
     const mintTx = await program.methods
       .mintRewardNft(
         "SolPot Winner #0",
-        "https://arweave.net/solpot-winner-0"
+        "https://arweave.net/solpot-winner-0",
+        500, // seller_fee_basis_points
+        [{ key: "round", value: "0" }]
       )
       .accountsStrict({
         gameConfig: gameConfigPda,
@@ -260,6 +1110,8 @@ describe("solpot", () => {
         asset: assetKeypair.publicKey,
         winner: player.publicKey,
         payer: authority.publicKey,
+        collection: null,
+        collectionAuthority: null,
         mplCoreProgram: MPL_CORE_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       })
@@ -271,4 +1123,231 @@ describe("solpot", () => {
     const roundFinal = await (program.account as any).round.fetch(roundPda);
     expect(roundFinal.nftMinted).to.be.true;
   });
+
+  it("Returns a tip record's rent once the pot is paid out", async () => {
+    const balanceBefore = await provider.connection.getBalance(tipper.publicKey);
+    const recordRent = (await provider.connection.getAccountInfo(tipRecordPda))!
+      .lamports;
+
+    // Nobody needs to sign: the rent can only go back to the tipper
+    await program.methods
+      .reclaimTip()
+      .accountsStrict({
+        round: roundPda,
+        tipRecord: tipRecordPda,
+        tipper: tipper.publicKey,
+      })
+      .rpc();
+
+    // The round was won, so the tip stays in the payout; only rent returns
+    expect(
+      (await provider.connection.getBalance(tipper.publicKey)) - balanceBefore
+    ).to.equal(recordRent);
+    expect(await provider.connection.getAccountInfo(tipRecordPda)).to.be.null;
+    const round = await (program.account as any).round.fetch(roundPda);
+    expect(round.liveTips).to.equal(0);
+    expect(round.refundedTips.toNumber()).to.equal(0);
+  });
+
+  it("Refunds tips when a refund_tips round is cancelled", async () => {
+    const gameConfig = await (program.account as any).gameConfig.fetch(
+      gameConfigPda
+    );
+    const [refundRoundPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("round"),
+        gameConfigPda.toBuffer(),
+        gameConfig.roundCount.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const [refundGuessLogPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("guess_log"), refundRoundPda.toBuffer()],
+      program.programId
+    );
+    const [refundRoundLeaderboardPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("round_leaderboard"), refundRoundPda.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .createRound({
+        wordHash: Array.from(WORD_HASH) as number[],
+        maxPlayers: 10,
+        durationSeconds: new anchor.BN(3600), // 1 hour duration
+        allowMultipleWinners: false,
+        maxGuesses: 3,
+        guessFeeLamports: new anchor.BN(0),
+        revealWindowSeconds: new anchor.BN(0), // plain guessing
+        answersMerkleRoot: new Array(32).fill(0), // single answer
+        minPlayers: 0,
+        randomFallback: false,
+        guessLogOverwrite: false,
+        gateMint: null,
+        wordLength: SECRET_WORD.length,
+        hintUri: "",
+        claimWindowSeconds: new anchor.BN(0), // prize never lapses
+        answerCommitment: new Array(32).fill(0), // none
+        feeIncrementLamports: new anchor.BN(0), // flat fee
+        maxEntryFeeLamports: new anchor.BN(0),
+        guessCooldownSeconds: new anchor.BN(0), // no cooldown
+        normalizationFlags: 0, // lowercase only
+        milestoneLamports: new anchor.BN(0), // no milestones
+        vestingDurationSeconds: new anchor.BN(0), // instant payout
+        vestingCliffSeconds: new anchor.BN(0),
+        allowlistRoot: new Array(32).fill(0), // open round
+        seedPot: new anchor.BN(0),
+        dictionaryRoot: new Array(32).fill(0), // any word
+        refundTips: true, // tips go back on a no-winner close
+        feeBasisPointsOverride: null, // game default
+        maxPotLamports: new anchor.BN(0), // uncapped
+        commitSlot: new anchor.BN(0), // no fairness proof
+        guessKind: 0, // string
+        graceSeconds: new anchor.BN(0), // guessing closes at expiry
+        antiSnipeSeconds: new anchor.BN(0), // no anti-sniping
+        maxAntiSnipeSeconds: new anchor.BN(0),
+        charityWallet: null, // not a fundraiser
+        charityBasisPoints: 0,
+        nonce: Array.from(randomBytes(16)), // retry key
+        sealed: false, // hint public from the start
+        startsAt: new anchor.BN(0), // open immediately
+      })
+      .accountsStrict({
+        gameConfig: gameConfigPda,
+        round: refundRoundPda,
+        winners: null,
+        participants: null,
+        prizeVault: null,
+        guessLog: refundGuessLogPda,
+        roundLeaderboard: refundRoundLeaderboardPda,
+        mint: null,
+        vault: null,
+        authority: authority.publicKey,
+        tokenProgram: null,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const tips: [Keypair, PublicKey, anchor.BN][] = [];
+    for (const amount of [0.02, 0.01]) {
+      const spectator = Keypair.generate();
+      const airdropSig = await provider.connection.requestAirdrop(
+        spectator.publicKey,
+        LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdropSig);
+      const [recordPda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("tip"),
+          refundRoundPda.toBuffer(),
+          Buffer.alloc(4), // round generation
+          spectator.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      const tip = new anchor.BN(amount * LAMPORTS_PER_SOL);
+      await program.methods
+        .tipRound(tip)
+        .accountsStrict({
+          gameConfig: gameConfigPda,
+          round: refundRoundPda,
+          tipRecord: recordPda,
+          tipper: spectator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([spectator])
+        .rpc();
+      tips.push([spectator, recordPda, tip]);
+    }
+
+    await program.methods
+      .cancelRound()
+      .accountsStrict({
+        gameConfig: gameConfigPda,
+        round: refundRoundPda,
+        authority: authority.publicKey,
+      })
+      .rpc();
+
+    const closeRound = () =>
+      program.methods
+        .closeRound()
+        .accountsStrict({
+          gameConfig: gameConfigPda,
+          round: refundRoundPda,
+          feeVault: feeVaultPda,
+          gameAuthority: authority.publicKey,
+          authority: authority.publicKey,
+        })
+        .rpc();
+
+    // The tip records would be stranded if the round closed first
+    try {
+      await closeRound();
+      expect.fail("closing with tip records open should be rejected");
+    } catch (err: any) {
+      expect(err.error.errorCode.code).to.equal("TipsNotReclaimed");
+    }
+
+    for (const [spectator, recordPda, tip] of tips) {
+      const balanceBefore = await provider.connection.getBalance(
+        spectator.publicKey
+      );
+      const recordRent = (await provider.connection.getAccountInfo(recordPda))!
+        .lamports;
+      await program.methods
+        .reclaimTip()
+        .accountsStrict({
+          round: refundRoundPda,
+          tipRecord: recordPda,
+          tipper: spectator.publicKey,
+        })
+        .rpc();
+      expect(
+        (await provider.connection.getBalance(spectator.publicKey)) -
+          balanceBefore
+      ).to.equal(tip.toNumber() + recordRent);
+    }
+
+    const round = await (program.account as any).round.fetch(refundRoundPda);
+    expect(round.refundedTips.toNumber()).to.equal(round.totalTips.toNumber());
+    expect(round.potLamports.toNumber()).to.equal(0);
+    expect(round.liveTips).to.equal(0);
+
+    await closeRound();
+    expect(await provider.connection.getAccountInfo(refundRoundPda)).to.be.null;
+  });
+
+  it("Ends the season and pays its top scorer", async () => {
+    const player = roundWinner;
+    const season = await (program.account as any).season.fetch(seasonPda);
+    expect(season.entries.length).to.equal(1);
+    expect(season.entries[0].player.toBase58()).to.equal(
+      player.publicKey.toBase58()
+    );
+    expect(season.entries[0].wins).to.equal(1);
+
+    const balanceBefore = await provider.connection.getBalance(
+      player.publicKey
+    );
+    await program.methods
+      .endSeason()
+      .accountsStrict({
+        gameConfig: gameConfigPda,
+        season: seasonPda,
+        authority: authority.publicKey,
+      })
+      .remainingAccounts([
+        { pubkey: player.publicKey, isWritable: true, isSigner: false },
+      ])
+      .rpc();
+
+    expect(
+      (await provider.connection.getBalance(player.publicKey)) - balanceBefore
+    ).to.equal(SEASON_PRIZE.toNumber());
+    const ended = await (program.account as any).season.fetch(seasonPda);
+    expect(ended.isActive).to.be.false;
+    expect(ended.prizePool.toNumber()).to.equal(0);
+    expect(ended.entries.length).to.equal(0);
+  });
 });